@@ -0,0 +1,37 @@
+// SafeCurves-style audit of the named curves and of an anomalous one.
+// `cargo run --release --example audit --features serde` prints the reports as JSON as well.
+use std::rc::Rc;
+
+use crypto_bigint::U512;
+use hw_13::{curves, EllipticCurve};
+
+fn main() {
+    for curve in curves::all() {
+        let report = curve.audit();
+        println!("{report}");
+        #[cfg(feature = "serde")]
+        println!("{}", serde_json::to_string_pretty(&report).unwrap());
+
+        match curve.name.as_str() {
+            // j = 0 gives the smallest discriminant possible
+            "Secp256k1" | "secp160k1" | "secp192k1" | "secp224k1" => {
+                assert!(report.cm_discriminant_exact && report.cm_discriminant_bits < 2.)
+            }
+            // pairing-friendly by design
+            "BN254" | "BLS12-381" => assert_eq!(report.embedding_degree, Some(12)),
+            _ => assert!(report.is_safe()),
+        }
+        assert_eq!(report.smart_attack, None);
+    }
+
+    // a curve with exactly `p` points
+    let p = U512::from(10007u16);
+    let anomalous = Rc::new(
+        EllipticCurve::try_new_anomalous("anomalous".to_string(), p, p, [0, 22, 9]).unwrap(),
+    );
+    assert_eq!(anomalous.count_points().unwrap(), p);
+    let report = anomalous.audit();
+    println!("{report}");
+    assert!(report.anomalous && !report.is_safe());
+    assert_eq!(report.smart_attack, Some(true));
+}
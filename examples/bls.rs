@@ -0,0 +1,102 @@
+// BLS signatures on BLS12-381: signing, aggregation, and the proofs of possession which make one message signed by many
+// verify against the sum of the keys
+use std::time::Instant;
+
+use hw_13::{
+    bls::{Bls, PublicKey, Scheme, Signature},
+    ProtocolError,
+};
+
+fn main() {
+    let basic = Bls::new(Scheme::Basic);
+    let keys: Vec<_> = (1..=3u8)
+        .map(|i| basic.key_gen(&[i; 32], b"").unwrap())
+        .collect();
+    assert_eq!(
+        basic.key_gen(&[0; 31], b"").err(),
+        Some(ProtocolError::ShortKeyMaterial { length: 31 })
+    );
+    // KeyGen is deterministic, and key_info separates the keys of one seed
+    assert_eq!(basic.key_gen(&[1; 32], b"").unwrap(), keys[0]);
+    assert_ne!(basic.key_gen(&[1; 32], b"info").unwrap(), keys[0]);
+    assert_eq!(format!("{:?}", keys[0]), "SecretKey(..)");
+    let public: Vec<_> = keys.iter().map(|key| basic.public_key(key)).collect();
+    public
+        .iter()
+        .for_each(|key| basic.key_validate(key).unwrap());
+
+    let start = Instant::now();
+    let signature = basic.sign(&keys[0], b"message");
+    basic.verify(&public[0], b"message", &signature).unwrap();
+    println!("sign and verify in {:.1?}", start.elapsed());
+    assert_eq!(
+        basic.verify(&public[0], b"massage", &signature),
+        Err(ProtocolError::InvalidSignature)
+    );
+    assert_eq!(
+        basic.verify(&public[1], b"message", &signature),
+        Err(ProtocolError::InvalidSignature)
+    );
+    let identity = PublicKey(public[0].0.curve().infinity());
+    assert_eq!(
+        basic.verify(&identity, b"message", &signature),
+        Err(ProtocolError::Identity { role: "public key" })
+    );
+
+    // three messages under three keys in one signature
+    let messages: [&[u8]; 3] = [b"first", b"second", b"third"];
+    let signatures: Vec<_> = keys
+        .iter()
+        .zip(messages)
+        .map(|(key, message)| basic.sign(key, message))
+        .collect();
+    let aggregate = basic.aggregate(&signatures).unwrap();
+    let signed: Vec<_> = public.iter().zip(messages).collect();
+    let start = Instant::now();
+    basic.aggregate_verify(&signed, &aggregate).unwrap();
+    println!("aggregate of 3 verified in {:.1?}", start.elapsed());
+    let swapped = [
+        (&public[0], messages[1]),
+        (&public[1], messages[0]),
+        signed[2],
+    ];
+    assert!(basic.aggregate_verify(&swapped, &aggregate).is_err());
+    let repeated = [(&public[0], messages[0]), (&public[1], messages[0])];
+    assert_eq!(
+        basic.aggregate_verify(&repeated, &aggregate),
+        Err(ProtocolError::DuplicateMessage)
+    );
+    assert!(basic.aggregate(&[]).is_err());
+    assert_eq!(
+        basic.fast_aggregate_verify(&[&public[0]], b"message", &signature),
+        Err(ProtocolError::Unsupported {
+            operation: "fast aggregate verification"
+        })
+    );
+
+    // proofs of possession
+    let pop = Bls::new(Scheme::ProofOfPossession);
+    for (key, public) in keys.iter().zip(&public) {
+        let proof = pop.prove_possession(key).unwrap();
+        pop.verify_possession(public, &proof).unwrap();
+    }
+    let proof = pop.prove_possession(&keys[0]).unwrap();
+    assert!(pop.verify_possession(&public[1], &proof).is_err());
+    // a proof isn't a signature of anything a key would sign
+    assert_ne!(proof, pop.sign(&keys[0], b"message"));
+
+    let signatures: Vec<Signature> = keys.iter().map(|key| pop.sign(key, b"block 42")).collect();
+    let aggregate = pop.aggregate(&signatures).unwrap();
+    let all: Vec<_> = public.iter().collect();
+    let start = Instant::now();
+    pop.fast_aggregate_verify(&all, b"block 42", &aggregate)
+        .unwrap();
+    println!("fast aggregate of 3 verified in {:.1?}", start.elapsed());
+    assert!(pop
+        .fast_aggregate_verify(&all[..2], b"block 42", &aggregate)
+        .is_err());
+    // the schemes don't share signatures
+    assert!(basic
+        .verify(&public[0], b"block 42", &signatures[0])
+        .is_err());
+}
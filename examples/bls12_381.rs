@@ -0,0 +1,78 @@
+// BLS12-381: the generators, the endomorphism psi, bilinearity of the optimal ate pairing and hashing into G1 and G2
+use std::time::Instant;
+
+use crypto_bigint::{rand_core::OsRng, NonZero, RandomMod, U512};
+use hw_13::{
+    bls12_381::{expand_message_xmd, Bls12_381, X},
+    extension::Field,
+};
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+fn main() {
+    // the vectors of RFC 9380 appendix K.1
+    let dst = b"QUUX-V01-CS02-with-expander-SHA256-128";
+    assert_eq!(
+        hex(&expand_message_xmd(b"", dst, 0x20)),
+        "68a985b87eb6b46952128911f2a4412bbc302a9d759667f87f7a21d803f07235"
+    );
+    assert_eq!(
+        hex(&expand_message_xmd(b"abc", dst, 0x20)),
+        "d8ccab23b5985ccea865c6c97b6e5b8350e794e603b4b97902f53a8a0d605615"
+    );
+
+    let start = Instant::now();
+    let curve = Bls12_381::new();
+    println!("set up in {:.1?}", start.elapsed());
+    let r = *curve.order();
+    let (g1, g2) = (curve.g1(), curve.g2());
+    assert!(curve.is_in_g1(g1) && curve.is_in_g2(g2));
+    assert!(!g1.is_infinity() && !g2.is_infinity());
+
+    // psi acts on G2 as the multiplication by x = -X
+    assert_eq!(curve.psi(g2), -&(g2 * U512::from_u64(X)));
+
+    let start = Instant::now();
+    let e = curve.pairing(g1, g2).unwrap();
+    println!("pairing in {:.1?}", start.elapsed());
+    let one = e.one_like();
+    assert_ne!(e, one);
+    assert_eq!(e.pow(&r), one);
+    let random = || U512::random_mod(&mut OsRng, &NonZero::new(r).unwrap());
+    for _ in 0..3 {
+        let (a, b) = (random(), random());
+        let ab = a.mul_wide(&b);
+        let ab = U512::const_rem_wide(ab, &r).0;
+        assert_eq!(curve.pairing(&(g1 * a), &(g2 * b)).unwrap(), e.pow(&ab));
+    }
+    // e(a.P, Q).e(-P, a.Q) = 1 with one final exponentiation
+    let a = random();
+    assert_eq!(
+        curve
+            .multi_pairing(&[(&(g1 * a), g2), (&-g1, &(g2 * a))])
+            .unwrap(),
+        one
+    );
+    assert_eq!(curve.pairing(&g1.curve().infinity(), g2).unwrap(), one);
+    println!("bilinear");
+
+    // the hashes land in the subgroups, and differ with the message and with the domain; the tests check them against
+    // the vectors of RFC 9380
+    let dst1 = b"QUUX-V01-CS02-with-BLS12381G1_XMD:SHA-256_SSWU_RO_";
+    let dst2 = b"QUUX-V01-CS02-with-BLS12381G2_XMD:SHA-256_SSWU_RO_";
+    let start = Instant::now();
+    for message in [&b""[..], b"abc", b"abcdef0123456789"] {
+        let (h1, h2) = (
+            curve.hash_to_g1(message, dst1),
+            curve.hash_to_g2(message, dst2),
+        );
+        assert!(curve.is_in_g1(&h1) && !h1.is_infinity());
+        assert!(curve.is_in_g2(&h2) && !h2.is_infinity());
+        assert_eq!(curve.hash_to_g2(message, dst2), h2);
+        assert_ne!(curve.hash_to_g2(message, dst1), h2);
+        println!("hash_to_g1({:?}) = {h1}", String::from_utf8_lossy(message));
+    }
+    println!("6 hashes in {:.1?}", start.elapsed());
+}
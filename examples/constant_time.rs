@@ -0,0 +1,69 @@
+// Comparing, selecting and negating points without branching on the secret-dependent values.
+use std::rc::Rc;
+
+use crypto_bigint::{
+    subtle::{Choice, ConditionallyNegatable, ConditionallySelectable, ConstantTimeEq},
+    U512,
+};
+use hw_13::{curves, CtPoint, Point, PointError};
+
+fn main() {
+    let curve = Rc::new(curves::secp256k1());
+    let g = curve.generator().unwrap();
+    let double = (&g + &g).unwrap();
+    let infinity = Point::new(Rc::clone(&curve), None, None).unwrap();
+
+    assert!(bool::from(g.ct_eq(&g)));
+    assert!(!bool::from(g.ct_eq(&double)));
+    assert!(!bool::from(g.ct_eq(&infinity)));
+    assert!(bool::from(infinity.ct_eq(&(&g + &-&g).unwrap())));
+    assert!(bool::from(infinity.is_identity()) && !bool::from(g.is_identity()));
+    // the points of a different curve are never equal
+    let other = Rc::new(curves::m511());
+    assert!(!bool::from(
+        Point::new(Rc::clone(&other), None, None)
+            .unwrap()
+            .ct_eq(&infinity)
+    ));
+
+    // a double-and-add ladder which adds on every step and keeps the sum only for the set bits
+    let scalar = U512::from(0xc0ffee_u32);
+    let mut result = infinity.clone();
+    for bit in (0..scalar.bits()).rev() {
+        result = (&result + &result).unwrap();
+        let sum = (&result + &g).unwrap();
+        result
+            .conditional_assign(&sum, Choice::from(scalar.bit_vartime(bit) as u8))
+            .unwrap();
+    }
+    assert!(result == (&g * scalar).unwrap());
+
+    let mut negated = g.clone();
+    negated.conditional_negate(Choice::from(0));
+    assert!(negated == g);
+    negated.conditional_negate(Choice::from(1));
+    assert!(negated == -&g);
+    let mut at_infinity = infinity.clone();
+    at_infinity.conditional_negate(Choice::from(1));
+    assert!(at_infinity == infinity);
+
+    // `CtPoint` is `Copy`, so the selection itself doesn't even look at the variant
+    let table = [infinity.to_ct(), g.to_ct(), double.to_ct()];
+    let mut picked = table[0];
+    for (i, entry) in table.iter().enumerate() {
+        picked.conditional_assign(entry, (i as u8).ct_eq(&2));
+    }
+    picked.conditional_negate(Choice::from(1));
+    assert!(Point::from_ct(picked, &curve).unwrap() == -&double);
+    let mut lost = CtPoint::conditional_select(&table[1], &table[0], Choice::from(0));
+    lost.conditional_negate(Choice::from(0));
+    assert!(Point::from_ct(lost, &other).is_err());
+
+    let selected = Point::conditional_select(&g, &infinity, Choice::from(1)).unwrap();
+    assert!(bool::from(selected.is_identity()));
+    assert!(matches!(
+        Point::conditional_select(&g, &other.generator().unwrap(), Choice::from(0)),
+        Err(PointError::DifferentCurves { .. })
+    ));
+    println!("constant-time operations agree with the usual ones");
+}
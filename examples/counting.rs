@@ -0,0 +1,46 @@
+// Counting points of hand-made teaching curves, cross-checking the methods against each other.
+use std::rc::Rc;
+
+use crypto_bigint::U512;
+use hw_13::EllipticCurve;
+
+fn main() {
+    // y^2 = x^3 + 2x + 3 over a few fields: tiny (Legendre symbols), small (Mestre, still enumerable) and the largest supported one
+    for p in [10007u64, 100003, (1 << 40) - 87] {
+        // the order is unknown before counting, so the first curve is only a vehicle for it
+        let draft = Rc::new(EllipticCurve::new(
+            format!("teaching curve over {p}"),
+            U512::from(3u8),
+            U512::from(p),
+            [0, 2, 3],
+        ));
+        let count = draft.count_points().unwrap();
+        println!("#E(F_{p}) = {}", count.as_words()[0]);
+
+        if p < 1 << 20 {
+            assert_eq!(U512::from(draft.points().count() as u64), count);
+        }
+
+        let curve = Rc::new(EllipticCurve::new(
+            draft.name.clone(),
+            count,
+            U512::from(p),
+            [0, 2, 3],
+        ));
+        curve.verify_order().unwrap();
+        // every point is annihilated by the group order
+        assert!(curve
+            .points()
+            .take(64)
+            .all(|point| (&point * count).unwrap().is_infinity()));
+    }
+
+    // a wrong order is caught
+    let wrong = Rc::new(EllipticCurve::new(
+        "teaching curve with a wrong order".to_string(),
+        U512::from(10009u64),
+        U512::from(10007u64),
+        [0, 2, 3],
+    ));
+    println!("{}", wrong.verify_order().is_err());
+}
@@ -0,0 +1,73 @@
+// Curves and points read back from what `Display` prints.
+use std::rc::Rc;
+
+use crypto_bigint::U512;
+use hw_13::{curves, EllipticCurve, EncodingError, Point, PointError};
+
+fn main() {
+    for curve in curves::all() {
+        let parsed: EllipticCurve = curve.to_string().parse().unwrap();
+        assert!(parsed == curve && parsed.get() == curve.get());
+
+        let curve = Rc::new(curve);
+        let g = curve.generator().unwrap();
+        let infinity = Point::new(Rc::clone(&curve), None, None).unwrap();
+        for point in [g.clone(), (&g * U512::from(12345u16)).unwrap(), infinity] {
+            let parsed: Point = point.to_string().parse().unwrap();
+            assert!(parsed == point);
+        }
+    }
+    println!("the named curves and their points are read back");
+
+    // negative coefficients are printed with a minus sign
+    let p = U512::from(10007u16);
+    let curve = Rc::new(EllipticCurve::new(
+        "E".to_string(),
+        U512::from(5003u16),
+        p,
+        [0, -3, 3],
+    ));
+    let parsed: EllipticCurve = curve.to_string().parse().unwrap();
+    assert_eq!(parsed.get(), curve.get());
+    // points of a curve outside the registry need the curve at hand
+    let point = curve.points().nth(7).unwrap();
+    assert!(matches!(
+        point.to_string().parse::<Point>(),
+        Err(EncodingError::UnknownCurve { .. })
+    ));
+    assert!(Point::parse_on(&curve, &point.to_string()).unwrap() == point);
+
+    // the way the source prints them: trimmed numbers with `0x`, and a minus sign
+    let text = "< Elliptic Curve Object >
+-------------------------
+    name: E
+    order: 0x138b
+    a2: 0x0
+    a4: -0x3
+    a6: 0x3
+    p: 0x2717
+    equation: y^2 = x^3 + a2.x^2 + a4.x + a6 (mod p)";
+    assert!(text.parse::<EllipticCurve>().unwrap() == *curve);
+
+    // and everything is validated
+    assert!(matches!(
+        text.replace("a6: 0x3", "a6: 0x2").parse::<EllipticCurve>(),
+        Err(EncodingError::Curve(_))
+    ));
+    assert!(matches!(
+        text.replace("    p: 0x2717\n", "").parse::<EllipticCurve>(),
+        Err(EncodingError::Malformed { .. })
+    ));
+    let g = curves::secp256k1().to_string();
+    let off_curve = Rc::new(curves::secp256k1())
+        .generator()
+        .unwrap()
+        .to_string()
+        .replace("b8\n", "b9\n");
+    assert!(matches!(
+        off_curve.parse::<Point>(),
+        Err(EncodingError::Point(PointError::NotOnCurve { .. }))
+    ));
+    assert!(g.parse::<Point>().is_err());
+    println!("malformed texts are refused");
+}
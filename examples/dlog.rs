@@ -0,0 +1,79 @@
+// Discrete logarithms with baby-step giant-step: a whole small group, a bounded range on secp256k1, and decrypting
+// exponential ElGamal.
+use std::{rc::Rc, time::Instant};
+
+use crypto_bigint::{rand_core::OsRng, NonZero, RandomMod, U512};
+use hw_13::{curves, dlog, EllipticCurve, Point, PointError};
+
+fn random_below(bound: U512) -> U512 {
+    U512::random_mod(&mut OsRng, &NonZero::new(bound).unwrap())
+}
+
+fn main() {
+    // any logarithm in the subgroup of order 5003
+    let p = U512::from(10007u16);
+    let order = U512::from(5003u16);
+    let curve = Rc::new(EllipticCurve::new("E".to_string(), order, p, [0, 2, 3]));
+    let base = curve
+        .points()
+        .map(|point| (&point * U512::from(2u8)).unwrap())
+        .find(|point| !point.is_infinity())
+        .unwrap();
+    for _ in 0..20 {
+        let k = random_below(order);
+        let target = (&base * k).unwrap();
+        assert_eq!(dlog::bsgs(&base, &target, order).unwrap(), Some(k));
+    }
+    // with a bound far beyond the order the baby steps go round the whole subgroup, and 4000.P = -1003.P is 4000
+    let k = U512::from(4000u16);
+    assert_eq!(
+        dlog::bsgs(&base, &(&base * k).unwrap(), U512::from(50_000_000u32)).unwrap(),
+        Some(k)
+    );
+    println!("logarithms in {} are found", curve.name);
+
+    // a 32-bit exponent on secp256k1
+    let secp256k1 = Rc::new(curves::secp256k1());
+    let g = secp256k1.generator().unwrap();
+    let bound = U512::ONE.shl_vartime(32);
+    let k = random_below(bound);
+    let target = (&g * k).unwrap();
+    let start = Instant::now();
+    assert_eq!(dlog::bsgs(&g, &target, bound).unwrap(), Some(k));
+    println!(
+        "a 32-bit logarithm on {} in {:.1?}",
+        secp256k1.name,
+        start.elapsed()
+    );
+    // but not when it's out of the range
+    let k = bound.wrapping_add(&U512::from(7u8));
+    assert_eq!(dlog::bsgs(&g, &(&g * k).unwrap(), bound).unwrap(), None);
+
+    // exponential ElGamal: the messages are in the exponent, so the ciphertexts add up and decrypting takes a logarithm
+    let secret = random_below(*secp256k1.order());
+    let public = (&g * secret).unwrap();
+    let encrypt = |message: u32| {
+        let r = random_below(*secp256k1.order());
+        (
+            (&g * r).unwrap(),
+            (&(&g * U512::from(message)).unwrap() + &(&public * r).unwrap()).unwrap(),
+        )
+    };
+    let votes = [1, 0, 1, 1, 0, 1, 1];
+    let tally = votes.iter().map(|&vote| encrypt(vote)).fold(
+        (
+            Point::new(Rc::clone(&secp256k1), None, None).unwrap(),
+            Point::new(Rc::clone(&secp256k1), None, None).unwrap(),
+        ),
+        |(a, b), (c1, c2)| ((&a + &c1).unwrap(), (&b + &c2).unwrap()),
+    );
+    let message_point = (&tally.1 - &(&tally.0 * secret).unwrap()).unwrap();
+    let total = dlog::bsgs(&g, &message_point, U512::from(1u32 << 20)).unwrap();
+    assert_eq!(total, Some(U512::from(votes.iter().sum::<u32>())));
+    println!("the encrypted tally is {}", total.unwrap().as_words()[0]);
+
+    assert!(matches!(
+        dlog::bsgs(&base, &g, order),
+        Err(PointError::DifferentCurves { .. })
+    ));
+}
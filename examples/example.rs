@@ -80,6 +80,8 @@ fn main() {
         /* "(GM511 * nM511)  */ "{}",
         (&GM511 * nM511).unwrap() == Z
     );
+    // the same check as above, but letting the point find its own order
+    println!("{}", GM511.order(&[(nM511, 1)]).unwrap() == nM511);
     println!("{}", (&P * nM511).unwrap() == Z);
     println!("{}", (nM511 * &Q).unwrap() == Z);
     println!("{}", (&Z * U512::from(265321u32)).unwrap() == Z);
@@ -0,0 +1,103 @@
+// Arithmetic in extension fields and the full 139-torsion of a supersingular curve, which only exists over F_{p^2}
+use std::rc::Rc;
+
+use crypto_bigint::{
+    modular::runtime_mod::{DynResidue, DynResidueParams},
+    U512,
+};
+use hw_13::{
+    extension::{Field, Fp12, Fp2, Fp6, Fpk},
+    EllipticCurve, FieldError,
+};
+
+/// The axioms worth checking on random elements of the field of `one`
+fn check_field<F: Field>(one: &F) {
+    for _ in 0..5 {
+        let (a, b, c) = (one.random(), one.random(), one.random());
+        assert_eq!(a * (b + c), a * b + a * c);
+        assert_eq!((a * b) * c, a * (b * c));
+        assert_eq!(a + -a, one.zero_like());
+        assert_eq!(a * a.inverse().unwrap(), *one);
+        assert_eq!(a.pow(&a.size()), a);
+        assert_eq!(a.frobenius(), a.pow(&a.characteristic()));
+        assert_eq!(a.square().sqrt().map(|r| r == a || r == -a), Some(true));
+    }
+    assert_eq!(one.zero_like().inverse(), None);
+}
+
+fn main() {
+    let p = U512::from(10007u16);
+    let params = DynResidueParams::new(&p);
+    let fp = |n: u64| DynResidue::new(&U512::from(n), params);
+
+    // p = 3 mod 4, so -1 is a non-square
+    assert_eq!(
+        Fp2::one(fp(1)).err(),
+        Some(FieldError::Reducible { degree: 2 })
+    );
+    let one2 = Fp2::one(-fp(1)).unwrap();
+    check_field(&one2);
+    println!("F_p^2 ok");
+
+    // the tower of the pairings, on the first xi = u + k which isn't a cube
+    let one6 = (1..)
+        .find_map(|k| Fp6::one(one2.from_coefficients(fp(k), fp(1))).ok())
+        .unwrap();
+    check_field(&one6);
+    let one12 = Fp12::one(&one6).unwrap();
+    check_field(&one12);
+    println!("F_p^6 over {}, F_p^12 ok", one6.xi());
+
+    // p = 2 mod 3 makes every element a cube, so x^3 - 2 is reducible; x^3 + x + c is irreducible for some c
+    assert!(Fpk::<3>::one([-fp(2), fp(0), fp(0)]).is_err());
+    let one3 = (1..)
+        .find_map(|c| Fpk::<3>::one([fp(c), fp(1), fp(0)]).ok())
+        .unwrap();
+    check_field(&one3);
+    println!(
+        "F_p^3 = F_p[x] / (x^3 + x + {}) ok",
+        one3.modulus()[0].retrieve().as_words()[0]
+    );
+
+    // y^2 = x^3 + x has p + 1 = 2^3.3^2.139 points over F_p and (p + 1)^2 over F_p^2, where E[139] is Z_139^2
+    let r = U512::from(139u8);
+    let curve = Rc::new(EllipticCurve::new(
+        "y^2 = x^3 + x".to_string(),
+        r,
+        p,
+        [0, 1, 0],
+    ));
+    let base = curve
+        .points()
+        .find(|point| !point.is_infinity() && !(point * U512::from(72u8)).unwrap().is_infinity());
+    let base = (&base.unwrap() * U512::from(72u8)).unwrap();
+    let over = Rc::new(curve.over(&one2).unwrap());
+    let p_base = base.over(&over).unwrap();
+    assert!((&p_base * r).is_infinity());
+    assert_eq!(p_base.frobenius(), p_base);
+
+    let cofactor = U512::from(10008u32 * 10008 / 139 / 139);
+    let q_base = loop {
+        let q = &over.random_point() * cofactor;
+        if !q.is_infinity() && q.frobenius() != q {
+            break q;
+        }
+    };
+    assert!(over.contains(&q_base));
+    assert!((&q_base * r).is_infinity());
+    // Q is no multiple of P as the multiples of P are fixed by the Frobenius, so P and Q span E[139]
+    let sum = (&p_base + &q_base).unwrap();
+    assert!((&sum * r).is_infinity());
+    assert_eq!((&sum - &q_base).unwrap(), p_base);
+    println!("E[139] = <P> + <Q> with\nP = {p_base}\nQ = {q_base}");
+
+    // the characteristic must match
+    let other = Fp2::one(-DynResidue::one(DynResidueParams::new(&U512::from(
+        10039u16,
+    ))))
+    .unwrap();
+    assert!(matches!(
+        curve.over(&other),
+        Err(FieldError::CharacteristicMismatch { .. })
+    ));
+}
@@ -0,0 +1,46 @@
+// Curves of prime order generated from seeds, and the base points of the named curves.
+use std::rc::Rc;
+
+use crypto_bigint::U512;
+use hw_13::{curves, CurveGenerator};
+
+fn main() {
+    // the base points of the registry are on their curves and of the declared order
+    for curve in curves::all() {
+        let curve = Rc::new(curve);
+        let generator = curve.generator().unwrap();
+        assert!(curve.contains(&generator));
+        assert!((&generator * *curve.order()).unwrap().is_infinity());
+        println!("{}: the base point is fine", curve.name);
+    }
+
+    // naive counting does the job on a small field
+    let p = U512::from((1u64 << 40) - 87);
+    let generator = CurveGenerator::new(p);
+    let (curve, seed) = generator.generate().unwrap();
+    let curve = Rc::new(curve);
+    assert_eq!(*curve.order(), curve.count_points().unwrap());
+    assert!(generator.verify(&curve, &seed));
+    println!("{curve}");
+
+    // Schoof's algorithm with early abort on a bigger one, allowing the cofactor of Montgomery curves
+    let p = U512::from((1u64 << 48) - 59);
+    let generator = CurveGenerator::new(p).with_max_cofactor(4);
+    let (curve, seed) = generator.generate_from_seed([7; 32]).unwrap();
+    let curve = Rc::new(curve);
+    assert!(generator.verify(&curve, &seed));
+    assert_eq!(
+        curve.compute_order(),
+        curve.order().wrapping_mul(&curve.cofactor().unwrap())
+    );
+    // the same seed gives the same curve, and a different one doesn't verify
+    let (again, _) = generator.generate_from_seed([7; 32]).unwrap();
+    assert!(*curve == again);
+    let mut other = seed;
+    other[0] ^= 1;
+    assert!(!generator.verify(&curve, &other));
+    println!(
+        "{curve}\ncofactor: {}",
+        curve.cofactor().unwrap().as_words()[0]
+    );
+}
@@ -0,0 +1,213 @@
+// Vélu's isogenies on the supersingular curves over F_419, and the toy CSIDH of the paper: the class group action by
+// the steps of degree 3, 5 and 7 commutes, so two parties walking their secret paths from each other's curves meet
+use std::{collections::BTreeSet, rc::Rc};
+
+use crypto_bigint::{
+    modular::runtime_mod::{DynResidue, DynResidueParams},
+    U512,
+};
+use hw_13::{EllipticCurve, Isogeny, Point, PointError};
+
+const P: u64 = 419;
+// p + 1 = 4.3.5.7 points on every supersingular curve over F_p
+const ORDER: u64 = 420;
+
+/// The j-invariant, which tells the curves apart up to isomorphism over the algebraic closure
+fn j(curve: &EllipticCurve) -> U512 {
+    curve.j_invariant().retrieve()
+}
+
+/// y^2 = x^3 - a2.x^2 + a4.x - a6, the twist by the non-square -1
+fn twist(curve: &EllipticCurve) -> Rc<EllipticCurve> {
+    Rc::new(EllipticCurve::from_uint_coefficients(
+        format!("{}^t", curve.name),
+        U512::from(ORDER),
+        U512::from(P),
+        [
+            (-*curve.a2()).retrieve(),
+            curve.a4().retrieve(),
+            (-*curve.a6()).retrieve(),
+        ],
+    ))
+}
+
+/// A point of order `l` of E(F_p), which spans all of E(F_p)[l] for the odd `l`
+fn kernel_point(curve: &Rc<EllipticCurve>, l: u64) -> Point {
+    curve
+        .points()
+        .map(|point| (&point * U512::from(ORDER / l)).unwrap())
+        .find(|point| !point.is_infinity())
+        .unwrap()
+}
+
+/// The action of l_1^e_1...l_k^e_k, l being the ideal (l, pi - 1) with its kernels in E(F_p), and its inverse the one
+/// with the kernels in E(F_p^2) of `x` in F_p, whose isogenies are the ones of the twist
+fn act(curve: &Rc<EllipticCurve>, exponents: &[(u64, i32)]) -> Rc<EllipticCurve> {
+    let mut curve = Rc::clone(curve);
+    for &(l, e) in exponents {
+        for _ in 0..e.unsigned_abs() {
+            let start = if e < 0 { twist(&curve) } else { curve };
+            let isogeny = Isogeny::from_generator(&kernel_point(&start, l)).unwrap();
+            assert_eq!(isogeny.degree(), l);
+            let end = Rc::clone(isogeny.codomain());
+            curve = if e < 0 { twist(&end) } else { end };
+        }
+    }
+    curve
+}
+
+fn evaluate(
+    coefficients: &[DynResidue<{ U512::LIMBS }>],
+    x: &DynResidue<{ U512::LIMBS }>,
+) -> DynResidue<{ U512::LIMBS }> {
+    coefficients
+        .iter()
+        .rev()
+        .fold(DynResidue::zero(*x.params()), |value, c| value * x + c)
+}
+
+fn main() {
+    let e0 = Rc::new(EllipticCurve::new(
+        "y^2 = x^3 + x".to_string(),
+        U512::from(ORDER),
+        U512::from(P),
+        [0, 1, 0],
+    ));
+    let params = DynResidueParams::new(&U512::from(P));
+    assert_eq!(j(&e0), U512::from(1728 % P));
+    let all: Vec<_> = e0.points().collect();
+    assert_eq!(all.len() as u64, ORDER);
+
+    // the kernel {O, (0, 0)} gives y^2 = x^3 - 4x
+    let origin = Point::new(Rc::clone(&e0), Some(U512::ZERO), Some(U512::ZERO)).unwrap();
+    let two = Isogeny::new(&e0, &[origin]).unwrap();
+    assert_eq!(two.degree(), 2);
+    assert_eq!(
+        *two.codomain().a4(),
+        -DynResidue::new(&U512::from(4u8), params)
+    );
+    assert_eq!(two.codomain().a6().retrieve(), U512::ZERO);
+
+    for l in [3, 5, 7] {
+        let isogeny = Isogeny::from_generator(&kernel_point(&e0, l)).unwrap();
+        let codomain = isogeny.codomain();
+        assert_eq!(isogeny.degree(), l);
+        assert_eq!(isogeny.kernel_polynomial().len() as u64, (l - 1) / 2 + 1);
+        assert_eq!(codomain.points().count() as u64, ORDER);
+        let images: BTreeSet<_> = all
+            .iter()
+            .map(|point| isogeny.evaluate(point).unwrap())
+            .collect();
+        // the kernel is all in E(F_p), so the image has 420 / l points
+        assert_eq!(images.len() as u64, ORDER / l);
+        assert!(images
+            .iter()
+            .all(|image| image.is_infinity() || codomain.contains(image)));
+        for point in isogeny.kernel() {
+            assert!(isogeny.evaluate(point).unwrap().is_infinity());
+        }
+        // a homomorphism, and the rational map is the one evaluated
+        let ((x_numerator, x_denominator), (y_numerator, y_denominator)) =
+            (isogeny.x_map(), isogeny.y_map());
+        for (a, b) in all.iter().zip(all.iter().skip(101)).step_by(37) {
+            let sum = (a + b).unwrap();
+            assert!(
+                isogeny.evaluate(&sum).unwrap()
+                    == (&isogeny.evaluate(a).unwrap() + &isogeny.evaluate(b).unwrap()).unwrap()
+            );
+            let image = isogeny.evaluate(a).unwrap();
+            if let (Some((x, y)), Some((image_x, image_y))) =
+                (a.x().zip(a.y()), image.x().zip(image.y()))
+            {
+                let x_map = evaluate(&x_numerator, x) * evaluate(&x_denominator, x).invert().0;
+                let y_map = *y * evaluate(&y_numerator, x) * evaluate(&y_denominator, x).invert().0;
+                assert_eq!((&x_map, &y_map), (image_x, image_y));
+            }
+        }
+        let [a4, a6] = [codomain.a4(), codomain.a6()].map(|c| c.retrieve().as_words()[0]);
+        println!(
+            "{l}-isogeny onto y^2 = x^3 + {a4}x + {a6} with j = {}",
+            j(codomain).as_words()[0]
+        );
+    }
+
+    // a chain of prime steps is the isogeny with the kernel of order 105 spanned by the point
+    let generator = all
+        .iter()
+        .map(|point| (point * U512::from(4u8)).unwrap())
+        .find(|point| point.order(&[(U512::from(105u8), 1)]).unwrap() == U512::from(105u8))
+        .unwrap();
+    let chain = Isogeny::chain(&generator, &[3, 5, 7]).unwrap();
+    let degrees: Vec<_> = chain.iter().map(Isogeny::degree).collect();
+    assert_eq!(degrees, [3, 5, 7]);
+    let image = chain.iter().fold(generator.clone(), |point, step| {
+        step.evaluate(&point).unwrap()
+    });
+    assert!(image.is_infinity());
+    let end = chain.last().unwrap().codomain();
+    assert_eq!(j(end), j(&act(&e0, &[(3, 1), (5, 1), (7, 1)])));
+    assert_eq!(
+        Isogeny::chain(&generator, &[3, 5]).err(),
+        Some(PointError::NotAnnihilated {
+            curve: e0.name.clone(),
+            multiple: U512::from(15u8)
+        })
+    );
+
+    // the kernel must be a subgroup of the domain
+    let third = kernel_point(&e0, 3);
+    assert_eq!(
+        Isogeny::new(&e0, std::slice::from_ref(&third)).err(),
+        Some(PointError::NotSubgroup {
+            curve: e0.name.clone()
+        })
+    );
+    let doubled = (&third + &third).unwrap();
+    assert_eq!(
+        Isogeny::new(&e0, &[third.clone(), doubled])
+            .unwrap()
+            .degree(),
+        3
+    );
+    assert!(matches!(
+        Isogeny::new(two.codomain(), &[third]),
+        Err(PointError::DifferentCurves { .. })
+    ));
+
+    // an isogeny-graph walk: the steps of degree 3 go round a cycle of the curves over F_p back to j = 1728
+    let mut curve = Rc::clone(&e0);
+    let mut cycle = vec![j(&curve).as_words()[0]];
+    loop {
+        curve = act(&curve, &[(3, 1)]);
+        if curve.is_isomorphic(&e0).is_some() {
+            break;
+        }
+        cycle.push(j(&curve).as_words()[0]);
+        assert!(cycle.len() < 100);
+    }
+    println!("the 3-isogeny cycle from j = 1728 = 52: {cycle:?}");
+
+    // the class group action commutes, and the steps undo each other, up to isomorphism over F_p
+    let (three_five, five_three) = (
+        act(&act(&e0, &[(3, 1)]), &[(5, 1)]),
+        act(&act(&e0, &[(5, 1)]), &[(3, 1)]),
+    );
+    assert!(three_five.is_isomorphic(&five_three).is_some());
+    assert!(act(&e0, &[(7, 1), (7, -1)]).is_isomorphic(&e0).is_some());
+    // the twist shares j but not the class, but for j = 1728 where it's the curve itself
+    assert!(twist(&three_five).is_isomorphic(&three_five).is_none());
+    assert!(twist(&e0).is_isomorphic(&e0).is_some());
+
+    // CSIDH with the exponents in -1..=1
+    let alice = [(3, 1), (5, -1), (7, 1)];
+    let bob = [(3, -1), (5, -1), (7, 1)];
+    let (public_alice, public_bob) = (act(&e0, &alice), act(&e0, &bob));
+    let (shared_alice, shared_bob) = (act(&public_bob, &alice), act(&public_alice, &bob));
+    assert!(shared_alice.is_isomorphic(&shared_bob).is_some());
+    println!(
+        "public j: {} and {}, shared j = {}",
+        j(&public_alice).as_words()[0],
+        j(&public_bob).as_words()[0],
+        j(&shared_alice).as_words()[0]
+    );
+}
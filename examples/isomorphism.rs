@@ -0,0 +1,176 @@
+// Curves up to isomorphism: Curve25519 as Wei25519, isomorphisms with u != 1 and the extra automorphisms of j = 0 and
+// j = 1728, and quadratic twists, whose points are the ones an unchecked x-only ladder computes on
+use std::rc::Rc;
+
+use crypto_bigint::{
+    modular::runtime_mod::{DynResidue, DynResidueParams},
+    U256, U512,
+};
+use hw_13::{
+    curves,
+    extension::{Field, Fp2},
+    CurveError, EllipticCurve, Error, FieldError, PointError,
+};
+
+fn main() {
+    // Curve25519 is y^2 = x^3 + A.x^2 + x, and x = x' - A / 3 makes it y^2 = x'^3 + a.x' + b
+    let montgomery = Rc::new(curves::curve25519());
+    let p = montgomery.original_p();
+    let params = DynResidueParams::new(&p);
+    let residue = |n: u64| DynResidue::new(&U512::from(n), params);
+    let a2 = *montgomery.a2();
+    let third = residue(3).invert().0;
+    let a = (residue(3) - a2.square()) * third;
+    let b = (residue(2) * a2.square() * a2 - residue(9) * a2) * third.square() * third;
+    let weierstrass = Rc::new(EllipticCurve::from_uint_coefficients(
+        "Wei25519".to_string(),
+        *montgomery.order(),
+        p,
+        [U512::ZERO, a.retrieve(), b.retrieve()],
+    ));
+    assert!(montgomery != weierstrass);
+    assert_eq!(montgomery.j_invariant(), weierstrass.j_invariant());
+    let to_weierstrass = montgomery.is_isomorphic(&weierstrass).unwrap();
+    let (u, r, s, t) = to_weierstrass.parameters();
+    assert_eq!((u, r), (residue(1), -a2 * third));
+    assert!(s.is_zero() && t.is_zero());
+
+    let generator = montgomery.generator().unwrap();
+    let image = to_weierstrass.evaluate(&generator).unwrap();
+    assert!(weierstrass.contains(&image));
+    // the base point of Wei25519 in RFC 7748's companion draft
+    assert_eq!(
+        image.x().unwrap().retrieve(),
+        U256::from_be_hex("2aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaad245a")
+            .resize()
+    );
+    let back = to_weierstrass.inverse();
+    assert!(back.evaluate(&image).unwrap() == generator);
+    let k = U512::from(0xdeadbeefu32);
+    let multiple = (&generator * k).unwrap();
+    assert!(to_weierstrass.evaluate(&multiple).unwrap() == (&image * k).unwrap());
+    assert!(matches!(
+        back.evaluate(&generator),
+        Err(PointError::DifferentCurves { .. })
+    ));
+    println!("Curve25519 -> Wei25519: x' = x + A / 3");
+
+    // a curve against its image under u = 5, r = 7: literally different, yet the same group
+    let p = U512::from(10007u16);
+    let params = DynResidueParams::new(&p);
+    let residue = |n: u64| DynResidue::new(&U512::from(n), params);
+    let curve = Rc::new(EllipticCurve::new(
+        "y^2 = x^3 + 2x^2 + 3x + 5".to_string(),
+        U512::from(3u8),
+        p,
+        [2, 3, 5],
+    ));
+    let (u, r) = (residue(5), residue(7));
+    let inverse = u.invert().0.square();
+    let [a2, a4, a6] = [*curve.a2(), *curve.a4(), *curve.a6()];
+    let coefficients = [
+        (a2 + residue(3) * r) * inverse,
+        (residue(3) * r.square() + residue(2) * a2 * r + a4) * inverse.square(),
+        ((r.square() + a2 * r + a4) * r + a6) * inverse.square() * inverse,
+    ];
+    let image = Rc::new(EllipticCurve::from_uint_coefficients(
+        "the image".to_string(),
+        U512::from(3u8),
+        p,
+        coefficients.map(|c| c.retrieve()),
+    ));
+    let isomorphism = curve.is_isomorphic(&image).unwrap();
+    // u and -u both fit, and the smaller one is returned
+    let (found, found_r, _, _) = isomorphism.parameters();
+    assert!(found == u || found == -u);
+    assert!(found_r == r);
+    for point in curve.points().step_by(97) {
+        let mapped = isomorphism.evaluate(&point).unwrap();
+        assert!(mapped.is_infinity() || image.contains(&mapped));
+        assert!(isomorphism.inverse().evaluate(&mapped).unwrap() == point);
+    }
+    let identity = curve.is_isomorphic(&curve).unwrap();
+    assert_eq!(identity.parameters().0, residue(1));
+    assert!(identity.parameters().1.is_zero());
+
+    // p = 10007 = 2 mod 3, so every element is a cube: y^2 = x^3 + 1 is isomorphic to y^2 = x^3 + c.1 only for the
+    // squares c, as u^6 is any square; and p = 3 mod 4 makes u^4 any square for j = 1728
+    let short = |name: &str, coefficients| {
+        Rc::new(EllipticCurve::new(
+            name.to_string(),
+            U512::from(3u8),
+            p,
+            coefficients,
+        ))
+    };
+    let j0 = short("y^2 = x^3 + 1", [0, 0, 1]);
+    assert_eq!(j0.j_invariant(), residue(0));
+    assert!(j0
+        .is_isomorphic(&short("y^2 = x^3 + 4", [0, 0, 4]))
+        .is_some());
+    // -1 isn't a square, so y^2 = x^3 - 1 is the quadratic twist
+    let j0_twist = short("y^2 = x^3 - 1", [0, 0, -1]);
+    assert_eq!(j0_twist.j_invariant(), residue(0));
+    assert!(j0.is_isomorphic(&j0_twist).is_none());
+    let j1728 = short("y^2 = x^3 + x", [0, 1, 0]);
+    assert_eq!(j1728.j_invariant(), residue(1728));
+    assert!(j1728
+        .is_isomorphic(&short("y^2 = x^3 + 9x", [0, 9, 0]))
+        .is_some());
+    assert!(j1728
+        .is_isomorphic(&short("y^2 = x^3 - x", [0, -1, 0]))
+        .is_none());
+
+    // the twist by the non-square 5: every x is on E, on the twist over 5, or on both when the right hand side is zero
+    let count = curve.count_points().unwrap();
+    let twisted = curve.quadratic_twist(U512::from(5u8)).unwrap();
+    let twist = twisted.twist();
+    assert_eq!(
+        count.wrapping_add(twist.order()),
+        U512::from(2 * 10007 + 2u32)
+    );
+    assert_eq!(twist.count_points().unwrap(), *twist.order());
+    for x in (0..10007u64).step_by(13) {
+        let x_residue = residue(x);
+        let on_curve = ((x_residue.square() + a2 * x_residue + a4) * x_residue + a6).is_square();
+        match twisted.twist_point_at(U512::from(x)) {
+            Ok(point) => {
+                assert!(twist.contains(&point));
+                assert!(!on_curve || point.y().unwrap().is_zero());
+            }
+            Err(PointError::NoPointWithX { .. }) => assert!(on_curve),
+            Err(error) => panic!("{error}"),
+        }
+    }
+    assert_eq!(
+        curve.quadratic_twist(U512::from(4u8)).err(),
+        Some(Error::Curve(CurveError::SquareTwist {
+            name: curve.name.clone(),
+            d: U512::from(4u8)
+        }))
+    );
+
+    // over F_p^2 = F_p(sqrt 5) the twist is isomorphic to the curve
+    let one = Fp2::one(residue(5)).unwrap();
+    let over = Rc::new(curve.over(&one).unwrap());
+    for _ in 0..5 {
+        let (a, b) = (over.random_point(), over.random_point());
+        let sum = twisted.map(&(&a + &b).unwrap()).unwrap();
+        let mapped = (&twisted.map(&a).unwrap() + &twisted.map(&b).unwrap()).unwrap();
+        assert_eq!(sum, mapped);
+    }
+    // and not over F_p
+    let base = Rc::new(curve.over(&residue(1)).unwrap());
+    assert_eq!(
+        twisted.map(&base.random_point()).err(),
+        Some(Error::Field(FieldError::NoSquareRoot {
+            d: U512::from(5u8),
+            degree: 1
+        }))
+    );
+    println!(
+        "#E = {}, #E^5 = {}",
+        count.as_words()[0],
+        twist.order().as_words()[0]
+    );
+}
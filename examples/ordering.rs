@@ -0,0 +1,45 @@
+// Points as keys of hash sets and ordered sets: `Hash` and `Ord` agree with the equality, which the tests check.
+use std::{
+    collections::{BTreeSet, HashSet},
+    rc::Rc,
+};
+
+use crypto_bigint::U512;
+use hw_13::{EllipticCurve, Point};
+
+fn main() {
+    // the same curve behind two `Rc`s, so that the points are equal without sharing the pointer
+    let p = U512::from(10007u16);
+    let new_curve = || {
+        Rc::new(EllipticCurve::new(
+            "E".to_string(),
+            U512::from(5003u16),
+            p,
+            [0, 2, 3],
+        ))
+    };
+    let (curve, twin) = (new_curve(), new_curve());
+    let points = || curve.points().chain(twin.points());
+
+    let set = points().collect::<HashSet<_>>();
+    let tree = points().collect::<BTreeSet<_>>();
+    assert_eq!(set.len(), tree.len());
+    // the point at infinity comes first, then by `x` and `y`
+    assert!(tree.first().unwrap().is_infinity());
+    println!(
+        "{} distinct points of {} out of {}",
+        tree.len(),
+        curve.name,
+        points().count()
+    );
+
+    // points of different curves are never equal, not even the points at infinity
+    let shifted = Rc::new(EllipticCurve::new(
+        "F".to_string(),
+        U512::from(5039u16),
+        p,
+        [0, 1, 1],
+    ));
+    let infinity = Point::new(Rc::clone(&shifted), None, None).unwrap();
+    assert!(!tree.contains(&infinity));
+}
@@ -0,0 +1,113 @@
+// Tate and Weil pairings on the supersingular y^2 = x^3 + x over F_10007: bilinearity, and the MOV reduction of a
+// logarithm on the curve to one in F_{p^2}
+use std::rc::Rc;
+
+use crypto_bigint::{
+    modular::runtime_mod::{DynResidue, DynResidueParams},
+    rand_core::OsRng,
+    NonZero, RandomMod, U512,
+};
+use hw_13::{
+    curves,
+    extension::{Field, Fp2},
+    pairing, EllipticCurve, FieldError, PointError,
+};
+
+fn main() {
+    let p = U512::from(10007u16);
+    let r = U512::from(139u8);
+    let curve = Rc::new(EllipticCurve::new(
+        "y^2 = x^3 + x".to_string(),
+        r,
+        p,
+        [0, 1, 0],
+    ));
+    assert_eq!(curve.embedding_degree(), Some(2));
+    assert_eq!(curves::secp256k1().embedding_degree(), None);
+
+    // P of order 139 over F_p, and its image under the distortion map (x, y) -> (-x, i.y), which is over F_{p^2} only
+    let cofactor = U512::from(72u8);
+    let base = curve
+        .points()
+        .map(|point| (&point * cofactor).unwrap())
+        .find(|point| !point.is_infinity())
+        .unwrap();
+    let one = Fp2::one(-DynResidue::one(DynResidueParams::new(&p))).unwrap();
+    let over = Rc::new(curve.over(&one).unwrap());
+    let p_point = base.over(&over).unwrap();
+    let (x, y) = (*p_point.x().unwrap(), *p_point.y().unwrap());
+    let i = one.from_coefficients(DynResidue::zero(*one.c0().params()), *one.c0());
+    let q_point = over.point(-x, i * y).unwrap();
+    assert_ne!(q_point.frobenius(), q_point);
+
+    let random = || U512::random_mod(&mut OsRng, &NonZero::new(r).unwrap());
+    for (name, pairing) in [
+        (
+            "Tate",
+            &(|p: &_, q: &_| pairing::tate(p, q, &r).ok()) as &dyn Fn(&_, &_) -> Option<Fp2>,
+        ),
+        ("Weil", &|p: &_, q: &_| pairing::weil(p, q, &r).ok()),
+    ] {
+        let e = pairing(&p_point, &q_point).unwrap();
+        assert_ne!(e, one);
+        assert_eq!(e.pow(&r), one);
+        for _ in 0..5 {
+            let (a, b) = (random(), random());
+            assert_eq!(
+                pairing(&(&p_point * a), &(&q_point * b)).unwrap(),
+                e.pow(&a.wrapping_mul(&b))
+            );
+        }
+        println!("{name}: e(P, Q) = {e}, bilinear");
+    }
+    // Weil is alternating
+    let forth = pairing::weil(&p_point, &q_point, &r).unwrap();
+    let back = pairing::weil(&q_point, &p_point, &r).unwrap();
+    assert_eq!(forth * back, one);
+    // both points over F_p make the reduced Tate pairing trivial with k = 2
+    let small = (&curve.points().nth(5).unwrap() * r).unwrap();
+    assert_eq!(
+        pairing::tate(&p_point, &small.over(&over).unwrap(), &r),
+        Ok(one)
+    );
+
+    // MOV: k.P = T on the curve becomes e(P, Q)^k = e(T, Q) among the 139-th roots of unity of F_{p^2}
+    let k = random();
+    let target = (&base * k).unwrap().over(&over).unwrap();
+    let (g, h) = (
+        pairing::tate(&p_point, &q_point, &r).unwrap(),
+        pairing::tate(&target, &q_point, &r).unwrap(),
+    );
+    let found = (0..139u8)
+        .scan(one, |power, _| {
+            let current = *power;
+            *power = current * g;
+            Some(current)
+        })
+        .position(|power| power == h)
+        .unwrap();
+    assert_eq!(U512::from(found as u8), k);
+    println!("MOV: the logarithm {found} recovered in F_p^2");
+
+    // F_p holds no 139-th roots of unity
+    let prime_field = Rc::new(curve.over(one.c0()).unwrap());
+    let base_p = base.over(&prime_field).unwrap();
+    assert_eq!(
+        pairing::tate(&base_p, &base_p, &r),
+        Err(FieldError::NoRootsOfUnity {
+            order: r,
+            degree: 1
+        }
+        .into())
+    );
+    // the points must be of order 139, and independent for Miller's loop
+    let stray = curve.points().nth(1).unwrap().over(&over).unwrap();
+    assert!(matches!(
+        pairing::weil(&stray, &q_point, &r),
+        Err(PointError::NotAnnihilated { .. })
+    ));
+    assert!(matches!(
+        pairing::weil(&p_point, &(&p_point * U512::from(2u8)), &r),
+        Err(PointError::DegeneratePairing { .. })
+    ));
+}
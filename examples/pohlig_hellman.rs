@@ -0,0 +1,68 @@
+// Pohlig-Hellman on a curve over a 64-bit field whose order has no prime factor beyond 24 bits: a logarithm takes a
+// moment, while rho would need days on a prime-order curve of the same size.
+use std::{rc::Rc, time::Instant};
+
+use crypto_bigint::{rand_core::OsRng, NonZero, RandomMod, U512};
+use hw_13::{dlog, primality, EllipticCurve};
+
+fn main() {
+    // the factorization itself: small primes, a square and two primes for rho to split
+    let (a, b) = (U512::from((1u64 << 31) - 1), U512::from((1u64 << 40) - 87));
+    let n = U512::from(243u8)
+        .wrapping_mul(&a)
+        .wrapping_mul(&a)
+        .wrapping_mul(&b)
+        .wrapping_mul(&U512::from(1000003u32));
+    assert_eq!(
+        primality::factor(n),
+        vec![
+            (U512::from(3u8), 5),
+            (U512::from(1000003u32), 1),
+            (a, 2),
+            (b, 1)
+        ]
+    );
+
+    // y^2 = x^3 - 3x + 1 over 2^64 - 59 has 3 * 47 * 701 * 3019 * 4861 * 12717269 points
+    let p = U512::from(u64::MAX - 58);
+    let order = U512::from(0xffffffff8ce91e53u64);
+    let curve = Rc::new(EllipticCurve::new("weak".to_string(), order, p, [0, -3, 1]));
+    assert_eq!(curve.compute_order(), order);
+    let factorization = primality::factor(order);
+    let primes: Vec<_> = factorization
+        .iter()
+        .map(|(prime, _)| prime.as_words()[0])
+        .collect();
+    println!("#E = {} = {primes:?}", order.as_words()[0]);
+
+    // the group is cyclic, so some point generates it
+    let base = curve
+        .points()
+        .find(|point| point.order(&factorization) == Ok(order))
+        .unwrap();
+    // in the subgroup of order 3, smaller than the table of baby steps, 2.P = -P is 2 rather than -1
+    let third = (&base * order.wrapping_div(&U512::from(3u8))).unwrap();
+    let two = U512::from(2u8);
+    assert_eq!(
+        dlog::bsgs(&third, &(&third * two).unwrap(), U512::from(3u8)).unwrap(),
+        Some(two)
+    );
+    for _ in 0..5 {
+        let k = U512::random_mod(&mut OsRng, &NonZero::new(order).unwrap());
+        let target = (&base * k).unwrap();
+        let start = Instant::now();
+        assert_eq!(
+            dlog::pohlig_hellman(&base, &target, order).unwrap(),
+            Some(k)
+        );
+        println!(
+            "found k = {:#x} in {:.1?}",
+            k.as_words()[0],
+            start.elapsed()
+        );
+    }
+
+    // a point out of the subgroup of order order / 47 has no logarithm there
+    let small = (&base * U512::from(47u8)).unwrap();
+    assert_eq!(dlog::pohlig_hellman(&small, &base, order).unwrap(), None);
+}
@@ -0,0 +1,96 @@
+// Primality tests against a sieve and the known pseudoprimes, and the curve constructor rejecting composites.
+use crypto_bigint::{U512, U64};
+use hw_13::{
+    curves,
+    primality::{baillie_psw, miller_rabin, strong_lucas},
+    CurveError, EllipticCurve,
+};
+
+const SIEVE_BOUND: usize = 200_000;
+
+fn main() {
+    let mut sieve = vec![true; SIEVE_BOUND];
+    sieve[0] = false;
+    sieve[1] = false;
+    for i in 2..SIEVE_BOUND {
+        if sieve[i] {
+            for multiple in (i * i..SIEVE_BOUND).step_by(i) {
+                sieve[multiple] = false;
+            }
+        }
+    }
+    // the tests are generic over the size of `Uint`
+    for (n, &prime) in sieve.iter().enumerate() {
+        let n = U64::from(n as u64);
+        assert_eq!(baillie_psw(&n), prime);
+    }
+    // random bases are drawn from OS, so it takes a while
+    for (n, &prime) in sieve.iter().enumerate().take(SIEVE_BOUND / 10) {
+        assert_eq!(miller_rabin(&U64::from(n as u64), 20), prime);
+    }
+    println!("agrees with the sieve below {SIEVE_BOUND}");
+
+    // strong pseudoprimes to base 2 fall to the Lucas part
+    for n in [
+        2047u64,
+        3277,
+        4033,
+        4681,
+        8321,
+        3215031751,
+        2152302898747,
+        3474749660383,
+        341550071728321,
+        3825123056546413051,
+    ] {
+        assert!(!strong_lucas(&U512::from(n)) && !baillie_psw(&U512::from(n)));
+    }
+    // and strong Lucas pseudoprimes to base 2 test
+    for n in [22499u64, 25199, 40309, 58519, 75077, 97439] {
+        assert!(strong_lucas(&U512::from(n)) && !baillie_psw(&U512::from(n)));
+    }
+    println!("pseudoprimes are caught");
+
+    let mersenne = |power| U512::ONE.shl_vartime(power).wrapping_sub(&U512::ONE);
+    for power in [61, 89, 107, 127] {
+        assert!(baillie_psw(&mersenne(power)));
+    }
+    for power in [67, 101, 257, 509] {
+        assert!(!baillie_psw(&mersenne(power)));
+    }
+    assert!(!baillie_psw(&mersenne(127).wrapping_mul(&mersenne(89))));
+    assert!(!baillie_psw(&mersenne(127).wrapping_mul(&mersenne(127))));
+    for curve in curves::all() {
+        assert!(baillie_psw(&curve.original_p()) && baillie_psw(curve.order()));
+    }
+    println!("big numbers are fine");
+
+    // the constructor rejects a composite modulus and, when asked to, a composite order
+    let composite = U512::from(10007u64 * 10009);
+    assert!(matches!(
+        EllipticCurve::try_new("".to_string(), U512::from(7u8), composite, [0, 2, 3]),
+        Err(CurveError::CompositeModulus { modulus }) if modulus == composite
+    ));
+    let curve = EllipticCurve::try_new(
+        "".to_string(),
+        U512::from(10008u16),
+        U512::from(10007u16),
+        [0, 2, 3],
+    )
+    .unwrap();
+    assert!(matches!(
+        curve.require_prime_order(),
+        Err(CurveError::CompositeOrder { .. })
+    ));
+    let secp256k1 = curves::secp256k1();
+    let (name, order, coefficients, p) = secp256k1.get();
+    EllipticCurve::try_new(
+        name.to_string(),
+        *order,
+        *p,
+        coefficients.try_into().unwrap(),
+    )
+    .and_then(EllipticCurve::require_prime_order)
+    .unwrap();
+    println!("the constructor checks are in place");
+}
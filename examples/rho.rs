@@ -0,0 +1,73 @@
+// Pollard's rho on a generated curve of prime order, the cost it predicts for bigger ones, and kangaroos catching a
+// logarithm known to lie in an interval on secp256k1.
+use std::{rc::Rc, time::Instant};
+
+use crypto_bigint::{rand_core::OsRng, NonZero, RandomMod, U512};
+use hw_13::{curves, dlog, CurveGenerator, PointError};
+
+fn random_below(bound: U512) -> U512 {
+    U512::random_mod(&mut OsRng, &NonZero::new(bound).unwrap())
+}
+
+fn main() {
+    let p = U512::from(268435399u32);
+    let (curve, _) = CurveGenerator::new(p).generate_from_seed([1; 32]).unwrap();
+    let curve = Rc::new(curve);
+    let (g, order) = (curve.generator().unwrap(), *curve.order());
+
+    let start = Instant::now();
+    let rounds = 4;
+    for _ in 0..rounds {
+        let k = random_below(order);
+        let target = (&g * k).unwrap();
+        assert_eq!(dlog::rho(&g, &target, order).unwrap(), Some(k));
+    }
+    let elapsed = start.elapsed() / rounds;
+    println!(
+        "a logarithm on a curve of {} bits in {elapsed:.1?} on average",
+        order.bits()
+    );
+
+    // the work grows as the square root of the order: this is what "128-bit security" of a 256-bit curve means
+    for bits in [64, 80, 128] {
+        let factor = 2f64.powf((bits as f64 - order.bits() as f64) / 2.);
+        let years = elapsed.as_secs_f64() * factor / (365.25 * 24. * 3600.);
+        println!("a {bits}-bit order would take about {years:.1e} years here");
+    }
+
+    // a 30-bit window somewhere in the middle of the range of secp256k1
+    let secp256k1 = Rc::new(curves::secp256k1());
+    let g = secp256k1.generator().unwrap();
+    let low = random_below(*secp256k1.order());
+    let high = low.wrapping_add(&U512::ONE.shl_vartime(30));
+    let k = low.wrapping_add(&random_below(U512::ONE.shl_vartime(30)));
+    let target = (&g * k).unwrap();
+    let start = Instant::now();
+    assert_eq!(dlog::kangaroo(&g, &target, low, high).unwrap(), Some(k));
+    println!(
+        "a logarithm in a 30-bit interval on {} in {:.1?}",
+        secp256k1.name,
+        start.elapsed()
+    );
+    // a window which misses it
+    assert_eq!(
+        dlog::kangaroo(
+            &g,
+            &target,
+            high,
+            high.wrapping_add(&U512::from(1u32 << 20))
+        )
+        .unwrap(),
+        None
+    );
+
+    assert!(matches!(
+        dlog::rho(&g, &curve.generator().unwrap(), order),
+        Err(PointError::DifferentCurves { .. })
+    ));
+    // a 256-bit order is out of reach, so rho doesn't even start
+    assert!(matches!(
+        dlog::rho(&g, &g, *secp256k1.order()),
+        Err(PointError::SearchTooWide { bits: 256, .. })
+    ));
+}
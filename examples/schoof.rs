@@ -0,0 +1,36 @@
+// Schoof's algorithm checked against the naive counting and timed on the named curves listed:
+// `cargo run --release --example schoof -- secp160k1 M-221`; the tests check every named curve.
+use std::{rc::Rc, time::Instant};
+
+use crypto_bigint::U512;
+use hw_13::{curves, EllipticCurve};
+
+fn main() {
+    for (p, coefficients) in [
+        (100003u64, [0, 2, 3]),
+        (1000003, [0, -3, 5]),
+        (1048573, [6, 1, 0]),
+        ((1 << 40) - 87, [0, 2, 3]),
+    ] {
+        let curve = Rc::new(EllipticCurve::new(
+            format!("teaching curve over {p}"),
+            U512::from(3u8),
+            U512::from(p),
+            coefficients,
+        ));
+        let order = curve.compute_order();
+        assert_eq!(order, curve.count_points().unwrap());
+        println!("#E(F_{p}) = {}", order.as_words()[0]);
+    }
+
+    for name in std::env::args().skip(1) {
+        let curve = curves::by_name(&name).expect("unknown curve");
+        let start = Instant::now();
+        let order = curve.compute_order();
+        assert_eq!(
+            order,
+            curve.order().wrapping_mul(&curve.cofactor().unwrap())
+        );
+        println!("{name}: {:?}", start.elapsed());
+    }
+}
@@ -0,0 +1,115 @@
+// Curves and points to JSON and back: `cargo run --example serde --features serde`.
+use std::rc::Rc;
+
+use crypto_bigint::U512;
+use hw_13::{curves, CurveGenerator, CurvePoints, EllipticCurve, OnCurve, Point};
+use serde::de::DeserializeSeed;
+
+fn main() {
+    // SEC1 itself, checked against the well known encoding of secp256k1 base point
+    let secp256k1 = Rc::new(curves::secp256k1());
+    let g = secp256k1.generator().unwrap();
+    let compressed = g.to_sec1(true);
+    assert_eq!(
+        compressed[..5],
+        [0x02, 0x79, 0xbe, 0x66, 0x7e],
+        "y of the base point is even"
+    );
+    assert!(Point::from_sec1(Rc::clone(&secp256k1), &compressed).unwrap() == g);
+    assert!(Point::from_sec1(Rc::clone(&secp256k1), &g.to_sec1(false)).unwrap() == g);
+    let infinity = Point::new(Rc::clone(&secp256k1), None, None).unwrap();
+    assert_eq!(infinity.to_sec1(true), [0]);
+
+    // a registry curve is just its name
+    let json = serde_json::to_string(secp256k1.as_ref()).unwrap();
+    assert_eq!(json, r#""Secp256k1""#);
+    assert!(serde_json::from_str::<EllipticCurve>(&json).unwrap() == *secp256k1);
+    assert!(serde_json::from_str::<EllipticCurve>(r#""secp256r1""#).is_err());
+
+    // other curves are their parameters: small coefficients as numbers and residues as hex
+    let p = U512::from(10007u16);
+    let small = Rc::new(EllipticCurve::new(
+        "E".to_string(),
+        U512::from(5003u16),
+        p,
+        [0, 2, 3],
+    ));
+    let base = (&small.points().nth(5).unwrap() * U512::from(2u8)).unwrap();
+    let (_, x, y) = base.get();
+    let small = EllipticCurve::new("E".to_string(), U512::from(5003u16), p, [0, 2, 3])
+        .with_generator(x.unwrap(), y.unwrap())
+        .unwrap();
+    let json = serde_json::to_string(&small).unwrap();
+    println!("{json}");
+    let again: EllipticCurve = serde_json::from_str(&json).unwrap();
+    assert!(again == small && again.get() == small.get());
+    assert!(Rc::new(again).generator().unwrap().x() == base.x());
+
+    let (generated, _) = CurveGenerator::new(U512::from(4_294_967_291u64))
+        .generate_from_seed([3; 32])
+        .unwrap();
+    let json = serde_json::to_string_pretty(&generated).unwrap();
+    println!("{json}");
+    let again: EllipticCurve = serde_json::from_str(&json).unwrap();
+    assert!(again == generated && again.order() == generated.order());
+
+    // the points of a message share one curve
+    let message = CurvePoints {
+        curve: Rc::clone(&secp256k1),
+        points: (1..5u8)
+            .map(|k| (&g * U512::from(k)).unwrap())
+            .chain([infinity])
+            .collect(),
+    };
+    let json = serde_json::to_string(&message).unwrap();
+    let again: CurvePoints = serde_json::from_str(&json).unwrap();
+    assert!(again.points == message.points);
+    assert!(again
+        .points
+        .iter()
+        .all(|point| Rc::ptr_eq(&point.curve(), &again.curve)));
+
+    // a single point goes onto the curve at hand, and compressed ones are fine too
+    let text = format!(r#""{}""#, hex(&g.to_sec1(true)));
+    let point = OnCurve(Rc::clone(&secp256k1))
+        .deserialize(&mut serde_json::Deserializer::from_str(&text))
+        .unwrap();
+    assert!(point == g && Rc::ptr_eq(&point.curve(), &secp256k1));
+
+    // everything is validated on the way in
+    let mut tampered = g.to_sec1(false);
+    tampered[64] ^= 1;
+    let on_secp256k1 = |text: String| {
+        OnCurve(Rc::clone(&secp256k1))
+            .deserialize(&mut serde_json::Deserializer::from_str(&text))
+            .map(|_| ())
+            .map_err(|error| error.to_string())
+    };
+    println!(
+        "{}",
+        on_secp256k1(format!(r#""{}""#, hex(&tampered))).unwrap_err()
+    );
+    assert!(on_secp256k1(r#""05""#.to_string()).is_err());
+    assert!(on_secp256k1(r#""04zz""#.to_string()).is_err());
+    let singular = r#"{"name":"E","order":"0x2716","p":"0x2717","coefficients":[0,-3,2]}"#;
+    println!(
+        "{}",
+        serde_json::from_str::<EllipticCurve>(singular)
+            .err()
+            .unwrap()
+    );
+    let off_curve = json_with_generator(&small, "0400010001");
+    assert!(serde_json::from_str::<EllipticCurve>(&off_curve).is_err());
+    println!("curves and points survive the round trip");
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+/// The curve serialized with another generator
+fn json_with_generator(curve: &EllipticCurve, generator: &str) -> String {
+    let mut value = serde_json::to_value(curve).unwrap();
+    value["generator"] = serde_json::Value::String(generator.to_string());
+    value.to_string()
+}
@@ -0,0 +1,47 @@
+// Smart's attack on anomalous curves: logarithms modulo a 48-bit prime in milliseconds, where rho would take minutes
+// and Pohlig-Hellman has nothing to split.
+use std::{rc::Rc, time::Instant};
+
+use crypto_bigint::{rand_core::OsRng, NonZero, RandomMod, U512};
+use hw_13::{dlog, EllipticCurve, PointError};
+
+fn main() {
+    for (p, coefficients) in [
+        (10007u64, [0, 22, 9]),
+        // the example of the p-adic elliptic logarithm in Sage's documentation
+        (235322474717419, [0, 0, 8856682]),
+    ] {
+        let p = U512::from(p);
+        let curve = Rc::new(
+            EllipticCurve::try_new_anomalous(format!("anomalous over {p}"), p, p, coefficients)
+                .unwrap(),
+        );
+        let base = curve.points().find(|point| !point.is_infinity()).unwrap();
+        assert!((&base * p).unwrap().is_infinity());
+        for _ in 0..10 {
+            let k = U512::random_mod(&mut OsRng, &NonZero::new(p).unwrap());
+            let target = (&base * k).unwrap();
+            let start = Instant::now();
+            assert_eq!(dlog::smart(&base, &target).unwrap(), Some(k));
+            println!(
+                "k = {:#x} on the curve over {:#x} in {:.1?}",
+                k.as_words()[0],
+                p.as_words()[0],
+                start.elapsed()
+            );
+        }
+    }
+
+    // the points of other curves aren't of order `p`
+    let curve = Rc::new(EllipticCurve::new(
+        "E".to_string(),
+        U512::from(5003u16),
+        U512::from(10007u16),
+        [0, 2, 3],
+    ));
+    let point = curve.points().nth(1).unwrap();
+    assert!(matches!(
+        dlog::smart(&point, &point),
+        Err(PointError::NotAnnihilated { .. })
+    ));
+}
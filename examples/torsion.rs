@@ -0,0 +1,121 @@
+// Division polynomials and the n-torsion they cut out, over F_p and over the extensions where all of E[n] appears
+use std::rc::Rc;
+
+use crypto_bigint::{
+    modular::runtime_mod::{DynResidue, DynResidueParams},
+    U512,
+};
+use hw_13::{
+    extension::{Field, Fp2, Fpk},
+    EllipticCurve,
+};
+
+fn main() {
+    // y^2 = x^3 + x has p + 1 = 2^3.3^2.139 points over F_p, a cyclic group, and E(F_{p^2}) is Z_{p+1}^2
+    let p = U512::from(10007u16);
+    let curve = Rc::new(EllipticCurve::new(
+        "y^2 = x^3 + x".to_string(),
+        U512::from(139u8),
+        p,
+        [0, 1, 0],
+    ));
+    for n in 1..=9 {
+        let psi = curve.division_polynomial(n);
+        let degree = if n % 2 == 0 {
+            (n * n - 4) / 2
+        } else {
+            (n * n - 1) / 2
+        };
+        assert_eq!(psi.degree(), Some(degree));
+    }
+    assert_eq!(curve.division_polynomial(0).degree(), None);
+    println!("{}", curve.division_polynomial(3));
+    println!("{}", curve.division_polynomial(4));
+
+    // E[n] is in E(F_{p^2}) for the n dividing p + 1, and only infinity of E[5] is
+    let one = Fp2::one(-DynResidue::one(DynResidueParams::new(&p))).unwrap();
+    for (n, count) in [(2, 4), (3, 9), (4, 16), (5, 1), (8, 64), (9, 81)] {
+        let psi = curve.division_polynomial(n);
+        let points = curve.torsion_points_over(&one, n).unwrap();
+        assert_eq!(points.len(), count, "E[{n}] over F_p^2");
+        assert_eq!(curve.torsion_degree(n) == 2, count == n * n);
+        assert!(points[0].is_infinity());
+        for point in &points {
+            assert!((point * U512::from(n as u64)).is_infinity());
+            if let Some((x, y)) = point.x().zip(point.y()) {
+                assert!(psi.evaluate(x, y).is_zero());
+            }
+        }
+        println!("#E[{n}](F_p^2) = {count}");
+    }
+    // the Frobenius squares to -p, which has the order 4 modulo 5
+    assert_eq!(curve.torsion_degree(5), 8);
+    let fp = |c: u64| DynResidue::new(&U512::from(c), DynResidueParams::new(&p));
+    let one = (0..)
+        .find_map(|c| {
+            let mut modulus = [fp(0); 8];
+            (modulus[0], modulus[1]) = (fp(c), fp(1));
+            Fpk::<8>::one(modulus).ok()
+        })
+        .unwrap();
+    let points = curve.torsion_points_over(&one, 5).unwrap();
+    assert_eq!(points.len(), 25);
+    assert!(points
+        .iter()
+        .all(|point| (point * U512::from(5u8)).is_infinity()));
+    println!("#E[5](F_p^8) = 25");
+
+    // over F_p only a cyclic part of E[n]
+    for (n, count) in [(2, 2), (3, 3), (4, 4), (6, 6), (9, 9), (5, 1), (139, 139)] {
+        let points = curve.torsion_points(n);
+        assert_eq!(points.len(), count, "E[{n}] over F_p");
+        assert!(points[0].is_infinity());
+        assert!(points
+            .iter()
+            .all(|point| (point * U512::from(n as u64)).unwrap().is_infinity()));
+    }
+    println!("#E(F_p)[139] = 139");
+
+    // a curve with a2 != 0, against the points found by enumeration
+    let curve = Rc::new(EllipticCurve::new(
+        "y^2 = x^3 + x^2 + 2x + 3".to_string(),
+        U512::from(3u8),
+        U512::from(1009u16),
+        [1, 2, 3],
+    ));
+    let all: Vec<_> = curve.points().collect();
+    // whether n.P is at infinity for n up to 12, walking through the multiples of each point once
+    let annihilated: Vec<Vec<bool>> = all
+        .iter()
+        .map(|point| {
+            let mut multiple = point.clone();
+            (1..=12)
+                .map(|_| {
+                    let at_infinity = multiple.is_infinity();
+                    multiple = (&multiple + point).unwrap();
+                    at_infinity
+                })
+                .collect()
+        })
+        .collect();
+    for n in 1..=12usize {
+        let mut expected: Vec<_> = all
+            .iter()
+            .zip(&annihilated)
+            .filter(|(_, annihilated)| annihilated[n - 1])
+            .map(|(point, _)| point.clone())
+            .collect();
+        let mut found = curve.torsion_points(n);
+        let key = |point: &hw_13::Point| {
+            point
+                .x()
+                .zip(point.y())
+                .map(|(x, y)| (x.retrieve(), y.retrieve()))
+        };
+        expected.sort_by_key(key);
+        found.sort_by_key(key);
+        assert!(found == expected, "E[{n}] over F_p");
+        assert_eq!(curve.torsion_degree(n) == 1, found.len() == n * n);
+    }
+    println!("{} points on {}, torsion matches", all.len(), curve.get().0);
+}
@@ -0,0 +1,166 @@
+// `EllipticCurve::try_new` refusing every kind of bad parameters, and what the errors tell about them.
+use std::{error::Error as _, rc::Rc};
+
+use crypto_bigint::U512;
+use hw_13::{curves, CurveError, EllipticCurve, Error, Point, PointError};
+
+fn main() {
+    let p = U512::from(10007u16);
+    let try_new = |order: u64, modulus: U512, coefficients| {
+        EllipticCurve::try_new("E".to_string(), U512::from(order), modulus, coefficients).err()
+    };
+    let name = "E".to_string();
+
+    assert_eq!(
+        try_new(2, p, [0, 2, 3]),
+        Some(CurveError::OrderTooSmall {
+            order: U512::from(2u8)
+        })
+    );
+    assert_eq!(
+        try_new(10008, U512::from(10008u16), [0, 2, 3]),
+        Some(CurveError::EvenModulus {
+            modulus: U512::from(10008u16)
+        })
+    );
+    assert_eq!(
+        try_new(10008, U512::from(10011u16), [0, 2, 3]),
+        Some(CurveError::CompositeModulus {
+            modulus: U512::from(10011u16)
+        })
+    );
+    assert_eq!(
+        try_new(10008, p, [0, 2, 10007]),
+        Some(CurveError::CoefficientOutOfField {
+            coefficient: "a6",
+            modulus: p
+        })
+    );
+    assert_eq!(
+        try_new(10008, p, [0, -10008, 3]),
+        Some(CurveError::CoefficientOutOfField {
+            coefficient: "a4",
+            modulus: p
+        })
+    );
+    // y^2 = x^3 - 3x + 2 = (x - 1)^2 (x + 2)
+    let singular = Some(CurveError::Singular { name: name.clone() });
+    assert_eq!(try_new(10008, p, [0, -3, 2]), singular);
+    // 4.37^3 + 27.66^2 = 32.10007, so the discriminant vanishes only modulo `p`
+    assert_eq!(try_new(10008, p, [0, 37, 66]), singular);
+    // the first one with a coefficient given as a residue
+    assert_eq!(
+        EllipticCurve::try_from_uint_coefficients(
+            "E".to_string(),
+            U512::from(10008u16),
+            p,
+            [
+                U512::ZERO,
+                p.wrapping_sub(&U512::from(3u8)),
+                U512::from(2u8)
+            ],
+        )
+        .err(),
+        singular
+    );
+    // Hasse interval of 10007 is about 9808..=10208
+    assert_eq!(
+        try_new(10301, p, [0, 2, 3]),
+        Some(CurveError::WrongOrder {
+            name: name.clone(),
+            order: U512::from(10301u16)
+        })
+    );
+    assert_eq!(try_new(5003, p, [0, 2, 3]), None);
+    // y^2 = x^3 + 22x + 9 has exactly 10007 points
+    assert_eq!(
+        try_new(10007, p, [0, 22, 9]),
+        Some(CurveError::Anomalous { name: name.clone() })
+    );
+    assert!(EllipticCurve::try_new_anomalous("E".to_string(), p, p, [0, 22, 9]).is_ok());
+    assert_eq!(
+        EllipticCurve::try_new("E".to_string(), U512::from(10008u16), p, [0, 2, 3])
+            .and_then(EllipticCurve::require_prime_order)
+            .err(),
+        Some(CurveError::CompositeOrder {
+            name: name.clone(),
+            order: U512::from(10008u16)
+        })
+    );
+    println!("every bad parameter is refused");
+
+    // the points are checked as well, and a bad generator keeps the reason as its source
+    let curve = Rc::new(EllipticCurve::new(
+        "E".to_string(),
+        U512::from(5003u16),
+        p,
+        [0, 2, 3],
+    ));
+    assert_eq!(
+        Point::new(Rc::clone(&curve), Some(p), Some(U512::ONE)).err(),
+        Some(PointError::CoordinateOutOfField {
+            curve: name.clone(),
+            coordinate: p
+        })
+    );
+    let off_curve = PointError::NotOnCurve {
+        curve: name.clone(),
+        x: U512::ONE,
+        y: U512::ONE,
+    };
+    assert_eq!(
+        Point::new(Rc::clone(&curve), Some(U512::ONE), Some(U512::ONE)).err(),
+        Some(off_curve.clone())
+    );
+    let error = EllipticCurve::new("E".to_string(), U512::from(5003u16), p, [0, 2, 3])
+        .with_generator(U512::ONE, U512::ONE)
+        .err()
+        .unwrap();
+    assert_eq!(error.source().unwrap().to_string(), off_curve.to_string());
+    // `Error` gathers them for the code which meets several kinds
+    let error = Error::from(error);
+    println!("{error}: {}", error.source().unwrap());
+    assert!(matches!(
+        error,
+        Error::Curve(CurveError::InvalidGenerator { .. })
+    ));
+
+    // the checked arithmetic refuses to mix the curves instead of panicking in `+=`
+    let (secp256k1, m511) = (Rc::new(curves::secp256k1()), Rc::new(curves::m511()));
+    let (g, h) = (secp256k1.generator().unwrap(), m511.generator().unwrap());
+    assert_eq!(
+        g.checked_add(&h).err(),
+        Some(PointError::DifferentCurves {
+            left: secp256k1.name.clone(),
+            right: m511.name.clone()
+        })
+    );
+    assert!(matches!(
+        h.checked_sub(&g),
+        Err(PointError::DifferentCurves { .. })
+    ));
+    // the operators themselves check it, cheaply: the same `Rc`, or the same fingerprint of the parameters
+    assert!(matches!(&g + &h, Err(PointError::DifferentCurves { .. })));
+    assert!(matches!(&h - &g, Err(PointError::DifferentCurves { .. })));
+    let twin = Rc::new(curves::secp256k1()).generator().unwrap();
+    assert!((&g + &twin).unwrap() == (&g * U512::from(2u8)).unwrap());
+    let mut sum = g.clone();
+    sum += g.clone();
+    assert!(sum == g.checked_mul(U512::from(2u8)).unwrap());
+    assert!(sum.checked_sub(&g).unwrap() == g);
+    println!("points of different curves don't mix");
+
+    // the named curves pass all the checks
+    for curve in curves::all() {
+        let (name, order, coefficients, p) = curve.get();
+        EllipticCurve::try_new(
+            name.to_string(),
+            *order,
+            *p,
+            coefficients.try_into().unwrap(),
+        )
+        .and_then(EllipticCurve::require_prime_order)
+        .unwrap();
+    }
+    println!("the named curves are fine");
+}
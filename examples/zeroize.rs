@@ -0,0 +1,71 @@
+// Points and secret keys wiping themselves with `zeroize` feature: `cargo run --example zeroize --features zeroize`.
+use std::{mem::MaybeUninit, ptr, rc::Rc, slice};
+
+use hw_13::{
+    bls::{Bls, Scheme},
+    curves, Point,
+};
+use zeroize::Zeroize;
+
+/// The bytes the coordinate is stored with, in Montgomery form
+fn stored_bytes(point: &Point) -> Vec<Vec<u8>> {
+    [point.x().unwrap(), point.y().unwrap()]
+        .iter()
+        .map(|coordinate| {
+            coordinate
+                .as_montgomery()
+                .as_words()
+                .iter()
+                .flat_map(|word| word.to_ne_bytes())
+                .collect()
+        })
+        .collect()
+}
+
+fn contains(haystack: &[u8], needle: &[u8]) -> bool {
+    haystack
+        .windows(needle.len())
+        .any(|window| window == needle)
+}
+
+fn main() {
+    let curve = Rc::new(curves::secp256k1());
+    // the integer type depends on `u8192` feature, so it's left to the inference
+    let secret = curve
+        .generator()
+        .unwrap()
+        .checked_mul(0xdead_beef_u32.into())
+        .unwrap();
+
+    // explicit wiping
+    let mut point = secret.clone();
+    point.zeroize();
+    assert!(point.x().unwrap().retrieve() == Default::default());
+    assert!(point.y().unwrap().retrieve() == Default::default());
+
+    // wiping on drop: the memory the point occupied no longer holds its coordinates
+    let coordinates = stored_bytes(&secret);
+    let mut slot = MaybeUninit::new(secret.clone());
+    let memory = |slot: &MaybeUninit<Point>| {
+        // SAFETY: the slot is never freed, only the point in it is dropped, and bytes are read regardless of the value
+        unsafe { slice::from_raw_parts(slot.as_ptr() as *const u8, size_of::<Point>()) }.to_vec()
+    };
+    let before = memory(&slot);
+    assert!(coordinates.iter().all(|bytes| contains(&before, bytes)));
+    // SAFETY: the slot holds an initialized point which is never used again
+    unsafe { ptr::drop_in_place(slot.as_mut_ptr()) };
+    let after = memory(&slot);
+    assert!(coordinates.iter().all(|bytes| !contains(&after, bytes)));
+
+    // the curve is still alive and sound
+    assert!(curve.contains(&secret));
+    println!("the coordinates are wiped");
+
+    // a wiped BLS secret key is zero, whose public key is the identity
+    let bls = Bls::new(Scheme::Basic);
+    let mut key = bls.key_gen(&[7; 32], b"").unwrap();
+    assert!(!bls.public_key(&key).0.is_infinity());
+    key.zeroize();
+    assert!(bls.public_key(&key).0.is_infinity());
+    println!("the secret key is wiped");
+}
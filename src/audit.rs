@@ -0,0 +1,319 @@
+//! A security report on a curve after the criteria of https://safecurves.cr.yp.to
+//!
+//! Everything comes from the group order, which is derived from `order` and the cofactor when Hasse bound allows it, so
+//! the audit is cheap for the cryptographic curves; otherwise the group is counted with `compute_order`.
+use std::{fmt, rc::Rc};
+
+use crypto_bigint::{rand_core::OsRng, Concat, NonZero, RandomMod};
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+use super::{dlog, primality, EllipticCurve, Ufeat};
+
+/// Room for $4p$, which doesn't fit `Ufeat` for the largest fields
+type Wide = <Ufeat as Concat>::Output;
+
+/// Primes below this are divided out of the numbers which need factoring
+const TRIAL_BOUND: u32 = 1 << 16;
+/// Embedding degrees up to this are searched for; MOV and FR reductions are hopeless for the bigger ones anyway
+pub const EMBEDDING_DEGREE_BOUND: u64 = 100;
+/// SafeCurves requires at least this much security from rho, CM discriminant and twist
+const SECURITY_BITS: f64 = 100.;
+
+/// The report `EllipticCurve::audit` returns
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct AuditReport {
+    pub name: String,
+    /// Whether the modulus is prime, so that the field is a field at all
+    pub p_prime: bool,
+    /// Whether `order` is prime; rho cost assumes it is
+    pub order_prime: bool,
+    /// The number of points on the curve over its order
+    pub cofactor: Ufeat,
+    /// The curve has exactly `p` points and falls to Smart's attack
+    pub anomalous: bool,
+    /// Whether Smart's attack recovered a random logarithm; only tried on anomalous curves
+    pub smart_attack: Option<bool>,
+    /// The smallest `k` with $p^k = 1 \mod order$; `None` if it exceeds `EMBEDDING_DEGREE_BOUND`
+    pub embedding_degree: Option<u64>,
+    /// $log_2$ of the absolute value of the fundamental discriminant of the endomorphism ring
+    pub cm_discriminant_bits: f64,
+    /// `false` when a big square factor might be left in $t^2 - 4p$, making `cm_discriminant_bits` an upper bound
+    pub cm_discriminant_exact: bool,
+    /// $log_2$ of the expected number of additions for Pollard's rho, $0.886 \sqrt{order}$
+    pub rho_cost_bits: f64,
+    /// The number of points on the quadratic twist
+    pub twist_order: Ufeat,
+    /// The rho cost for the largest prime subgroup of the twist; `None` if the twist order isn't factored
+    pub twist_rho_cost_bits: Option<f64>,
+}
+
+impl AuditReport {
+    /// Whether the curve passes every criterion checked
+    pub fn is_safe(&self) -> bool {
+        self.p_prime
+            && self.order_prime
+            && !self.anomalous
+            && self.embedding_degree.is_none()
+            && self.cm_discriminant_bits > SECURITY_BITS
+            && self.rho_cost_bits > SECURITY_BITS
+            && self
+                .twist_rho_cost_bits
+                .is_some_and(|bits| bits > SECURITY_BITS)
+    }
+}
+
+impl EllipticCurve {
+    /// Checks the curve against SafeCurves criteria which concern the curve equation and the field
+    pub fn audit(&self) -> AuditReport {
+        let p = self.original_p();
+        let order = *self.order();
+        let (count, cofactor) = match self.cofactor() {
+            Some(cofactor) => (order.wrapping_mul(&cofactor), cofactor),
+            None => {
+                let count = self.compute_order();
+                (count, count.wrapping_div(&order))
+            }
+        };
+        let p_plus_one = p.wrapping_add(&Ufeat::ONE);
+        // the twist has `2(p + 1) - count` points, and `t^2` doesn't care about the sign of the trace
+        let twist_order = p_plus_one.shl_vartime(1).wrapping_sub(&count);
+        let trace = if count > p_plus_one {
+            count.wrapping_sub(&p_plus_one)
+        } else {
+            p_plus_one.wrapping_sub(&count)
+        };
+        let (cm_discriminant_bits, cm_discriminant_exact) = cm_discriminant(&p, &trace);
+
+        AuditReport {
+            name: self.name.clone(),
+            p_prime: primality::baillie_psw(&p),
+            order_prime: primality::baillie_psw(&order),
+            cofactor,
+            anomalous: count == p,
+            smart_attack: (count == p).then(|| self.smart_attack_succeeds()),
+            embedding_degree: self.embedding_degree(),
+            cm_discriminant_bits,
+            cm_discriminant_exact,
+            rho_cost_bits: rho_cost_bits(&order),
+            twist_order,
+            twist_rho_cost_bits: largest_prime_factor(&twist_order)
+                .map(|prime| rho_cost_bits(&prime)),
+        }
+    }
+
+    /// Takes the logarithm of a random multiple of the generator, or of the first point when there's none, with
+    /// `dlog::smart`
+    fn smart_attack_succeeds(&self) -> bool {
+        let curve = Rc::new(self.clone());
+        let base = match curve
+            .generator()
+            .or_else(|| curve.points().find(|point| !point.is_infinity()))
+        {
+            Some(base) => base,
+            None => return false,
+        };
+        let k = Ufeat::random_mod(&mut OsRng, &NonZero::new(curve.original_p()).unwrap());
+        (&base * k)
+            .and_then(|target| dlog::smart(&base, &target))
+            .is_ok_and(|found| found == Some(k))
+    }
+}
+
+/// `log2` accurate enough for the report
+fn log2<const L: usize>(n: &crypto_bigint::Uint<L>) -> f64 {
+    let bits = n.bits();
+    if bits <= 64 {
+        return (n.as_words()[0] as f64).log2();
+    }
+    let top = n.shr_vartime(bits - 64).as_words()[0];
+    (top as f64).log2() + (bits - 64) as f64
+}
+
+/// $0.886 \sqrt{n}$, as $\sqrt{\pi / 4} = 0.886$
+fn rho_cost_bits(n: &Ufeat) -> f64 {
+    log2(n) / 2. + 0.886f64.log2()
+}
+
+/// The size of the fundamental discriminant `D` from $t^2 - 4p = f^2.D$
+///
+/// Only the square factors made of small primes are found for sure: when the rest is neither a square nor a prime, it's
+/// taken as squarefree.
+fn cm_discriminant(p: &Ufeat, trace: &Ufeat) -> (f64, bool) {
+    let (p, trace) = (
+        p.resize::<{ Wide::LIMBS }>(),
+        trace.resize::<{ Wide::LIMBS }>(),
+    );
+    let magnitude = p.shl_vartime(2).wrapping_sub(&trace.wrapping_mul(&trace));
+    let (factorization, rest) = primality::trial_factor(magnitude, TRIAL_BOUND);
+    let squarefree = factorization
+        .iter()
+        .filter(|(_, exponent)| exponent % 2 == 1)
+        .fold(Wide::ONE, |product, (prime, _)| {
+            product.wrapping_mul(&Wide::from(*prime))
+        });
+    let root = rest.sqrt_vartime();
+    let (squarefree, exact) = if root.wrapping_mul(&root) == rest {
+        (squarefree, true)
+    } else {
+        (
+            squarefree.wrapping_mul(&rest),
+            primality::baillie_psw(&rest),
+        )
+    };
+    // D = -squarefree when it's 1 mod 4 and -4.squarefree otherwise
+    let fundamental = if squarefree.as_words()[0] % 4 == 3 {
+        squarefree
+    } else {
+        squarefree.shl_vartime(2)
+    };
+    (log2(&fundamental), exact)
+}
+
+/// `None` when the part left after trial division is composite
+fn largest_prime_factor(n: &Ufeat) -> Option<Ufeat> {
+    let (factorization, rest) = primality::trial_factor(*n, TRIAL_BOUND);
+    if rest == Ufeat::ONE {
+        factorization.last().map(|(prime, _)| Ufeat::from(*prime))
+    } else if primality::baillie_psw(&rest) {
+        Some(rest)
+    } else {
+        None
+    }
+}
+
+impl fmt::Display for AuditReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let verdict = |passed: bool| if passed { "ok" } else { "UNSAFE" };
+        let embedding_degree = match self.embedding_degree {
+            Some(k) => k.to_string(),
+            None => format!("> {EMBEDDING_DEGREE_BOUND}"),
+        };
+        let twist = match self.twist_rho_cost_bits {
+            Some(bits) => format!("2^{bits:.1}"),
+            None => "unknown, the twist order isn't factored".to_string(),
+        };
+        write!(
+            f,
+            "< Security Audit of {} >
+-------------------------
+    p prime: {} [{}]
+    order prime: {} [{}]
+    cofactor: {:#x}
+    anomalous: {} [{}]
+    Smart's attack: {}
+    embedding degree: {} [{}]
+    CM discriminant: {}2^{:.1} [{}]
+    rho cost: 2^{:.1} [{}]
+    twist rho cost: {} [{}]
+    safe: {}",
+            self.name,
+            self.p_prime,
+            verdict(self.p_prime),
+            self.order_prime,
+            verdict(self.order_prime),
+            self.cofactor,
+            self.anomalous,
+            verdict(!self.anomalous),
+            match self.smart_attack {
+                Some(true) => "breaks it",
+                Some(false) => "failed",
+                None => "not applicable",
+            },
+            embedding_degree,
+            verdict(self.embedding_degree.is_none()),
+            if self.cm_discriminant_exact {
+                ""
+            } else {
+                "at most "
+            },
+            self.cm_discriminant_bits,
+            verdict(self.cm_discriminant_bits > SECURITY_BITS),
+            self.rho_cost_bits,
+            verdict(self.rho_cost_bits > SECURITY_BITS),
+            twist,
+            verdict(
+                self.twist_rho_cost_bits
+                    .is_some_and(|bits| bits > SECURITY_BITS)
+            ),
+            self.is_safe()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::curves;
+
+    #[test]
+    fn flags_an_anomalous_curve() {
+        let curve = EllipticCurve::try_new_anomalous(
+            "anomalous".to_string(),
+            Ufeat::from(10007u16),
+            Ufeat::from(10007u16),
+            [0, 22, 9],
+        )
+        .unwrap();
+        let report = curve.audit();
+        assert!(report.anomalous);
+        assert_eq!(report.smart_attack, Some(true));
+        assert!(!report.is_safe());
+    }
+
+    #[test]
+    fn flags_a_supersingular_curve() {
+        // y^2 = x^3 + x has p + 1 = 2^3.3^2.139 points for p = 3 mod 4, and p = -1 mod 139
+        let curve = EllipticCurve::new(
+            "supersingular".to_string(),
+            Ufeat::from(139u8),
+            Ufeat::from(10007u16),
+            [0, 1, 0],
+        );
+        let report = curve.audit();
+        assert_eq!(report.embedding_degree, Some(2));
+        assert_eq!(report.cofactor, Ufeat::from(72u8));
+        assert!(report.order_prime && !report.anomalous);
+        assert_eq!(report.smart_attack, None);
+        // the twist has p + 1 points too, and its largest prime factor is as small
+        assert_eq!(report.twist_order, Ufeat::from(10008u16));
+        assert_eq!(
+            report.twist_rho_cost_bits,
+            Some(rho_cost_bits(&Ufeat::from(139u8)))
+        );
+        assert!(!report.is_safe());
+    }
+
+    #[test]
+    fn flags_a_small_largest_prime_factor() {
+        // the same curve taken with a composite `order`, whose largest prime factor is what rho actually faces
+        let curve = EllipticCurve::new(
+            "composite order".to_string(),
+            Ufeat::from(1251u16),
+            Ufeat::from(10007u16),
+            [0, 1, 0],
+        );
+        let report = curve.audit();
+        assert!(!report.order_prime);
+        assert_eq!(
+            largest_prime_factor(curve.order()),
+            Some(Ufeat::from(139u8))
+        );
+        assert!(report.rho_cost_bits < SECURITY_BITS);
+        assert!(!report.is_safe());
+    }
+
+    #[test]
+    fn passes_secp256k1_but_its_cm_discriminant() {
+        let report = curves::secp256k1().audit();
+        assert!(report.p_prime && report.order_prime && !report.anomalous);
+        assert_eq!(report.cofactor, Ufeat::ONE);
+        assert_eq!(report.embedding_degree, None);
+        assert!(report.rho_cost_bits > SECURITY_BITS);
+        // j = 0: the endomorphism ring has discriminant -3
+        assert!(report.cm_discriminant_exact);
+        assert_eq!(report.cm_discriminant_bits, 3f64.log2());
+        assert!(!report.is_safe());
+    }
+}
@@ -0,0 +1,436 @@
+//! BLS signatures of draft-irtf-cfrg-bls-signature on BLS12-381, the variant with the public keys in G1 and the
+//! signatures in G2
+//!
+//! A signature is $sk.H(m)$ and verifies when $e(pk, H(m)) = e(g_1, \sigma)$; the signatures of any messages under any
+//! keys add up to one which verifies against all the couples at once. The basic scheme requires the aggregated messages
+//! to be distinct, against rogue keys chosen to cancel the others; the scheme with proofs of possession has every key
+//! sign itself instead, and then many signatures of one message verify against the sum of the keys.
+//!
+//! The ciphersuites are `BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_NUL_` and `BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_POP_`,
+//! hashing to G2 with the SSWU suite of `bls12_381`.
+use std::fmt;
+
+use crypto_bigint::Encoding;
+use sha2::{Digest, Sha256};
+
+use super::{
+    bls12_381::Bls12_381,
+    extension::{Field, Fp, Fp2},
+    PointOver, ProtocolError, Ufeat,
+};
+
+/// The salt `KeyGen` starts hashing from
+const KEYGEN_SALT: &[u8] = b"BLS-SIG-KEYGEN-SALT-";
+/// The bytes `KeyGen` reduces modulo `r`: 16 more than `r` takes
+const KEYGEN_BYTES: usize = 48;
+
+/// The variants of the draft this module implements
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Scheme {
+    /// Aggregation of distinct messages only
+    Basic,
+    /// Keys come with a proof of possession, so a message signed many times verifies against the aggregated keys
+    ProofOfPossession,
+}
+
+impl Scheme {
+    /// The domain separation tag of the signatures
+    pub fn dst(&self) -> &'static [u8] {
+        match self {
+            Scheme::Basic => b"BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_NUL_",
+            Scheme::ProofOfPossession => b"BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_POP_",
+        }
+    }
+}
+
+/// The domain separation tag of the proofs of possession
+pub const POP_DST: &[u8] = b"BLS_POP_BLS12381G2_XMD:SHA-256_SSWU_RO_POP_";
+
+/// A scalar in `1..r`; its `Debug` doesn't show it
+#[derive(Clone, PartialEq, Eq)]
+pub struct SecretKey(Ufeat);
+
+impl fmt::Debug for SecretKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("SecretKey(..)")
+    }
+}
+#[cfg(feature = "zeroize")]
+impl zeroize::Zeroize for SecretKey {
+    fn zeroize(&mut self) {
+        self.0.zeroize();
+    }
+}
+#[cfg(feature = "zeroize")]
+impl Drop for SecretKey {
+    fn drop(&mut self) {
+        zeroize::Zeroize::zeroize(self);
+    }
+}
+#[cfg(feature = "zeroize")]
+impl zeroize::ZeroizeOnDrop for SecretKey {}
+
+/// A point of G1; `Bls::key_validate` tells whether it's a valid key
+#[derive(Clone, Debug, PartialEq)]
+pub struct PublicKey(pub PointOver<Fp>);
+
+/// A point of G2, checked to be in the subgroup on verification
+#[derive(Clone, Debug, PartialEq)]
+pub struct Signature(pub PointOver<Fp2>);
+
+/// A scheme on the curve, which is set up once
+#[derive(Clone, Debug)]
+pub struct Bls {
+    curve: Bls12_381,
+    scheme: Scheme,
+}
+
+impl Bls {
+    pub fn new(scheme: Scheme) -> Self {
+        Bls {
+            curve: Bls12_381::new(),
+            scheme,
+        }
+    }
+    pub fn curve(&self) -> &Bls12_381 {
+        &self.curve
+    }
+    pub fn scheme(&self) -> Scheme {
+        self.scheme
+    }
+
+    /// `KeyGen` of the draft: the secret key derived with HKDF-SHA-256 from at least 32 bytes of `ikm`, which should be
+    /// uniformly random, and the optional `key_info`
+    pub fn key_gen(&self, ikm: &[u8], key_info: &[u8]) -> Result<SecretKey, ProtocolError> {
+        if ikm.len() < 32 {
+            return Err(ProtocolError::ShortKeyMaterial { length: ikm.len() });
+        }
+        let order = self.curve.order();
+        let mut salt = KEYGEN_SALT.to_vec();
+        loop {
+            salt = Sha256::digest(&salt).to_vec();
+            let prk = hmac(&salt, &[ikm, &[0]].concat());
+            let info = [key_info, &(KEYGEN_BYTES as u16).to_be_bytes()].concat();
+            let okm = hkdf_expand(&prk, &info, KEYGEN_BYTES);
+            let mut buffer = vec![0u8; Ufeat::BYTES];
+            buffer[Ufeat::BYTES - KEYGEN_BYTES..].copy_from_slice(&okm);
+            let scalar = Ufeat::from_be_slice(&buffer).wrapping_rem(order);
+            if scalar != Ufeat::ZERO {
+                return Ok(SecretKey(scalar));
+            }
+        }
+    }
+
+    /// `SkToPk`
+    pub fn public_key(&self, secret: &SecretKey) -> PublicKey {
+        PublicKey(self.curve.g1() * secret.0)
+    }
+
+    /// `KeyValidate`: the key isn't the identity and is in G1
+    pub fn key_validate(&self, key: &PublicKey) -> Result<(), ProtocolError> {
+        if key.0.is_infinity() {
+            return Err(ProtocolError::Identity { role: "public key" });
+        }
+        if !self.curve.is_in_g1(&key.0) {
+            return Err(ProtocolError::NotInSubgroup {
+                role: "public key",
+                curve: key.0.curve().name.clone(),
+            });
+        }
+        Ok(())
+    }
+
+    pub fn sign(&self, secret: &SecretKey, message: &[u8]) -> Signature {
+        Signature(&self.curve.hash_to_g2(message, self.scheme.dst()) * secret.0)
+    }
+
+    pub fn verify(
+        &self,
+        key: &PublicKey,
+        message: &[u8],
+        signature: &Signature,
+    ) -> Result<(), ProtocolError> {
+        self.core_verify(&[(key, message)], signature, self.scheme.dst())
+    }
+
+    /// The sum of the signatures, refusing an empty list and the points off the twist
+    pub fn aggregate(&self, signatures: &[Signature]) -> Result<Signature, ProtocolError> {
+        let (first, rest) = signatures
+            .split_first()
+            .ok_or(ProtocolError::InvalidSignature)?;
+        if **first.0.curve() != **self.curve.g2_curve() {
+            return Err(ProtocolError::NotInSubgroup {
+                role: "signature",
+                curve: first.0.curve().name.clone(),
+            });
+        }
+        let mut sum = first.0.clone();
+        for signature in rest {
+            sum = (&sum + &signature.0)?;
+        }
+        Ok(Signature(sum))
+    }
+
+    /// Verifies the aggregate of the signatures of each message under its key; the basic scheme refuses repeated
+    /// messages
+    pub fn aggregate_verify(
+        &self,
+        signed: &[(&PublicKey, &[u8])],
+        signature: &Signature,
+    ) -> Result<(), ProtocolError> {
+        if self.scheme == Scheme::Basic {
+            let mut messages: Vec<_> = signed.iter().map(|(_, message)| message).collect();
+            messages.sort();
+            if messages.windows(2).any(|pair| pair[0] == pair[1]) {
+                return Err(ProtocolError::DuplicateMessage);
+            }
+        }
+        self.core_verify(signed, signature, self.scheme.dst())
+    }
+
+    /// `PopProve`: the key signs its own encoding
+    pub fn prove_possession(&self, secret: &SecretKey) -> Result<Signature, ProtocolError> {
+        self.require_pop("proof of possession")?;
+        let key = self.public_key(secret);
+        Ok(Signature(
+            &self.curve.hash_to_g2(&encode(&key.0), POP_DST) * secret.0,
+        ))
+    }
+
+    /// `PopVerify`
+    pub fn verify_possession(
+        &self,
+        key: &PublicKey,
+        proof: &Signature,
+    ) -> Result<(), ProtocolError> {
+        self.require_pop("proof of possession")?;
+        self.core_verify(&[(key, &encode(&key.0))], proof, POP_DST)
+    }
+
+    /// `FastAggregateVerify`: the aggregate of signatures of one message verified against the sum of the keys, which is
+    /// sound only when the keys proved possession
+    pub fn fast_aggregate_verify(
+        &self,
+        keys: &[&PublicKey],
+        message: &[u8],
+        signature: &Signature,
+    ) -> Result<(), ProtocolError> {
+        self.require_pop("fast aggregate verification")?;
+        let (first, rest) = keys.split_first().ok_or(ProtocolError::InvalidSignature)?;
+        let mut sum = first.0.clone();
+        for key in rest {
+            sum = (&sum + &key.0)?;
+        }
+        self.core_verify(&[(&PublicKey(sum), message)], signature, self.scheme.dst())
+    }
+
+    fn require_pop(&self, operation: &'static str) -> Result<(), ProtocolError> {
+        match self.scheme {
+            Scheme::ProofOfPossession => Ok(()),
+            Scheme::Basic => Err(ProtocolError::Unsupported { operation }),
+        }
+    }
+
+    /// `CoreAggregateVerify`: $\prod e(pk_i, H(m_i)) . e(-g_1, \sigma) = 1$ with a single final exponentiation
+    fn core_verify(
+        &self,
+        signed: &[(&PublicKey, &[u8])],
+        signature: &Signature,
+        dst: &[u8],
+    ) -> Result<(), ProtocolError> {
+        if signed.is_empty() {
+            return Err(ProtocolError::InvalidSignature);
+        }
+        if !self.curve.is_in_g2(&signature.0) {
+            return Err(ProtocolError::NotInSubgroup {
+                role: "signature",
+                curve: signature.0.curve().name.clone(),
+            });
+        }
+        let hashes: Vec<_> = signed
+            .iter()
+            .map(|(key, message)| {
+                self.key_validate(key)?;
+                Ok(self.curve.hash_to_g2(message, dst))
+            })
+            .collect::<Result<_, ProtocolError>>()?;
+        let generator = -self.curve.g1();
+        let mut pairs: Vec<_> = signed
+            .iter()
+            .zip(&hashes)
+            .map(|((key, _), hash)| (&key.0, hash))
+            .collect();
+        pairs.push((&generator, &signature.0));
+        let product = self.curve.multi_pairing(&pairs)?;
+        if product == product.one_like() {
+            Ok(())
+        } else {
+            Err(ProtocolError::InvalidSignature)
+        }
+    }
+}
+
+/// The compressed encoding of a point of G1 the draft signs as a proof of possession, that of ZCash: `x` in 48
+/// big-endian bytes, whose top bits flag the compression, infinity, and `y` over $(p - 1) / 2$
+fn encode(point: &PointOver<Fp>) -> Vec<u8> {
+    const COORDINATE_BYTES: usize = 48;
+    const COMPRESSED: u8 = 0x80;
+    const INFINITY: u8 = 0x40;
+    const LARGEST: u8 = 0x20;
+    let mut bytes = vec![0u8; COORDINATE_BYTES];
+    match point.x().zip(point.y()) {
+        None => bytes[0] = COMPRESSED | INFINITY,
+        Some((x, y)) => {
+            bytes.copy_from_slice(&x.retrieve().to_be_bytes()[Ufeat::BYTES - COORDINATE_BYTES..]);
+            bytes[0] |= COMPRESSED;
+            if y.retrieve() > (-*y).retrieve() {
+                bytes[0] |= LARGEST;
+            }
+        }
+    }
+    bytes
+}
+
+/// HMAC-SHA-256 of RFC 2104
+fn hmac(key: &[u8], message: &[u8]) -> Vec<u8> {
+    const BLOCK: usize = 64;
+    let mut padded = [0u8; BLOCK];
+    if key.len() > BLOCK {
+        padded[..32].copy_from_slice(&Sha256::digest(key));
+    } else {
+        padded[..key.len()].copy_from_slice(key);
+    }
+    let pad = |byte: u8| padded.iter().map(|k| k ^ byte).collect::<Vec<_>>();
+    let inner = Sha256::new()
+        .chain_update(pad(0x36))
+        .chain_update(message)
+        .finalize();
+    Sha256::new()
+        .chain_update(pad(0x5c))
+        .chain_update(inner)
+        .finalize()
+        .to_vec()
+}
+
+/// HKDF-Expand of RFC 5869 with SHA-256
+fn hkdf_expand(prk: &[u8], info: &[u8], length: usize) -> Vec<u8> {
+    let mut okm = Vec::with_capacity(length);
+    let mut block = Vec::new();
+    for i in 1..=length.div_ceil(32) as u8 {
+        block = hmac(prk, &[&block[..], info, &[i]].concat());
+        okm.extend(&block);
+    }
+    okm.truncate(length);
+    okm
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::encoding::parse_hex;
+
+    /// The vectors of the basic scheme the `bls-signatures` crate checks: the message, the secret key, then the
+    /// compressed public key and signature
+    const BASIC_VECTORS: [[&str; 4]; 4] = [
+        [
+            "",
+            "3ce2e976962a07ab68ccfa29194968dbb6c917c041d44bfc1c9f1a671017f70e",
+            "b2be11dc8e54ee74dbc07569fd74fe03b5f52ad71cd49a8579b6c6387891f5a20ad980ec2747618c1b9ad35846a68a3e",
+            "b53cfdf8b488a286df1ed20432e2bbc4e6361003757dfda3a4fd6cd98de95e5513f7c448d70b2681e14547a6ced47e7c10e28432e8abcb34de1dc28f39328fd2a13db12a4c6a30bd17b0e42881a429003e4c24583ba0f29a40fd836cf05e1a40",
+        ],
+        [
+            "1234",
+            "6de2989580e8210501e005a7e45f645fc525518d4d2acf1b7fce5852d5d3fe5f",
+            "981de2d88a80a2d7752ecda66443340a789ea62dd68dca6a3a8caf3b6c1e94248a8819a4f6ba554f50f5ccb8bc40e67c",
+            "84aa59cad078a34c3c1f876e924ee199cd8cf74857cebcad3037561964cfda50dce5f4d0709aa690dae7113b01a9c8c31557f5589c38eb720e86864ff0c4446fba21899d4cd0b2862ec395de1dfdb736bf38ca56d17019b257c5d4dd563bf5b7",
+        ],
+        [
+            "4fb3300556a1b2e3978f5da60cb0708b4d41385a6008976a88527d418d21cf9e",
+            "16696c2bb32968007ca92ec631286349ec19eddb461605c7eaa1d88e08dd356d",
+            "a31e3adf1fd542e00c8ae3d4dafea1133c09cd71723584b159cfb78ab5e1bad97f955b891f761c79fa8215b8c53addf7",
+            "8d4512fabe80f7fb68726794a86b2e20cd04b5787aecfbf43b02c8eb67e42b60b797eba0489c4eb1165e4391f31ee8600af43a3d57e6bfdbb950564916f6b1c57e4ef7fba312fd7db431aaec906fa38acefe90687d3755cb3d10b19901bd0074",
+        ],
+        [
+            "a5c5307ab61fa939de5337f3624ae537dab99065dd89e18bdf9ca95304886d0490580dafece6f9a25c2c2fc61ba0f0195b73693d97757006f62602544545aafb",
+            "228e0d1407619ba12a2e7396d48aa4f75111811bc20a97d5177bb51fc96ecb4f",
+            "884d99448e1913ab4b2ad5d674dff868c2a0dfee9a5727b97045a6861ca2e25402610e03dc564e62de868a1d83e91589",
+            "aa28a8ed7618157c0a7c16889e33ba5fac0a277759bc7b3be4c6c41a8e235d9c58fae064752dd7978b0422ddc67687c6082ccf6f1f5d9161bb5f3396fd486979215a7846e7659442f7895a1770c5ace587ea2806370f211d3737ed8b710c037d",
+        ],
+    ];
+
+    /// The compressed encoding of a point of G2 as `encode` does for G1: `c1` before `c0`, and `y` compared by `c1`
+    /// first
+    fn encode2(point: &PointOver<Fp2>) -> Vec<u8> {
+        let (x, y) = point.x().zip(point.y()).unwrap();
+        let coefficients = |c: &Fp2| {
+            let [c0, c1] = [c.c0(), c.c1()].map(|c| c.retrieve());
+            (c1, c0)
+        };
+        let tail = |n: Ufeat| n.to_be_bytes()[Ufeat::BYTES - 48..].to_vec();
+        let (x1, x0) = coefficients(x);
+        let mut bytes = [tail(x1), tail(x0)].concat();
+        bytes[0] |= 0x80;
+        if coefficients(y) > coefficients(&-*y) {
+            bytes[0] |= 0x20;
+        }
+        bytes
+    }
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+    }
+
+    #[test]
+    fn basic_scheme_matches_the_vectors() {
+        let bls = Bls::new(Scheme::Basic);
+        for (index, [message, secret, public, signature]) in BASIC_VECTORS.into_iter().enumerate() {
+            let message = message.as_bytes();
+            let secret = SecretKey(parse_hex(secret).unwrap());
+            let key = bls.public_key(&secret);
+            assert_eq!(hex(&encode(&key.0)), public);
+            let signed = bls.sign(&secret, message);
+            assert_eq!(hex(&encode2(&signed.0)), signature);
+            // the pairings take most of the time unoptimized, and the encodings already pin the signatures down
+            if index == 0 {
+                bls.verify(&key, message, &signed).unwrap();
+                assert!(bls.verify(&key, b"other", &signed).is_err());
+            }
+        }
+    }
+
+    #[test]
+    fn pop_scheme_matches_the_ethereum_vectors() {
+        let bls = Bls::new(Scheme::ProofOfPossession);
+        let secret = SecretKey(
+            parse_hex("263dbd792f5b1be47ed85f8938c0f29586af0d3ac7b977f21c278fe1462040e3").unwrap(),
+        );
+        let key = bls.public_key(&secret);
+        assert_eq!(
+            hex(&encode(&key.0)),
+            "a491d1b0ecd9bb917989f0e74f0dea0422eac4a873e5e2644f368dffb9a6e20fd6e10c1b77654d067c0618f6e5a7f79a"
+        );
+        for (message, signature) in [
+            ([0; 32], "b6ed936746e01f8ecf281f020953fbf1f01debd5657c4a383940b020b26507f6076334f91e2366c96e9ab279fb5158090352ea1c5b0c9274504f4f0e7053af24802e51e4568d164fe986834f41e55c8e850ce1f98458c0cfc9ab380b55285a55"),
+            ([0x56; 32], "882730e5d03f6b42c3abc26d3372625034e1d871b65a8a6b900a56dae22da98abbe1b68f85e49fe7652a55ec3d0591c20767677e33e5cbb1207315c41a9ac03be39c2e7668edc043d6cb1d9fd93033caa8a1c5b0e84bedaeb6c64972503a43eb"),
+        ] {
+            let signed = bls.sign(&secret, &message);
+            assert_eq!(hex(&encode2(&signed.0)), signature);
+        }
+        let proof = bls.prove_possession(&secret).unwrap();
+        bls.verify_possession(&key, &proof).unwrap();
+    }
+
+    #[cfg(feature = "zeroize")]
+    #[test]
+    fn zeroize_wipes_the_secret_key() {
+        use zeroize::Zeroize;
+
+        let bls = Bls::new(Scheme::Basic);
+        let mut key = bls.key_gen(&[7; 32], b"").unwrap();
+        assert!(!bls.public_key(&key).0.is_infinity());
+        key.zeroize();
+        // the zero key, whose public key is the identity
+        assert_eq!(key.0, Ufeat::ZERO);
+        assert!(bls.public_key(&key).0.is_infinity());
+    }
+}
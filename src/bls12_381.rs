@@ -0,0 +1,657 @@
+//! BLS12-381: G1 over $F_p$, G2 over $F_{p^2}$, the optimal ate pairing and hashing to both groups
+//!
+//! The curve is $y^2 = x^3 + 4$ with the parameter $x = -$`X`, $r = x^4 - x^2 + 1$ and $p = (x - 1)^2 r / 3 + x$. G2
+//! lives on the M-type sextic twist $y^2 = x^3 + 4(u + 1)$ over $F_{p^2} = F_p[u] / (u^2 + 1)$, and the pairing in
+//! the tower $F_{p^{12}}$ of `extension` with $\xi = u + 1$, where $w^6 = \xi$ untwists a point of G2 onto the curve.
+//!
+//! Hashing follows RFC 9380 with the suites `BLS12381G1_XMD:SHA-256_SSWU_RO_` and `BLS12381G2_XMD:SHA-256_SSWU_RO_`
+//! of its section 8.8, the ones the BLS signature standard settled on: the simplified SWU map needs $AB \ne 0$, so it
+//! lands on a curve 11-isogenous to G1's, or 3-isogenous to G2's, and the isogeny tabulated in appendix E brings the
+//! point back.
+//!
+//! Nothing here is constant time, the scalar multiplications included.
+use std::rc::Rc;
+
+use crypto_bigint::modular::runtime_mod::DynResidue;
+use sha2::{Digest, Sha256};
+
+use super::{
+    curves,
+    encoding::parse_hex,
+    extension::{Exponent, Field, Fp, Fp12, Fp2, Fp6},
+    pairing, CurveOver, PointError, PointOver, Ufeat,
+};
+
+/// The absolute value of the curve parameter $x$, which is negative; it has only 6 bits set, which makes the Miller loop
+/// short and sparse
+pub const X: u64 = 0xd201000000010000;
+/// The bytes of a hash to a field element: 16 more than $p$ takes, so that the bias of the reduction is negligible
+const FIELD_HASH_BYTES: usize = 64;
+
+/// $A'$ and $B'$ of the curve 11-isogenous to G1, RFC 9380 section 8.8.1
+const ISOGENOUS_11: [&str; 2] = [
+    "00144698a3b8e9433d693a02c96d4982b0ea985383ee66a8d8e8981aefd881ac98936f8da0e0f97f5cf428082d584c1d",
+    "12e2908d11688030018b12e8753eee3b2016c1f0f24f4070a0b9c14fcef35ef55a23215a316ceaa5d1cc48e98e172be0",
+];
+/// The 11-isogeny of RFC 9380 appendix E.2 onto G1: the coefficients of the numerator and the denominator of `x`, then
+/// those of `y`, lowest degree first
+const ISOGENY_11: [&[&str]; 4] = [
+    &[
+        "11a05f2b1e833340b809101dd99815856b303e88a2d7005ff2627b56cdb4e2c85610c2d5f2e62d6eaeac1662734649b7",
+        "17294ed3e943ab2f0588bab22147a81c7c17e75b2f6a8417f565e33c70d1e86b4838f2a6f318c356e834eef1b3cb83bb",
+        "0d54005db97678ec1d1048c5d10a9a1bce032473295983e56878e501ec68e25c958c3e3d2a09729fe0179f9dac9edcb0",
+        "1778e7166fcc6db74e0609d307e55412d7f5e4656a8dbf25f1b33289f1b330835336e25ce3107193c5b388641d9b6861",
+        "0e99726a3199f4436642b4b3e4118e5499db995a1257fb3f086eeb65982fac18985a286f301e77c451154ce9ac8895d9",
+        "1630c3250d7313ff01d1201bf7a74ab5db3cb17dd952799b9ed3ab9097e68f90a0870d2dcae73d19cd13c1c66f652983",
+        "0d6ed6553fe44d296a3726c38ae652bfb11586264f0f8ce19008e218f9c86b2a8da25128c1052ecaddd7f225a139ed84",
+        "17b81e7701abdbe2e8743884d1117e53356de5ab275b4db1a682c62ef0f2753339b7c8f8c8f475af9ccb5618e3f0c88e",
+        "080d3cf1f9a78fc47b90b33563be990dc43b756ce79f5574a2c596c928c5d1de4fa295f296b74e956d71986a8497e317",
+        "169b1f8e1bcfa7c42e0c37515d138f22dd2ecb803a0c5c99676314baf4bb1b7fa3190b2edc0327797f241067be390c9e",
+        "10321da079ce07e272d8ec09d2565b0dfa7dccdde6787f96d50af36003b14866f69b771f8c285decca67df3f1605fb7b",
+        "06e08c248e260e70bd1e962381edee3d31d79d7e22c837bc23c0bf1bc24c6b68c24b1b80b64d391fa9c8ba2e8ba2d229",
+    ],
+    &[
+        "08ca8d548cff19ae18b2e62f4bd3fa6f01d5ef4ba35b48ba9c9588617fc8ac62b558d681be343df8993cf9fa40d21b1c",
+        "12561a5deb559c4348b4711298e536367041e8ca0cf0800c0126c2588c48bf5713daa8846cb026e9e5c8276ec82b3bff",
+        "0b2962fe57a3225e8137e629bff2991f6f89416f5a718cd1fca64e00b11aceacd6a3d0967c94fedcfcc239ba5cb83e19",
+        "03425581a58ae2fec83aafef7c40eb545b08243f16b1655154cca8abc28d6fd04976d5243eecf5c4130de8938dc62cd8",
+        "13a8e162022914a80a6f1d5f43e7a07dffdfc759a12062bb8d6b44e833b306da9bd29ba81f35781d539d395b3532a21e",
+        "0e7355f8e4e667b955390f7f0506c6e9395735e9ce9cad4d0a43bcef24b8982f7400d24bc4228f11c02df9a29f6304a5",
+        "0772caacf16936190f3e0c63e0596721570f5799af53a1894e2e073062aede9cea73b3538f0de06cec2574496ee84a3a",
+        "14a7ac2a9d64a8b230b3f5b074cf01996e7f63c21bca68a81996e1cdf9822c580fa5b9489d11e2d311f7d99bbdcc5a5e",
+        "0a10ecf6ada54f825e920b3dafc7a3cce07f8d1d7161366b74100da67f39883503826692abba43704776ec3a79a1d641",
+        "095fc13ab9e92ad4476d6e3eb3a56680f682b4ee96f7d03776df533978f31c1593174e4b4b7865002d6384d168ecdd0a",
+        "000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000001",
+    ],
+    &[
+        "090d97c81ba24ee0259d1f094980dcfa11ad138e48a869522b52af6c956543d3cd0c7aee9b3ba3c2be9845719707bb33",
+        "134996a104ee5811d51036d776fb46831223e96c254f383d0f906343eb67ad34d6c56711962fa8bfe097e75a2e41c696",
+        "00cc786baa966e66f4a384c86a3b49942552e2d658a31ce2c344be4b91400da7d26d521628b00523b8dfe240c72de1f6",
+        "01f86376e8981c217898751ad8746757d42aa7b90eeb791c09e4a3ec03251cf9de405aba9ec61deca6355c77b0e5f4cb",
+        "08cc03fdefe0ff135caf4fe2a21529c4195536fbe3ce50b879833fd221351adc2ee7f8dc099040a841b6daecf2e8fedb",
+        "16603fca40634b6a2211e11db8f0a6a074a7d0d4afadb7bd76505c3d3ad5544e203f6326c95a807299b23ab13633a5f0",
+        "04ab0b9bcfac1bbcb2c977d027796b3ce75bb8ca2be184cb5231413c4d634f3747a87ac2460f415ec961f8855fe9d6f2",
+        "0987c8d5333ab86fde9926bd2ca6c674170a05bfe3bdd81ffd038da6c26c842642f64550fedfe935a15e4ca31870fb29",
+        "09fc4018bd96684be88c9e221e4da1bb8f3abd16679dc26c1e8b6e6a1f20cabe69d65201c78607a360370e577bdba587",
+        "0e1bba7a1186bdb5223abde7ada14a23c42a0ca7915af6fe06985e7ed1e4d43b9b3f7055dd4eba6f2bafaaebca731c30",
+        "19713e47937cd1be0dfd0b8f1d43fb93cd2fcbcb6caf493fd1183e416389e61031bf3a5cce3fbafce813711ad011c132",
+        "18b46a908f36f6deb918c143fed2edcc523559b8aaf0c2462e6bfe7f911f643249d9cdf41b44d606ce07c8a4d0074d8e",
+        "0b182cac101b9399d155096004f53f447aa7b12a3426b08ec02710e807b4633f06c851c1919211f20d4c04f00b971ef8",
+        "0245a394ad1eca9b72fc00ae7be315dc757b3b080d4c158013e6632d3c40659cc6cf90ad1c232a6442d9d3f5db980133",
+        "05c129645e44cf1102a159f748c4a3fc5e673d81d7e86568d9ab0f5d396a7ce46ba1049b6579afb7866b1e715475224b",
+        "15e6be4e990f03ce4ea50b3b42df2eb5cb181d8f84965a3957add4fa95af01b2b665027efec01c7704b456be69c8b604",
+    ],
+    &[
+        "16112c4c3a9c98b252181140fad0eae9601a6de578980be6eec3232b5be72e7a07f3688ef60c206d01479253b03663c1",
+        "1962d75c2381201e1a0cbd6c43c348b885c84ff731c4d59ca4a10356f453e01f78a4260763529e3532f6102c2e49a03d",
+        "058df3306640da276faaae7d6e8eb15778c4855551ae7f310c35a5dd279cd2eca6757cd636f96f891e2538b53dbf67f2",
+        "16b7d288798e5395f20d23bf89edb4d1d115c5dbddbcd30e123da489e726af41727364f2c28297ada8d26d98445f5416",
+        "0be0e079545f43e4b00cc912f8228ddcc6d19c9f0f69bbb0542eda0fc9dec916a20b15dc0fd2ededda39142311a5001d",
+        "08d9e5297186db2d9fb266eaac783182b70152c65550d881c5ecd87b6f0f5a6449f38db9dfa9cce202c6477faaf9b7ac",
+        "166007c08a99db2fc3ba8734ace9824b5eecfdfa8d0cf8ef5dd365bc400a0051d5fa9c01a58b1fb93d1a1399126a775c",
+        "16a3ef08be3ea7ea03bcddfabba6ff6ee5a4375efa1f4fd7feb34fd206357132b920f5b00801dee460ee415a15812ed9",
+        "1866c8ed336c61231a1be54fd1d74cc4f9fb0ce4c6af5920abc5750c4bf39b4852cfe2f7bb9248836b233d9d55535d4a",
+        "167a55cda70a6e1cea820597d94a84903216f763e13d87bb5308592e7ea7d4fbc7385ea3d529b35e346ef48bb8913f55",
+        "04d2f259eea405bd48f010a01ad2911d9c6dd039bb61a6290e591b36e636a5c871a5c29f4f83060400f8b49cba8f6aa8",
+        "0accbb67481d033ff5852c1e48c50c477f94ff8aefce42d28c0f9a88cea7913516f968986f7ebbea9684b529e2561092",
+        "0ad6b9514c767fe3c3613144b45f1496543346d98adf02267d5ceef9a00d9b8693000763e3b90ac11e99b138573345cc",
+        "02660400eb2e4f3b628bdd0d53cd76f2bf565b94e72927c1cb748df27942480e420517bd8714cc80d1fadc1326ed06f7",
+        "0e0fa1d816ddc03e6b24255e0d7819c171c40f65e273b853324efcd6356caa205ca2f570f13497804415473a1d634b8f",
+        "000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000001",
+    ],
+];
+/// The 3-isogeny of RFC 9380 appendix E.3 onto G2, as `ISOGENY_11` with the coordinates of the coefficients
+const ISOGENY_3: [&[[&str; 2]]; 4] = [
+    &[
+        ["05c759507e8e333ebb5b7a9a47d7ed8532c52d39fd3a042a88b58423c50ae15d5c2638e343d9c71c6238aaaaaaaa97d6", "05c759507e8e333ebb5b7a9a47d7ed8532c52d39fd3a042a88b58423c50ae15d5c2638e343d9c71c6238aaaaaaaa97d6"],
+        ["000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000", "11560bf17baa99bc32126fced787c88f984f87adf7ae0c7f9a208c6b4f20a4181472aaa9cb8d555526a9ffffffffc71a"],
+        ["11560bf17baa99bc32126fced787c88f984f87adf7ae0c7f9a208c6b4f20a4181472aaa9cb8d555526a9ffffffffc71e", "08ab05f8bdd54cde190937e76bc3e447cc27c3d6fbd7063fcd104635a790520c0a395554e5c6aaaa9354ffffffffe38d"],
+        ["171d6541fa38ccfaed6dea691f5fb614cb14b4e7f4e810aa22d6108f142b85757098e38d0f671c7188e2aaaaaaaa5ed1", "000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000"],
+    ],
+    &[
+        ["000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000", "1a0111ea397fe69a4b1ba7b6434bacd764774b84f38512bf6730d2a0f6b0f6241eabfffeb153ffffb9feffffffffaa63"],
+        ["00000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000c", "1a0111ea397fe69a4b1ba7b6434bacd764774b84f38512bf6730d2a0f6b0f6241eabfffeb153ffffb9feffffffffaa9f"],
+        ["000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000001", "000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000"],
+    ],
+    &[
+        ["1530477c7ab4113b59a4c18b076d11930f7da5d4a07f649bf54439d87d27e500fc8c25ebf8c92f6812cfc71c71c6d706", "1530477c7ab4113b59a4c18b076d11930f7da5d4a07f649bf54439d87d27e500fc8c25ebf8c92f6812cfc71c71c6d706"],
+        ["000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000", "05c759507e8e333ebb5b7a9a47d7ed8532c52d39fd3a042a88b58423c50ae15d5c2638e343d9c71c6238aaaaaaaa97be"],
+        ["11560bf17baa99bc32126fced787c88f984f87adf7ae0c7f9a208c6b4f20a4181472aaa9cb8d555526a9ffffffffc71c", "08ab05f8bdd54cde190937e76bc3e447cc27c3d6fbd7063fcd104635a790520c0a395554e5c6aaaa9354ffffffffe38f"],
+        ["124c9ad43b6cf79bfbf7043de3811ad0761b0f37a1e26286b0e977c69aa274524e79097a56dc4bd9e1b371c71c718b10", "000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000"],
+    ],
+    &[
+        ["1a0111ea397fe69a4b1ba7b6434bacd764774b84f38512bf6730d2a0f6b0f6241eabfffeb153ffffb9feffffffffa8fb", "1a0111ea397fe69a4b1ba7b6434bacd764774b84f38512bf6730d2a0f6b0f6241eabfffeb153ffffb9feffffffffa8fb"],
+        ["000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000", "1a0111ea397fe69a4b1ba7b6434bacd764774b84f38512bf6730d2a0f6b0f6241eabfffeb153ffffb9feffffffffa9d3"],
+        ["000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000012", "1a0111ea397fe69a4b1ba7b6434bacd764774b84f38512bf6730d2a0f6b0f6241eabfffeb153ffffb9feffffffffaa99"],
+        ["000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000001", "000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000"],
+    ],
+];
+
+/// The curves and the constants of the pairing, built once since the fields are chosen at runtime
+#[derive(Clone, Debug)]
+pub struct Bls12_381 {
+    order: Ufeat,
+    g1: Rc<CurveOver<Fp>>,
+    g2: Rc<CurveOver<Fp2>>,
+    g12: Rc<CurveOver<Fp12>>,
+    generator1: PointOver<Fp>,
+    generator2: PointOver<Fp2>,
+    one12: Fp12,
+    /// $w^{-2}$ and $w^{-3}$
+    untwist: (Fp12, Fp12),
+    /// $\xi^{-(p - 1) / 3}$ and $\xi^{-(p - 1) / 2}$
+    psi: (Fp2, Fp2),
+    /// $p^2$ and $(p^4 - p^2 + 1) / r$, the exponents of the final exponentiation
+    final_exponents: (Exponent, Exponent),
+    sswu1: Sswu<Fp>,
+    sswu2: Sswu<Fp2>,
+}
+
+impl Default for Bls12_381 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Bls12_381 {
+    pub fn new() -> Self {
+        let curve = Rc::new(curves::bls12_381());
+        let p = curve.original_p();
+        let one = DynResidue::one(*curve.p());
+        let g1 = Rc::new(
+            curve
+                .over(&one)
+                .expect("the characteristic is the one of the curve"),
+        );
+        let generator1 = curve.generator().unwrap().over(&g1).unwrap();
+
+        let one2 = Fp2::one(-one).expect("-1 isn't a square as p = 3 mod 4");
+        let xi = one2.from_coefficients(one, one);
+        let g2 = Rc::new(
+            CurveOver::new(
+                "BLS12-381 G2".to_string(),
+                [one2.zero_like(), one2.zero_like(), integer(&one2, 4) * xi],
+            )
+            .unwrap(),
+        );
+        let fp = |hex: &str| DynResidue::new(&parse_hex(hex).unwrap(), *curve.p());
+        let fp2 = |c0: &str, c1: &str| one2.from_coefficients(fp(c0), fp(c1));
+        let generator2 = g2
+            .point(
+                fp2(
+                    "024aa2b2f08f0a91260805272dc51051c6e47ad4fa403b02b4510b647ae3d1770bac0326a805bbefd48056c8c121bdb8",
+                    "13e02b6052719f607dacd3a088274f65596bd0d09920b61ab5da61bbdc7f5049334cf11213945d57e5ac7d055d042b7e",
+                ),
+                fp2(
+                    "0ce5d527727d6e118cc9cdc6da2e351aadfd9baa8cbdd3a76d429a695160d12c923ac9cc3baca289e193548608b82801",
+                    "0606c4a02ea734cc32acd2b02bc28b99cb3e287e85a763af267492ab572e99ab3f370d275cec1da1aaa9075ff05f79be",
+                ),
+            )
+            .expect("the generator of G2 is on the twist");
+
+        let one6 = Fp6::one(xi).expect("u + 1 isn't a cube in F_p^2");
+        let one12 = Fp12::one(&one6).expect("v isn't a square in F_p^6");
+        let g12 = Rc::new(curve.over(&one12).unwrap());
+        let w = one12.from_coefficients(one6.zero_like(), one6);
+        let w_inverse = w.inverse().unwrap();
+        let untwist = (w_inverse.square(), w_inverse.square() * w_inverse);
+
+        let p_minus_one = p.wrapping_sub(&Ufeat::ONE);
+        let xi_inverse = xi.inverse().unwrap();
+        let psi = (
+            xi_inverse.pow(&p_minus_one.wrapping_div(&Ufeat::from(3u8))),
+            xi_inverse.pow(&p_minus_one.shr_vartime(1)),
+        );
+
+        let wide = |n: &Ufeat| n.resize::<{ Exponent::LIMBS }>();
+        let p_squared = wide(&p).wrapping_mul(&wide(&p));
+        let hard = p_squared
+            .wrapping_mul(&p_squared)
+            .wrapping_sub(&p_squared)
+            .wrapping_add(&Exponent::ONE)
+            .wrapping_div(&wide(curve.order()));
+
+        let [a, b] = ISOGENOUS_11.map(fp);
+        let sswu1 = Sswu::new(
+            "the curve 11-isogenous to BLS12-381 G1",
+            [a, b],
+            integer(&one, 11),
+            ISOGENY_11.map(|coefficients| coefficients.iter().map(|c| fp(c)).collect()),
+        );
+        let i = one2.from_coefficients(one.zero_like(), one);
+        let sswu2 = Sswu::new(
+            "the curve 3-isogenous to BLS12-381 G2",
+            [integer(&one2, 240) * i, integer(&one2, 1012) * (one2 + i)],
+            -(integer(&one2, 2) + i),
+            ISOGENY_3.map(|coefficients| coefficients.iter().map(|[c0, c1]| fp2(c0, c1)).collect()),
+        );
+
+        Bls12_381 {
+            order: *curve.order(),
+            sswu1,
+            sswu2,
+            g1,
+            g2,
+            g12,
+            generator1,
+            generator2,
+            one12,
+            untwist,
+            psi,
+            final_exponents: (p_squared, hard),
+        }
+    }
+
+    /// `r`, the prime order of G1, G2 and of the values of the pairing
+    pub fn order(&self) -> &Ufeat {
+        &self.order
+    }
+    pub fn g1_curve(&self) -> &Rc<CurveOver<Fp>> {
+        &self.g1
+    }
+    pub fn g2_curve(&self) -> &Rc<CurveOver<Fp2>> {
+        &self.g2
+    }
+    /// The standard generator of G1
+    pub fn g1(&self) -> &PointOver<Fp> {
+        &self.generator1
+    }
+    /// The standard generator of G2
+    pub fn g2(&self) -> &PointOver<Fp2> {
+        &self.generator2
+    }
+
+    /// Whether the point is on G1's curve and of order `r`, or infinity; the curve has other points, see the cofactor
+    pub fn is_in_g1(&self, point: &PointOver<Fp>) -> bool {
+        **point.curve() == *self.g1 && (point * self.order).is_infinity()
+    }
+    /// Whether the point is on the twist and of order `r`, or infinity
+    pub fn is_in_g2(&self, point: &PointOver<Fp2>) -> bool {
+        **point.curve() == *self.g2 && (point * self.order).is_infinity()
+    }
+
+    /// The endomorphism of the twist which untwists, applies Frobenius and twists back; it acts on G2 as the
+    /// multiplication by $p = x \mod r$
+    pub fn psi(&self, point: &PointOver<Fp2>) -> PointOver<Fp2> {
+        match point.x().zip(point.y()) {
+            None => point.clone(),
+            Some((x, y)) => self
+                .g2
+                .point(x.conjugate() * self.psi.0, y.conjugate() * self.psi.1)
+                .expect("psi is an endomorphism of the twist"),
+        }
+    }
+
+    /// The optimal ate pairing $e(P, Q) = f_{x,Q}(P)^{(p^{12} - 1) / r}$ of `p` in G1 and `q` in G2
+    ///
+    /// Fails with `DifferentCurves` for points of other curves; the subgroups aren't checked, that's `is_in_g1` and
+    /// `is_in_g2`.
+    pub fn pairing(&self, p: &PointOver<Fp>, q: &PointOver<Fp2>) -> Result<Fp12, PointError> {
+        self.multi_pairing(&[(p, q)])
+    }
+
+    /// The product of the pairings of the couples, at the cost of one final exponentiation
+    pub fn multi_pairing(
+        &self,
+        pairs: &[(&PointOver<Fp>, &PointOver<Fp2>)],
+    ) -> Result<Fp12, PointError> {
+        let mut product = self.one12;
+        for (p, q) in pairs {
+            require_curve(p, &self.g1)?;
+            require_curve(q, &self.g2)?;
+            if p.is_infinity() || q.is_infinity() {
+                continue;
+            }
+            product = product
+                * pairing::miller(&self.untwisted(q), &self.embedded(p), &Ufeat::from_u64(X))?;
+        }
+        // x < 0: f_{x,Q} is the inverse of f_{-x,Q} up to a vertical line, which the final exponentiation kills
+        Ok(self.final_exponentiation(product.inverse().expect("Miller's loop values are nonzero")))
+    }
+
+    /// $(x.w^{-2}, y.w^{-3})$ on the curve over $F_{p^{12}}$
+    fn untwisted(&self, point: &PointOver<Fp2>) -> PointOver<Fp12> {
+        let (x, y) = point.x().zip(point.y()).unwrap();
+        self.g12
+            .point(
+                self.embed(x) * self.untwist.0,
+                self.embed(y) * self.untwist.1,
+            )
+            .expect("the untwisting is an isomorphism")
+    }
+
+    /// The point of G1 on the curve over $F_{p^{12}}$
+    fn embedded(&self, point: &PointOver<Fp>) -> PointOver<Fp12> {
+        let (x, y) = point.x().zip(point.y()).unwrap();
+        let embed = |c: &Fp| self.one12.embed(c);
+        self.g12
+            .point(embed(x), embed(y))
+            .expect("G1 is a subgroup of the points over F_p^12")
+    }
+
+    fn embed(&self, c: &Fp2) -> Fp12 {
+        let one6 = *self.one12.c0();
+        self.one12.embed6(&one6.embed2(c))
+    }
+
+    /// The power $(p^{12} - 1) / r$ as $(p^6 - 1)(p^2 + 1)$, after which the inverse is the conjugate, then
+    /// $(p^4 - p^2 + 1) / r$
+    fn final_exponentiation(&self, f: Fp12) -> Fp12 {
+        let f = f.conjugate() * f.inverse().unwrap();
+        let f = f.pow(&self.final_exponents.0) * f;
+        f.pow(&self.final_exponents.1)
+    }
+
+    /// `hash_to_curve` of RFC 9380 onto G1
+    pub fn hash_to_g1(&self, message: &[u8], dst: &[u8]) -> PointOver<Fp> {
+        let u = hash_to_field(message, dst, 2, 1, &self.g1.a2().one_like());
+        let sum = &self.sswu1.map(&self.g1, u[0][0]) + &self.sswu1.map(&self.g1, u[1][0]);
+        // h_eff = 1 - x clears the cofactor of G1
+        &sum.unwrap() * Ufeat::from_u64(X + 1)
+    }
+
+    /// `hash_to_curve` of RFC 9380 onto G2
+    pub fn hash_to_g2(&self, message: &[u8], dst: &[u8]) -> PointOver<Fp2> {
+        let one2 = self.g2.a2().one_like();
+        let u: Vec<_> = hash_to_field(message, dst, 2, 2, &one2.c0().one_like())
+            .into_iter()
+            .map(|c| one2.from_coefficients(c[0], c[1]))
+            .collect();
+        let sum = &self.sswu2.map(&self.g2, u[0]) + &self.sswu2.map(&self.g2, u[1]);
+        self.clear_cofactor_g2(&sum.unwrap())
+    }
+
+    /// The multiplication by $h_{eff}$ of RFC 9380 as Budroni and Pintore compute it with `psi`:
+    /// $[x^2 - x - 1]P + [x - 1]\psi(P) + \psi^2(2P)$
+    fn clear_cofactor_g2(&self, point: &PointOver<Fp2>) -> PointOver<Fp2> {
+        let times_x = |point: &PointOver<Fp2>| -&(point * Ufeat::from_u64(X));
+        let add =
+            |a: &PointOver<Fp2>, b: &PointOver<Fp2>| (a + b).expect("the points are on the twist");
+        let sub =
+            |a: &PointOver<Fp2>, b: &PointOver<Fp2>| (a - b).expect("the points are on the twist");
+        let t1 = times_x(point);
+        let t2 = self.psi(point);
+        let t3 = self.psi(&self.psi(&add(point, point)));
+        let t3 = sub(&t3, &t2);
+        let t2 = times_x(&add(&t1, &t2));
+        let t3 = sub(&add(&t3, &t2), &t1);
+        sub(&t3, point)
+    }
+}
+
+/// `DifferentCurves` unless the point is on `curve`
+fn require_curve<F: Field>(point: &PointOver<F>, curve: &CurveOver<F>) -> Result<(), PointError> {
+    if **point.curve() == *curve {
+        Ok(())
+    } else {
+        Err(PointError::DifferentCurves {
+            left: point.curve().name.clone(),
+            right: curve.name.clone(),
+        })
+    }
+}
+
+/// `expand_message_xmd` of RFC 9380 with SHA-256: `length` pseudorandom bytes out of `message`, separated from other
+/// uses of the hash by `dst`
+///
+/// Panics beyond the limits of the RFC, 8160 bytes; a `dst` over 255 bytes is hashed down as it prescribes.
+pub fn expand_message_xmd(message: &[u8], dst: &[u8], length: usize) -> Vec<u8> {
+    const BLOCK: usize = 64;
+    const OUTPUT: usize = 32;
+    let ell = length.div_ceil(OUTPUT);
+    assert!(
+        ell <= 255 && length <= u16::MAX as usize,
+        "too many bytes asked"
+    );
+    let long_dst;
+    let dst = if dst.len() > 255 {
+        long_dst = Sha256::new()
+            .chain_update(b"H2C-OVERSIZE-DST-")
+            .chain_update(dst)
+            .finalize();
+        &long_dst[..]
+    } else {
+        dst
+    };
+    let dst_prime = [dst, &[dst.len() as u8]].concat();
+    let b_0 = Sha256::new()
+        .chain_update([0u8; BLOCK])
+        .chain_update(message)
+        .chain_update((length as u16).to_be_bytes())
+        .chain_update([0u8])
+        .chain_update(&dst_prime)
+        .finalize();
+    let mut blocks = vec![Sha256::new()
+        .chain_update(b_0)
+        .chain_update([1u8])
+        .chain_update(&dst_prime)
+        .finalize()];
+    for i in 2..=ell {
+        let mixed: Vec<u8> = b_0
+            .iter()
+            .zip(blocks.last().unwrap())
+            .map(|(a, b)| a ^ b)
+            .collect();
+        blocks.push(
+            Sha256::new()
+                .chain_update(mixed)
+                .chain_update([i as u8])
+                .chain_update(&dst_prime)
+                .finalize(),
+        );
+    }
+    let mut bytes: Vec<u8> = blocks.concat();
+    bytes.truncate(length);
+    bytes
+}
+
+/// `hash_to_field` of RFC 9380: `count` elements of the extension of the given `degree`, as their coordinates over the
+/// prime field of `one`
+fn hash_to_field(
+    message: &[u8],
+    dst: &[u8],
+    count: usize,
+    degree: usize,
+    one: &Fp,
+) -> Vec<Vec<Fp>> {
+    let bytes = expand_message_xmd(message, dst, count * degree * FIELD_HASH_BYTES);
+    let p = *one.params().modulus();
+    bytes
+        .chunks(FIELD_HASH_BYTES)
+        .map(|chunk| {
+            let mut buffer = vec![0u8; Ufeat::BYTES];
+            buffer[Ufeat::BYTES - FIELD_HASH_BYTES..].copy_from_slice(chunk);
+            DynResidue::new(
+                &Ufeat::from_be_slice(&buffer).wrapping_rem(&p),
+                *one.params(),
+            )
+        })
+        .collect::<Vec<_>>()
+        .chunks(degree)
+        .map(<[Fp]>::to_vec)
+        .collect()
+}
+
+/// `sgn0` of RFC 9380: the parity of the first nonzero coordinate
+fn sgn0<F: Field>(element: &F) -> bool {
+    let (mut sign, mut zero) = (false, true);
+    for c in element.to_coefficients() {
+        sign |= zero && c.bit_vartime(0);
+        zero &= c == Ufeat::ZERO;
+    }
+    sign
+}
+
+/// A small integer in the field of `like`
+fn integer<F: Field>(like: &F, n: i64) -> F {
+    let magnitude = like.embed(&DynResidue::new(
+        &Ufeat::from_u64(n.unsigned_abs()),
+        like.prime_params(),
+    ));
+    if n < 0 {
+        -magnitude
+    } else {
+        magnitude
+    }
+}
+
+/// The simplified SWU map of RFC 9380 section 6.6.2 onto an isogenous curve $y^2 = x^3 + A'.x + B'$, followed by the
+/// isogeny onto the curve of the group
+#[derive(Clone, Debug)]
+struct Sswu<F: Field> {
+    isogenous: Rc<CurveOver<F>>,
+    z: F,
+    /// The numerator and the denominator of `x`, then those of `y` over the `y` of the isogenous curve
+    isogeny: [Vec<F>; 4],
+}
+
+impl<F: Field> Sswu<F> {
+    fn new(name: &str, [a, b]: [F; 2], z: F, isogeny: [Vec<F>; 4]) -> Self {
+        let isogenous = CurveOver::new(name.to_string(), [a.zero_like(), a, b])
+            .expect("the isogenous curve is smooth");
+        Sswu {
+            isogenous: Rc::new(isogenous),
+            z,
+            isogeny,
+        }
+    }
+
+    /// The version of section 6.6.2 rather than the straight-line one of appendix F.2, as nothing here is constant time
+    fn map(&self, curve: &Rc<CurveOver<F>>, u: F) -> PointOver<F> {
+        let (a, b) = (*self.isogenous.a4(), *self.isogenous.a6());
+        let z_u2 = self.z * u.square();
+        let x1 = match (z_u2.square() + z_u2).inverse() {
+            Some(tv1) => -b * a.inverse().unwrap() * (u.one_like() + tv1),
+            // the exceptional case
+            None => b * (self.z * a).inverse().unwrap(),
+        };
+        // Z isn't a square, so either g(x1) or g(Z.u^2.x1) = Z^3.u^6.g(x1) is
+        let (x, y) = match self.isogenous.rhs(&x1).sqrt() {
+            Some(y) => (x1, y),
+            None => {
+                let x2 = z_u2 * x1;
+                let y = self.isogenous.rhs(&x2).sqrt();
+                (x2, y.expect("Z isn't a square"))
+            }
+        };
+        let y = if sgn0(&u) == sgn0(&y) { y } else { -y };
+
+        let [x_numerator, x_denominator, y_numerator, y_denominator] =
+            self.isogeny.each_ref().map(|coefficients| {
+                coefficients
+                    .iter()
+                    .rev()
+                    .fold(x.zero_like(), |value, c| value * x + *c)
+            });
+        // the kernel of the isogeny goes to infinity
+        match x_denominator.inverse().zip(y_denominator.inverse()) {
+            None => curve.infinity(),
+            Some((x_inverse, y_inverse)) => curve
+                .point(x_numerator * x_inverse, y * y_numerator * y_inverse)
+                .expect("the isogeny lands on the curve"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const G1_DST: &[u8] = b"QUUX-V01-CS02-with-BLS12381G1_XMD:SHA-256_SSWU_RO_";
+    const G2_DST: &[u8] = b"QUUX-V01-CS02-with-BLS12381G2_XMD:SHA-256_SSWU_RO_";
+
+    /// The messages of the vectors of RFC 9380 appendix J.9.1 and J.10.1
+    fn messages() -> [Vec<u8>; 5] {
+        [
+            b"".to_vec(),
+            b"abc".to_vec(),
+            b"abcdef0123456789".to_vec(),
+            [&b"q128_"[..], &[b'q'; 128]].concat(),
+            [&b"a512_"[..], &[b'a'; 512]].concat(),
+        ]
+    }
+    const G1_VECTORS: [[&str; 2]; 5] = [
+        [
+            "052926add2207b76ca4fa57a8734416c8dc95e24501772c814278700eed6d1e4e8cf62d9c09db0fac349612b759e79a1",
+            "08ba738453bfed09cb546dbb0783dbb3a5f1f566ed67bb6be0e8c67e2e81a4cc68ee29813bb7994998f3eae0c9c6a265",
+        ],
+        [
+            "03567bc5ef9c690c2ab2ecdf6a96ef1c139cc0b2f284dca0a9a7943388a49a3aee664ba5379a7655d3c68900be2f6903",
+            "0b9c15f3fe6e5cf4211f346271d7b01c8f3b28be689c8429c85b67af215533311f0b8dfaaa154fa6b88176c229f2885d",
+        ],
+        [
+            "11e0b079dea29a68f0383ee94fed1b940995272407e3bb916bbf268c263ddd57a6a27200a784cbc248e84f357ce82d98",
+            "03a87ae2caf14e8ee52e51fa2ed8eefe80f02457004ba4d486d6aa1f517c0889501dc7413753f9599b099ebcbbd2d709",
+        ],
+        [
+            "15f68eaa693b95ccb85215dc65fa81038d69629f70aeee0d0f677cf22285e7bf58d7cb86eefe8f2e9bc3f8cb84fac488",
+            "1807a1d50c29f430b8cafc4f8638dfeeadf51211e1602a5f184443076715f91bb90a48ba1e370edce6ae1062f5e6dd38",
+        ],
+        [
+            "082aabae8b7dedb0e78aeb619ad3bfd9277a2f77ba7fad20ef6aabdc6c31d19ba5a6d12283553294c1825c4b3ca2dcfe",
+            "05b84ae5a942248eea39e1d91030458c40153f3b654ab7872d779ad1e942856a20c438e8d99bc8abfbf74729ce1f7ac8",
+        ],
+    ];
+    const G2_VECTORS: [[&str; 4]; 5] = [
+        [
+            "0141ebfbdca40eb85b87142e130ab689c673cf60f1a3e98d69335266f30d9b8d4ac44c1038e9dcdd5393faf5c41fb78a",
+            "05cb8437535e20ecffaef7752baddf98034139c38452458baeefab379ba13dff5bf5dd71b72418717047f5b0f37da03d",
+            "0503921d7f6a12805e72940b963c0cf3471c7b2a524950ca195d11062ee75ec076daf2d4bc358c4b190c0c98064fdd92",
+            "12424ac32561493f3fe3c260708a12b7c620e7be00099a974e259ddc7d1f6395c3c811cdd19f1e8dbf3e9ecfdcbab8d6",
+        ],
+        [
+            "02c2d18e033b960562aae3cab37a27ce00d80ccd5ba4b7fe0e7a210245129dbec7780ccc7954725f4168aff2787776e6",
+            "139cddbccdc5e91b9623efd38c49f81a6f83f175e80b06fc374de9eb4b41dfe4ca3a230ed250fbe3a2acf73a41177fd8",
+            "1787327b68159716a37440985269cf584bcb1e621d3a7202be6ea05c4cfe244aeb197642555a0645fb87bf7466b2ba48",
+            "00aa65dae3c8d732d10ecd2c50f8a1baf3001578f71c694e03866e9f3d49ac1e1ce70dd94a733534f106d4cec0eddd16",
+        ],
+        [
+            "121982811d2491fde9ba7ed31ef9ca474f0e1501297f68c298e9f4c0028add35aea8bb83d53c08cfc007c1e005723cd0",
+            "190d119345b94fbd15497bcba94ecf7db2cbfd1e1fe7da034d26cbba169fb3968288b3fafb265f9ebd380512a71c3f2c",
+            "05571a0f8d3c08d094576981f4a3b8eda0a8e771fcdcc8ecceaf1356a6acf17574518acb506e435b639353c2e14827c8",
+            "0bb5e7572275c567462d91807de765611490205a941a5a6af3b1691bfe596c31225d3aabdf15faff860cb4ef17c7c3be",
+        ],
+        [
+            "19a84dd7248a1066f737cc34502ee5555bd3c19f2ecdb3c7d9e24dc65d4e25e50d83f0f77105e955d78f4762d33c17da",
+            "0934aba516a52d8ae479939a91998299c76d39cc0c035cd18813bec433f587e2d7a4fef038260eef0cef4d02aae3eb91",
+            "14f81cd421617428bc3b9fe25afbb751d934a00493524bc4e065635b0555084dd54679df1536101b2c979c0152d09192",
+            "09bcccfa036b4847c9950780733633f13619994394c23ff0b32fa6b795844f4a0673e20282d07bc69641cee04f5e5662",
+        ],
+        [
+            "01a6ba2f9a11fa5598b2d8ace0fbe0a0eacb65deceb476fbbcb64fd24557c2f4b18ecfc5663e54ae16a84f5ab7f62534",
+            "11fca2ff525572795a801eed17eb12785887c7b63fb77a42be46ce4a34131d71f7a73e95fee3f812aea3de78b4d01569",
+            "0b6798718c8aed24bc19cb27f866f1c9effcdbf92397ad6448b5c9db90d2b9da6cbabf48adc1adf59a1a28344e79d57e",
+            "03a47f8e6d1763ba0cad63d6114c0accbef65707825a511b251a660a9b3994249ae4e63fac38b23da0c398689ee2ab52",
+        ],
+    ];
+
+    /// The coordinates of the point over the prime field, `x` first
+    fn coordinates<F: Field>(point: &PointOver<F>) -> Vec<Ufeat> {
+        let (x, y) = point.x().zip(point.y()).unwrap();
+        [x.to_coefficients(), y.to_coefficients()].concat()
+    }
+
+    fn parsed(hexes: &[&str]) -> Vec<Ufeat> {
+        hexes.iter().map(|hex| parse_hex(hex).unwrap()).collect()
+    }
+
+    #[test]
+    fn hash_to_g1_matches_rfc_9380() {
+        let curve = Bls12_381::new();
+        for (message, expected) in messages().iter().zip(G1_VECTORS) {
+            let point = curve.hash_to_g1(message, G1_DST);
+            assert!(curve.is_in_g1(&point));
+            assert_eq!(coordinates(&point), parsed(&expected));
+        }
+    }
+
+    #[test]
+    fn hash_to_g2_matches_rfc_9380() {
+        let curve = Bls12_381::new();
+        for (message, expected) in messages().iter().zip(G2_VECTORS) {
+            let point = curve.hash_to_g2(message, G2_DST);
+            assert!(curve.is_in_g2(&point));
+            assert_eq!(coordinates(&point), parsed(&expected));
+        }
+    }
+}
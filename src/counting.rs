@@ -0,0 +1,428 @@
+use std::{collections::HashMap, rc::Rc};
+
+use crypto_bigint::{modular::runtime_mod::DynResidue, rand_core::OsRng, NonZero, RandomMod};
+
+use super::{field, CurveError, EllipticCurve, Error, Point, PointError, Ufeat};
+
+/// Naive point counting is meant for hand-made teaching curves: fields up to this many bits
+pub const COUNTING_BOUND_BITS: usize = 40;
+/// Below this modulus summing the Legendre symbols is cheaper than baby-step giant-step
+const LEGENDRE_BOUND: u64 = 1 << 16;
+/// Mestre's theorem guarantees the convergence for p > 229, so running out of attempts means bad luck and falls back to the summation
+const MESTRE_ATTEMPTS: usize = 64;
+/// Don't enumerate the candidates for the group order while there're too many of them
+const CANDIDATES_BOUND: u64 = 1 << 16;
+
+impl EllipticCurve {
+    /// Counts the points of the curve, including the point at infinity
+    ///
+    /// Works only for fields up to `COUNTING_BOUND_BITS` bits: Legendre symbols summation for tiny fields and
+    /// baby-step giant-step on the curve and its quadratic twist (Mestre) for the rest.
+    pub fn count_points(self: &Rc<Self>) -> Result<Ufeat, Error> {
+        let p = self.small_modulus()?;
+        Ok(Ufeat::from(self.count_points_small(p, MESTRE_ATTEMPTS)?))
+    }
+
+    /// Checks the `order` the curve was created with against the number of its points
+    ///
+    /// As `order` is the order of the base point subgroup it has to divide the number of points, but not necessary to be equal to it.
+    pub fn verify_order(self: &Rc<Self>) -> Result<(), Error> {
+        let count = self.count_points()?;
+        if count.wrapping_rem(self.order()) == Ufeat::ZERO {
+            Ok(())
+        } else {
+            Err(CurveError::OrderMismatch {
+                name: self.name.clone(),
+                order: *self.order(),
+                count,
+            }
+            .into())
+        }
+    }
+
+    /// Iterates over every point of the curve: the point at infinity first, then by `x` ascending
+    pub fn points(self: &Rc<Self>) -> Points {
+        Points {
+            curve: Rc::clone(self),
+            next_x: Ufeat::ZERO,
+            pending: None,
+            started: false,
+        }
+    }
+
+    fn small_modulus(&self) -> Result<u64, CurveError> {
+        if self.original_p().bits() > COUNTING_BOUND_BITS {
+            return Err(CurveError::FieldTooLarge {
+                name: self.name.clone(),
+                bits: self.original_p().bits(),
+            });
+        }
+        Ok(self.original_p().as_words()[0])
+    }
+
+    /// `count_points` with the bound on the attempts of Mestre's method before it falls back to the summation
+    fn count_points_small(self: &Rc<Self>, p: u64, attempts: usize) -> Result<u64, PointError> {
+        if p < LEGENDRE_BOUND {
+            return Ok(self.count_points_legendre(p));
+        }
+        Ok(match self.count_points_mestre(p, attempts)? {
+            Some(count) => count,
+            None => self.count_points_legendre(p),
+        })
+    }
+
+    /// #E = p + 1 + sum of Legendre symbols of the right hand side over all `x`
+    pub(crate) fn count_points_legendre(&self, p: u64) -> u64 {
+        let sum = (0..p)
+            .map(|x| {
+                field::legendre(&self.rhs(&DynResidue::new(&Ufeat::from(x), *self.p()))) as i64
+            })
+            .sum::<i64>();
+        (p as i64 + 1 + sum) as u64
+    }
+
+    /// Collects the orders of random points on the curve and its twist until only one group order in Hasse interval fits them
+    fn count_points_mestre(
+        self: &Rc<Self>,
+        p: u64,
+        attempts: usize,
+    ) -> Result<Option<u64>, PointError> {
+        let twist = Rc::new(self.small_quadratic_twist(p));
+        // 2 * sqrt(p) rounded up
+        let width = (4 * p as u128).isqrt() as u64 + 1;
+        let (low, high) = (p + 1 - width, p + 1 + width);
+        // the twist has 2p + 2 - #E points
+        let (twist_low, twist_high) = (2 * p + 2 - high, 2 * p + 2 - low);
+
+        let (mut lcm_curve, mut lcm_twist) = (1, 1);
+        for attempt in 0..attempts {
+            if attempt % 2 == 0 {
+                let order = random_point_order(self, lcm_curve, low, high)?;
+                lcm_curve = lcm(lcm_curve, order);
+            } else {
+                let order = random_point_order(&twist, lcm_twist, twist_low, twist_high)?;
+                lcm_twist = lcm(lcm_twist, order);
+            }
+
+            let first = low.div_ceil(lcm_curve) * lcm_curve;
+            if first > high || (high - first) / lcm_curve > CANDIDATES_BOUND {
+                continue;
+            }
+            let mut candidates = (first..=high)
+                .step_by(lcm_curve as usize)
+                .filter(|count| (2 * p + 2 - count).is_multiple_of(lcm_twist));
+            if let (Some(count), None) = (candidates.next(), candidates.next()) {
+                return Ok(Some(count));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Twist by the smallest non-square `d`: $y^2 = x^3 + d.a2.x^2 + d^2.a4.x + d^3.a6$
+    fn small_quadratic_twist(&self, p: u64) -> EllipticCurve {
+        let one = DynResidue::one(*self.p());
+        let mut d = one + one;
+        while field::legendre(&d) != -1 {
+            d += one;
+        }
+        EllipticCurve::from_uint_coefficients(
+            format!("quadratic twist of {}", self.name),
+            // the order of the twist is exactly what's unknown; the middle of Hasse interval is just a placeholder
+            Ufeat::from(p + 1),
+            self.original_p(),
+            [
+                (*self.a2() * d).retrieve(),
+                (*self.a4() * d.square()).retrieve(),
+                (*self.a6() * d.square() * d).retrieve(),
+            ],
+        )
+    }
+
+    fn random_point(self: &Rc<Self>) -> Point {
+        let modulus = NonZero::new(self.original_p()).unwrap();
+        loop {
+            let x = DynResidue::new(&Ufeat::random_mod(&mut OsRng, &modulus), *self.p());
+            if let Some(y) = field::sqrt(&self.rhs(&x)) {
+                return Point::Regular {
+                    x,
+                    y,
+                    curve: Rc::clone(self),
+                };
+            }
+        }
+    }
+}
+
+/// Finds the order of a random point knowing that `known` divides the group order which lies in `low..=high`
+fn random_point_order(
+    curve: &Rc<EllipticCurve>,
+    known: u64,
+    low: u64,
+    high: u64,
+) -> Result<u64, PointError> {
+    let point = curve.random_point();
+    let multiple = known
+        * annihilating_multiple(
+            &(&point * Ufeat::from(known))?,
+            low.div_ceil(known),
+            high / known,
+        )?;
+    let factorization = factor_small(multiple)
+        .into_iter()
+        .map(|(prime, exponent)| (Ufeat::from(prime), exponent))
+        .collect::<Vec<_>>();
+    Ok(point.order(&factorization)?.as_words()[0])
+}
+
+/// Baby-step giant-step for some positive `k` with `k * point` at infinity, knowing that there's one in `low..=high`
+fn annihilating_multiple(point: &Point, low: u64, high: u64) -> Result<u64, PointError> {
+    let step = (high - low + 1).isqrt() + 1;
+
+    let mut baby_steps = HashMap::new();
+    let mut runner = point.clone();
+    for j in 1..=step {
+        match runner.x() {
+            None => return Ok(j),
+            Some(x) => {
+                // the same `x` means `runner` is plus or minus the stored multiple
+                if let Some(i) = baby_steps.insert(x.retrieve(), j) {
+                    return Ok(if runner == (point * Ufeat::from(i))? {
+                        j - i
+                    } else {
+                        j + i
+                    });
+                }
+            }
+        }
+        runner = (&runner + point)?;
+    }
+
+    let giant_step = (point * Ufeat::from(step))?;
+    let mut giant = (point * Ufeat::from(low))?;
+    let mut k = low;
+    while k <= high + step {
+        match giant.x() {
+            None => return Ok(k),
+            Some(x) => {
+                if let Some(&j) = baby_steps.get(&x.retrieve()) {
+                    // giant = -j * point or giant = j * point
+                    if (&giant + &(point * Ufeat::from(j))?)?.is_infinity() {
+                        return Ok(k + j);
+                    } else if k != j {
+                        return Ok(k.abs_diff(j));
+                    }
+                }
+            }
+        }
+        giant = (&giant + &giant_step)?;
+        k += step;
+    }
+    Err(PointError::OrderOutOfRange {
+        curve: point.curve().name.clone(),
+        low,
+        high,
+    })
+}
+
+fn factor_small(mut n: u64) -> Vec<(u64, u32)> {
+    let mut factorization = Vec::new();
+    let mut divisor = 2;
+    while divisor * divisor <= n {
+        let mut exponent = 0;
+        while n.is_multiple_of(divisor) {
+            n /= divisor;
+            exponent += 1;
+        }
+        if exponent > 0 {
+            factorization.push((divisor, exponent));
+        }
+        divisor += if divisor == 2 { 1 } else { 2 };
+    }
+    if n > 1 {
+        factorization.push((n, 1));
+    }
+    factorization
+}
+
+fn gcd(a: u64, b: u64) -> u64 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+pub(crate) fn lcm(a: u64, b: u64) -> u64 {
+    a / gcd(a, b) * b
+}
+
+/// Iterator over all the points of a curve, see `EllipticCurve::points`
+pub struct Points {
+    curve: Rc<EllipticCurve>,
+    next_x: Ufeat,
+    // the second point with the same `x`
+    pending: Option<Point>,
+    started: bool,
+}
+
+impl Iterator for Points {
+    type Item = Point;
+    fn next(&mut self) -> Option<Self::Item> {
+        if !self.started {
+            self.started = true;
+            return Some(Point::AtInfinity {
+                curve: Rc::clone(&self.curve),
+            });
+        }
+        if let Some(point) = self.pending.take() {
+            return Some(point);
+        }
+        while self.next_x < self.curve.original_p() {
+            let x = DynResidue::new(&self.next_x, *self.curve.p());
+            self.next_x = self.next_x.wrapping_add(&Ufeat::ONE);
+            if let Some(y) = field::sqrt(&self.curve.rhs(&x)) {
+                let (y_small, y_big) = if y.retrieve() <= y.neg().retrieve() {
+                    (y, y.neg())
+                } else {
+                    (y.neg(), y)
+                };
+                if y_big != y_small {
+                    self.pending = Some(Point::Regular {
+                        x,
+                        y: y_big,
+                        curve: Rc::clone(&self.curve),
+                    });
+                }
+                return Some(Point::Regular {
+                    x,
+                    y: y_small,
+                    curve: Rc::clone(&self.curve),
+                });
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use super::*;
+
+    fn curve(p: u64, coefficients: [isize; 3]) -> Rc<EllipticCurve> {
+        Rc::new(EllipticCurve::new(
+            format!("teaching curve over {p}"),
+            Ufeat::from(3u8),
+            Ufeat::from(p),
+            coefficients,
+        ))
+    }
+
+    /// The number of points found by trying every `x` against a table of how many `y` square to each residue
+    fn enumerate(curve: &EllipticCurve) -> u64 {
+        let p = curve.original_p().as_words()[0] as u128;
+        let [a2, a4, a6] =
+            [curve.a2(), curve.a4(), curve.a6()].map(|a| a.retrieve().as_words()[0] as u128);
+        let mut roots = vec![0; p as usize];
+        for y in 0..p {
+            roots[(y * y % p) as usize] += 1;
+        }
+        1 + (0..p)
+            .map(|x| roots[(((x * x + a2 * x + a4) % p * x + a6) % p) as usize])
+            .sum::<u64>()
+    }
+
+    const CURVES: [(u64, [isize; 3]); 4] = [
+        (13, [0, 1, 1]),
+        (101, [0, 2, 3]),
+        (1009, [1, -1, 5]),
+        // past `LEGENDRE_BOUND`, so counted by Mestre's method
+        (65537, [0, -3, 7]),
+    ];
+
+    #[test]
+    fn count_points_agrees_with_enumeration() {
+        for (p, coefficients) in CURVES {
+            let curve = curve(p, coefficients);
+            assert_eq!(
+                curve.count_points(),
+                Ok(Ufeat::from(enumerate(&curve))),
+                "{p}"
+            );
+        }
+    }
+
+    #[test]
+    fn mestre_falls_back_to_legendre_symbols() {
+        let (p, coefficients) = CURVES[3];
+        let curve = curve(p, coefficients);
+        assert_eq!(curve.count_points_mestre(p, 0), Ok(None));
+        assert_eq!(curve.count_points_small(p, 0), Ok(enumerate(&curve)));
+    }
+
+    #[test]
+    fn count_points_refuses_large_fields() {
+        let curve = curve((1 << 41) - 21, [0, 2, 3]);
+        assert_eq!(
+            curve.count_points(),
+            Err(CurveError::FieldTooLarge {
+                name: curve.name.clone(),
+                bits: 41
+            }
+            .into())
+        );
+    }
+
+    #[test]
+    fn points_yields_each_point_once() {
+        for (p, coefficients) in &CURVES[..3] {
+            let curve = curve(*p, *coefficients);
+            let points = curve.points().collect::<Vec<_>>();
+            assert!(points[0].is_infinity());
+            assert_eq!(points.len() as u64, enumerate(&curve));
+            assert_eq!(points.iter().collect::<BTreeSet<_>>().len(), points.len());
+            for point in &points[1..] {
+                let (_, x, y) = point.get();
+                assert!(Point::new(Rc::clone(&curve), x, y).is_ok());
+            }
+        }
+    }
+
+    #[test]
+    fn verify_order_checks_divisibility() {
+        let (p, coefficients) = CURVES[2];
+        let count = enumerate(&curve(p, coefficients));
+        let with_order = |order: u64| {
+            Rc::new(EllipticCurve::new(
+                "teaching curve".to_string(),
+                Ufeat::from(order),
+                Ufeat::from(p),
+                coefficients,
+            ))
+        };
+        assert_eq!(with_order(count).verify_order(), Ok(()));
+        let order = [3, 5, 7, 11]
+            .into_iter()
+            .find(|q| !count.is_multiple_of(*q))
+            .unwrap();
+        assert_eq!(
+            with_order(order).verify_order(),
+            Err(CurveError::OrderMismatch {
+                name: "teaching curve".to_string(),
+                order: Ufeat::from(order),
+                count: Ufeat::from(count),
+            }
+            .into())
+        );
+    }
+
+    #[test]
+    fn small_quadratic_twist_completes_the_count() {
+        for (p, coefficients) in CURVES {
+            let curve = curve(p, coefficients);
+            let twist = curve.small_quadratic_twist(p);
+            assert_eq!(enumerate(&curve) + enumerate(&twist), 2 * p + 2, "{p}");
+        }
+    }
+}
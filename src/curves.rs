@@ -0,0 +1,187 @@
+//! Named curves which fit the source representation: small coefficients of Weierstrass or Montgomery equation
+//!
+//! `order` is the order of the base point subgroup as everywhere in the crate, `EllipticCurve::cofactor` recovers the rest.
+//! Every curve comes with its standard base point, see `EllipticCurve::generator`.
+use super::{encoding::parse_hex, EllipticCurve, Ufeat};
+
+/// Parses big-endian hex of any length not exceeding `Ufeat`
+fn hex(digits: &str) -> Ufeat {
+    parse_hex(digits).expect("the registry holds valid hex")
+}
+
+/// `2^power - subtrahend`
+fn two_pow_minus(power: usize, subtrahend: Ufeat) -> Ufeat {
+    Ufeat::ONE.shl_vartime(power).wrapping_sub(&subtrahend)
+}
+
+/// `EllipticCurve::default` with the base point attached
+pub fn secp256k1() -> EllipticCurve {
+    EllipticCurve::default()
+        .with_generator(
+            hex("79BE667EF9DCBBAC55A06295CE870B07029BFCDB2DCE28D959F2815B16F81798"),
+            hex("483ADA7726A3C4655DA4FBFC0E1108A8FD17B448A68554199C47D08FFB10D4B8"),
+        )
+        .unwrap()
+}
+
+pub fn secp160k1() -> EllipticCurve {
+    EllipticCurve::new(
+        "secp160k1".to_string(),
+        hex("0100000000000000000001B8FA16DFAB9ACA16B6B3"),
+        two_pow_minus(
+            160,
+            Ufeat::ONE
+                .shl_vartime(32)
+                .wrapping_add(&Ufeat::from(21389u16)),
+        ),
+        [0, 0, 7],
+    )
+    .with_generator(
+        hex("3B4C382CE37AA192A4019E763036F4F5DD4D7EBB"),
+        hex("938CF935318FDCED6BC28286531733C3F03C4FEE"),
+    )
+    .unwrap()
+}
+
+pub fn secp192k1() -> EllipticCurve {
+    EllipticCurve::new(
+        "secp192k1".to_string(),
+        hex("FFFFFFFFFFFFFFFFFFFFFFFE26F2FC170F69466A74DEFD8D"),
+        two_pow_minus(
+            192,
+            Ufeat::ONE
+                .shl_vartime(32)
+                .wrapping_add(&Ufeat::from(4553u16)),
+        ),
+        [0, 0, 3],
+    )
+    .with_generator(
+        hex("DB4FF10EC057E9AE26B07D0280B7F4341DA5D1B1EAE06C7D"),
+        hex("9B2F2F6D9C5628A7844163D015BE86344082AA88D95E2F9D"),
+    )
+    .unwrap()
+}
+
+pub fn secp224k1() -> EllipticCurve {
+    EllipticCurve::new(
+        "secp224k1".to_string(),
+        hex("010000000000000000000000000001DCE8D2EC6184CAF0A971769FB1F7"),
+        two_pow_minus(
+            224,
+            Ufeat::ONE
+                .shl_vartime(32)
+                .wrapping_add(&Ufeat::from(6803u16)),
+        ),
+        [0, 0, 5],
+    )
+    .with_generator(
+        hex("A1455B334DF099DF30FC28A169A467E9E47075A90F7E650EB6B7A45C"),
+        hex("7E089FED7FBA344282CAFBD6F7E319F7C0B0BD59E2CA4BDB556D61A5"),
+    )
+    .unwrap()
+}
+
+/// alt_bn128 of Ethereum precompiles
+pub fn bn254() -> EllipticCurve {
+    EllipticCurve::new(
+        "BN254".to_string(),
+        hex("30644e72e131a029b85045b68181585d2833e84879b9709143e1f593f0000001"),
+        hex("30644e72e131a029b85045b68181585d97816a916871ca8d3c208c16d87cfd47"),
+        [0, 0, 3],
+    )
+    .with_generator(hex("1"), hex("2"))
+    .unwrap()
+}
+
+/// The G1 curve of the pairing-friendly BLS12-381, `bls12_381` adds G2 and the pairing
+pub fn bls12_381() -> EllipticCurve {
+    EllipticCurve::new(
+        "BLS12-381".to_string(),
+        hex("73eda753299d7d483339d80809a1d80553bda402fffe5bfeffffffff00000001"),
+        hex("1a0111ea397fe69a4b1ba7b6434bacd764774b84f38512bf6730d2a0f6b0f6241eabfffeb153ffffb9feffffffffaaab"),
+        [0, 0, 4],
+    )
+    .with_generator(
+        hex("17f1d3a73197d7942695638c4fa9ac0fc3688c4f9774b905a14e3a3f171bac586c55e83ff97a1aeffb3af00adb22c6bb"),
+        hex("08b3f481e3aaa0f1a09e30ed741d8ae4fcf5e095d5d00af600db18cb2c04b3edd03cc744a2888ae40caa232946c5e7e1"),
+    )
+    .unwrap()
+}
+
+pub fn curve25519() -> EllipticCurve {
+    EllipticCurve::new(
+        "Curve25519".to_string(),
+        hex("1000000000000000000000000000000014def9dea2f79cd65812631a5cf5d3ed"),
+        two_pow_minus(255, Ufeat::from(19u8)),
+        [486662, 1, 0],
+    )
+    .with_generator(
+        hex("9"),
+        hex("20AE19A1B8A086B4E01EDD2C7748D14C923D4D7E6D7C61B229E9C5A27ECED3D9"),
+    )
+    .unwrap()
+}
+
+pub fn m221() -> EllipticCurve {
+    EllipticCurve::new(
+        "M-221".to_string(),
+        hex("40000000000000000000000000015a08ed730e8a2f77f005042605b"),
+        two_pow_minus(221, Ufeat::from(3u8)),
+        [117050, 1, 0],
+    )
+    .with_generator(
+        hex("4"),
+        hex("0f7acdd2a4939571d1cef14eca37c228e61dbff10707dc6c08c5056d"),
+    )
+    .unwrap()
+}
+
+/// The curve of `example.rs`
+pub fn m511() -> EllipticCurve {
+    EllipticCurve::new(
+        "M-511".to_string(),
+        hex("100000000000000000000000000000000000000000000000000000000000000017B5FEFF30C7F5677AB2AEEBD13779A2AC125042A6AA10BFA54C15BAB76BAF1B"),
+        two_pow_minus(511, Ufeat::from(187u8)),
+        [530438, 1, 0],
+    )
+    .with_generator(
+        hex("5"),
+        hex("2fbdc0ad8530803d28fdbad354bb488d32399ac1cf8f6e01ee3f96389b90c809422b9429e8a43dbf49308ac4455940abe9f1dbca542093a895e30a64af056fa5"),
+    )
+    .unwrap()
+}
+
+pub fn curve448() -> EllipticCurve {
+    EllipticCurve::new(
+        "Curve448".to_string(),
+        hex("3fffffffffffffffffffffffffffffffffffffffffffffffffffffff7cca23e9c44edb49aed63690216cc2728dc58f552378c292ab5844f3"),
+        two_pow_minus(448, Ufeat::ONE.shl_vartime(224).wrapping_add(&Ufeat::ONE)),
+        [156326, 1, 0],
+    )
+    .with_generator(
+        hex("5"),
+        hex("7d235d1295f5b1f66c98ab6e58326fcecbae5d34f55545d060f75dc28df3f6edb8027e2346430d211312c4b150677af76fd7223d457b5b1a"),
+    )
+    .unwrap()
+}
+
+/// Every curve of the registry, from the smallest field up
+pub fn all() -> Vec<EllipticCurve> {
+    vec![
+        secp160k1(),
+        secp192k1(),
+        m221(),
+        secp224k1(),
+        bn254(),
+        curve25519(),
+        secp256k1(),
+        bls12_381(),
+        curve448(),
+        m511(),
+    ]
+}
+
+/// Looks a curve up by its `name`
+pub fn by_name(name: &str) -> Option<EllipticCurve> {
+    all().into_iter().find(|curve| curve.name == name)
+}
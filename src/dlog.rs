@@ -0,0 +1,776 @@
+//! Discrete logarithms on the curves: `k` with $target = k.base$
+//!
+//! The algorithms are generic, so they're practical only in small groups or when `k` is known to lie in a small range:
+//! the course exercises, exponential ElGamal, and showing what makes weak parameters weak.
+use std::collections::{hash_map::Entry, HashMap, VecDeque};
+
+use crypto_bigint::{
+    modular::runtime_mod::{DynResidue, DynResidueParams},
+    rand_core::OsRng,
+    NonZero, RandomMod,
+};
+
+use super::{padic::Lift, primality, Point, PointError, Ufeat};
+
+/// Below this many bits of the order or the interval width `bsgs` is cheaper than setting up the walks
+const SMALL_BITS: usize = 16;
+/// Smart's attack draws another lift this many times when the logarithm of `base` vanishes on it
+const LIFT_ATTEMPTS: usize = 8;
+/// The number of precomputed steps of an r-adding walk; 20 and more make it as random as a random walk
+const ADDING_WALK: usize = 32;
+/// Fruitless cycles up to this length are caught by remembering the last points of a walk; a cycle of length `2l` turns
+/// up about once in $r^l$ steps, so the 4-cycles are as frequent as the distinguished points
+const CYCLE_WINDOW: usize = 16;
+/// The walks which don't meet a distinguished point in this many times the expected length are caught in a cycle
+const WALK_LENGTH_FACTOR: u64 = 16;
+/// The search gives up after this many times the expected number of group operations
+const BUDGET_FACTOR: u64 = 16;
+
+type Residue = DynResidue<{ Ufeat::LIMBS }>;
+
+/// Baby-step giant-step: some `k` in `0..bound` with $target = k.base$, `None` if there's none
+///
+/// Takes about $\sqrt{2 bound}$ group operations and stores $\sqrt{bound / 2}$ coordinates: a baby step `j.base` stands
+/// for both `j` and `-j` as they share `x`, so the giant steps stride twice the table. Refuses with `SearchTooWide` a
+/// `bound` whose table size doesn't fit `u64`, which is far beyond any memory anyway.
+pub fn bsgs(base: &Point, target: &Point, bound: Ufeat) -> Result<Option<Ufeat>, PointError> {
+    base.require_same_curve(target)?;
+    if bound == Ufeat::ZERO {
+        return Ok(None);
+    }
+    if target.is_infinity() {
+        return Ok(Some(Ufeat::ZERO));
+    }
+    // ceil(sqrt(bound / 2)), a bit generously
+    let m = bound
+        .shr_vartime(1)
+        .sqrt_vartime()
+        .wrapping_add(&Ufeat::ONE);
+    if m.bits() > 64 {
+        return Err(PointError::SearchTooWide {
+            curve: base.curve().name.clone(),
+            method: "baby-step giant-step",
+            bits: bound.bits(),
+        });
+    }
+
+    let mut baby_steps = HashMap::new();
+    let mut runner = base.clone();
+    // known once the baby steps go round the whole group
+    let mut order = None;
+    for j in 1..=m.as_words()[0] {
+        match runner.x() {
+            None => {
+                order = Some(j);
+                break;
+            }
+            Some(x) => match baby_steps.entry(x.retrieve()) {
+                // j.base = -i.base
+                Entry::Occupied(i) => {
+                    order = Some(j + i.get());
+                    break;
+                }
+                Entry::Vacant(entry) => {
+                    entry.insert(j);
+                }
+            },
+        }
+        runner = (&runner + base)?;
+    }
+    // every multiple is then +-j.base for a `j` of the table, and the giant steps would only find the smaller of the two
+    if let Some(order) = order {
+        let Some(&j) = target.x().and_then(|x| baby_steps.get(&x.retrieve())) else {
+            return Ok(None);
+        };
+        for k in [j, order - j].map(Ufeat::from) {
+            if k < bound && (base * k)? == *target {
+                return Ok(Some(k));
+            }
+        }
+        return Ok(None);
+    }
+
+    let stride = m.shl_vartime(1);
+    let giant_step = (base * stride)?;
+    let mut giant = target.clone();
+    let mut offset = Ufeat::ZERO;
+    // `giant = target - offset.base`, and `k` is at least `offset - m` for the ones not tried yet
+    while offset.saturating_sub(&m) < bound {
+        let candidates = match giant.x() {
+            None => vec![offset],
+            Some(x) => match baby_steps.get(&x.retrieve()) {
+                Some(&j) => {
+                    let j = Ufeat::from(j);
+                    vec![offset.saturating_sub(&j), offset.wrapping_add(&j)]
+                }
+                None => vec![],
+            },
+        };
+        for k in candidates {
+            if k < bound && (base * k)? == *target {
+                return Ok(Some(k));
+            }
+        }
+        giant = (&giant - &giant_step)?;
+        offset = offset.wrapping_add(&stride);
+    }
+    Ok(None)
+}
+
+/// Pohlig-Hellman: `k` with $target = k.base$ from the logarithms modulo the prime powers dividing the order of `base`
+///
+/// `order` is the order of the curve or any other multiple of the order of `base`: it's factored with
+/// `primality::factor`, and the exact order of `base` is found from that. For each $p^e$ dividing it, the logarithm of
+/// $(n / p^e).target$ is found digit by digit in base `p` with `bsgs` in the subgroup of order `p`, and the residues are
+/// combined by the Chinese remainder theorem. The work is about $\sqrt{p}$ for the largest `p`, whatever the size of the
+/// order: a curve whose order has only small prime factors offers no security at all, which is why the standards insist
+/// on a large prime subgroup.
+///
+/// `None` if `target` isn't a multiple of `base`.
+pub fn pohlig_hellman(
+    base: &Point,
+    target: &Point,
+    order: Ufeat,
+) -> Result<Option<Ufeat>, PointError> {
+    base.require_same_curve(target)?;
+    let order = base.order(&primality::factor(order))?;
+    let mut logarithm = Ufeat::ZERO;
+    let mut modulus = Ufeat::ONE;
+    for (prime, exponent) in primality::factor(order) {
+        let power = (0..exponent).fold(Ufeat::ONE, |power, _| power.wrapping_mul(&prime));
+        let cofactor = order.wrapping_div(&power);
+        let (base, target) = ((base * cofactor)?, (target * cofactor)?);
+        // the residue modulo p^e is x = d_0 + d_1.p + ...; p^(e-1-j).(target - (d_0 + ... + d_(j-1).p^(j-1)).base) is
+        // d_j.p^(e-1).base
+        let generator = (&base * power.wrapping_div(&prime))?;
+        let (mut residue, mut place) = (Ufeat::ZERO, Ufeat::ONE);
+        for j in 1..=exponent {
+            let shift = (j..exponent).fold(Ufeat::ONE, |shift, _| shift.wrapping_mul(&prime));
+            let rest = (&target - &(&base * residue)?)?;
+            let digit = match bsgs(&generator, &(&rest * shift)?, prime)? {
+                Some(digit) => digit,
+                None => return Ok(None),
+            };
+            residue = residue.wrapping_add(&digit.wrapping_mul(&place));
+            place = place.wrapping_mul(&prime);
+        }
+        logarithm = crt(logarithm, modulus, residue, power);
+        modulus = modulus.wrapping_mul(&power);
+    }
+    // a `target` out of the subgroup of `base` may still give every digit
+    Ok(((base * logarithm)? == *target).then_some(logarithm))
+}
+
+/// Smart's attack: `k` with $target = k.base$ on an anomalous curve, the one with exactly `p` points, in polynomial time
+///
+/// The curve and the points are lifted to the p-adic integers (see `padic`), where `p` times each of them lands in the
+/// kernel of reduction and the elliptic logarithm maps that to the additive group: $\psi(p.target) = k.\psi(p.base)$,
+/// and `k` is the ratio of the first p-adic digits. Refuses with `NotAnnihilated` a `base` not of order `p`, which is
+/// any point off an anomalous curve.
+pub fn smart(base: &Point, target: &Point) -> Result<Option<Ufeat>, PointError> {
+    base.require_same_curve(target)?;
+    let curve = base.curve();
+    let p = curve.original_p();
+    if !(base * p)?.is_infinity() {
+        return Err(PointError::NotAnnihilated {
+            curve: curve.name.clone(),
+            multiple: p,
+        });
+    }
+    let (x, y) = match (base.x(), base.y()) {
+        (Some(x), Some(y)) => (x.retrieve(), y.retrieve()),
+        _ => return Ok(target.is_infinity().then_some(Ufeat::ZERO)),
+    };
+    let (target_x, target_y) = match (target.x(), target.y()) {
+        (Some(x), Some(y)) => (x.retrieve(), y.retrieve()),
+        _ => return Ok(Some(Ufeat::ZERO)),
+    };
+    for _ in 0..LIFT_ATTEMPTS {
+        let lift = Lift::random(&curve);
+        let (base_log, invertible) = DynResidue::new(&lift.logarithm(&x, &y), *curve.p()).invert();
+        if !bool::from(invertible) {
+            continue;
+        }
+        let k = (DynResidue::new(&lift.logarithm(&target_x, &target_y), *curve.p()) * base_log)
+            .retrieve();
+        return Ok(((base * k)? == *target).then_some(k));
+    }
+    Ok(None)
+}
+
+/// `x` modulo `m.n` with $x = a \mod m$ and $x = b \mod n$ for coprime `m` and `n`
+fn crt(a: Ufeat, m: Ufeat, b: Ufeat, n: Ufeat) -> Ufeat {
+    let multiply = |x: Ufeat, y: Ufeat| Ufeat::const_rem_wide(x.mul_wide(&y), &n).0;
+    // x = a + m.t with t = (b - a) / m mod n
+    let (inverse, _) = m.wrapping_rem(&n).inv_mod(&n);
+    let t = multiply(b.sub_mod(&a.wrapping_rem(&n), &n), inverse);
+    a.wrapping_add(&m.wrapping_mul(&t))
+}
+
+/// The representative of $\{point, -point\}$ with the smaller `y`, and whether it's the negation
+fn canonical(point: Point) -> (Point, bool) {
+    match point.y() {
+        Some(y) if y.retrieve() > (-*y).retrieve() => (-&point, true),
+        _ => (point, false),
+    }
+}
+
+/// The index of the step to take from a point with the given `x`; the low bits are left to tell distinguished points
+fn step_index(x: &Ufeat, steps: usize) -> usize {
+    // Fibonacci hashing of the lowest word spreads every bit of it over the top ones
+    (x.as_words()[0].wrapping_mul(0x9e37_79b9_7f4a_7c15) >> 32) as usize % steps
+}
+
+/// Whether the lowest `bits` bits of `x` are zero
+fn is_distinguished(x: &Ufeat, bits: u32) -> bool {
+    x.as_words()[0] & ((1 << bits) - 1) == 0
+}
+
+/// Pollard's rho with an r-adding walk on the classes $\{X, -X\}$ and distinguished points: `k` with
+/// $target = k.base$ for `base` of prime `order`
+///
+/// A walk starts at a random $a.base + b.target$ and adds precomputed combinations of the same form chosen by `x`, keeping
+/// track of `a` and `b`; it ends at a point with the lowest bits of `x` zero. Two walks ending at the same point give
+/// $a + b.k = a' + b'.k \mod order$. Walking on the classes of the negation map takes $\sqrt{2}$ times fewer steps, about
+/// $\sqrt{\pi . order / 4}$, but falls into fruitless cycles such as $X \to -(X + R) \to X$; the short ones are escaped by
+/// doubling the smallest point of the cycle, and the rare longer ones by the walk length limit.
+///
+/// `None` if nothing is found within 16 times the expected work, which means `target` isn't a multiple of `base` in
+/// practice. Refuses with `CompositeOrder` an `order` which isn't prime, as the coefficients are inverted modulo it,
+/// and with `SearchTooWide` one over 128 bits: $2^{64}$ steps are out of reach anyway, and the walk lengths would
+/// overflow.
+pub fn rho(base: &Point, target: &Point, order: Ufeat) -> Result<Option<Ufeat>, PointError> {
+    base.require_same_curve(target)?;
+    if !primality::baillie_psw(&order) {
+        return Err(PointError::CompositeOrder {
+            curve: base.curve().name.clone(),
+            order,
+        });
+    }
+    if order.bits() <= SMALL_BITS {
+        return bsgs(base, target, order);
+    }
+    if order.bits() > 128 {
+        return Err(PointError::SearchTooWide {
+            curve: base.curve().name.clone(),
+            method: "Pollard's rho",
+            bits: order.bits(),
+        });
+    }
+    let params = DynResidueParams::new(&order);
+    let modulus = NonZero::new(order).unwrap();
+    let random = || DynResidue::new(&Ufeat::random_mod(&mut OsRng, &modulus), params);
+    let combination = |a: &Residue, b: &Residue| -> Result<Point, PointError> {
+        &(base * a.retrieve())? + &(target * b.retrieve())?
+    };
+
+    // a distinguished point is met every 2^(bits/2 - 4) steps, so that there're a few dozen walks
+    let distinguished_bits = (order.bits() / 2).saturating_sub(4) as u32;
+    let walk_limit = WALK_LENGTH_FACTOR << distinguished_bits;
+    let budget = BUDGET_FACTOR.saturating_mul(1 << (order.bits() / 2).min(63));
+    let steps = (0..ADDING_WALK)
+        .map(|_| {
+            let (a, b) = (random(), random());
+            Ok((combination(&a, &b)?, a, b))
+        })
+        .collect::<Result<Vec<_>, PointError>>()?;
+    let solve = |a: Residue, b: Residue, other_a: Residue, other_b: Residue| {
+        // a + b.k = a' + b'.k
+        let (inverse, invertible) = (other_b - b).invert();
+        if !bool::from(invertible) {
+            return Ok(None);
+        }
+        let k = ((a - other_a) * inverse).retrieve();
+        Ok::<_, PointError>(((base * k)? == *target).then_some(k))
+    };
+
+    let mut trails = HashMap::new();
+    let mut operations = 0;
+    while operations < budget {
+        let (mut a, mut b) = (random(), random());
+        let (mut point, negated) = canonical(combination(&a, &b)?);
+        if negated {
+            (a, b) = (-a, -b);
+        }
+        let mut recent: VecDeque<(Ufeat, Point, Residue, Residue)> = VecDeque::new();
+        for _ in 0..walk_limit {
+            let x = match point.x() {
+                Some(x) => x.retrieve(),
+                // a.base + b.target = 0
+                None => match solve(a, b, DynResidue::zero(params), DynResidue::zero(params))? {
+                    Some(k) => return Ok(Some(k)),
+                    None => break,
+                },
+            };
+            if is_distinguished(&x, distinguished_bits) {
+                if let Some(&(other_a, other_b)) = trails.get(&x) {
+                    if let Some(k) = solve(a, b, other_a, other_b)? {
+                        return Ok(Some(k));
+                    }
+                }
+                trails.insert(x, (a, b));
+                break;
+            }
+
+            let (next, mut next_a, mut next_b) =
+                if let Some(start) = recent.iter().position(|(seen, ..)| *seen == x) {
+                    // a fruitless cycle: the walk has been here already, so it leaves by doubling the smallest point
+                    let (_, smallest, smallest_a, smallest_b) = recent
+                        .drain(start..)
+                        .min_by(|left, right| left.0.cmp(&right.0))
+                        .unwrap();
+                    recent.clear();
+                    (
+                        (&smallest + &smallest)?,
+                        smallest_a + smallest_a,
+                        smallest_b + smallest_b,
+                    )
+                } else {
+                    let (step, step_a, step_b) = &steps[step_index(&x, ADDING_WALK)];
+                    ((&point + step)?, a + step_a, b + step_b)
+                };
+            let (next, negated) = canonical(next);
+            if negated {
+                (next_a, next_b) = (-next_a, -next_b);
+            }
+            if recent.len() == CYCLE_WINDOW {
+                recent.pop_front();
+            }
+            recent.push_back((x, point, a, b));
+            (point, a, b) = (next, next_a, next_b);
+            operations += 1;
+        }
+    }
+    Ok(None)
+}
+
+/// Pollard's kangaroo (lambda) method for `k` in `low..high` with $target = k.base$
+///
+/// A tame kangaroo starts at a known multiple of `base` and wild ones start at `target`, each jumping by a power of two
+/// times `base` chosen by `x`; they leave the distinguished points they land on as traps, and once a wild kangaroo lands
+/// on a tame one's trap, $k$ is the difference of their distances. The negation map halves the interval: with the
+/// middle `m` of it, the logarithm of either $target - m.base$ or its negation lies in the upper half, so two wild
+/// kangaroos go after the half with a tame one, taking a few times $\sqrt{(high - low) / 2}$ jumps in total.
+///
+/// `None` if nothing is found within 16 times the expected work; refuses with `SearchTooWide` the intervals wider than
+/// $2^{126}$.
+pub fn kangaroo(
+    base: &Point,
+    target: &Point,
+    low: Ufeat,
+    high: Ufeat,
+) -> Result<Option<Ufeat>, PointError> {
+    base.require_same_curve(target)?;
+    if high <= low {
+        return Ok(None);
+    }
+    let width = high.wrapping_sub(&low);
+    if width.bits() <= SMALL_BITS {
+        let shifted = (target - &(base * low)?)?;
+        return Ok(bsgs(base, &shifted, width)?.map(|k| k.wrapping_add(&low)));
+    }
+    if width.bits() > 126 {
+        return Err(PointError::SearchTooWide {
+            curve: base.curve().name.clone(),
+            method: "Pollard's kangaroos",
+            bits: width.bits(),
+        });
+    }
+
+    // target - middle.base = ±k' with k' in 0..=half
+    let middle = low.wrapping_add(&width.shr_vartime(1));
+    let centered = (target - &(base * middle)?)?;
+    let half = {
+        let words = width.shr_vartime(1);
+        let words = words.as_words();
+        words[0] as u128 | (words[1] as u128) << 64
+    };
+    let to_ufeat = |n: u128| Ufeat::from_u128(n);
+
+    // the jumps 1, 2, 4, ..., 2^(n-1) average to about sqrt(half) / 2, so that a kangaroo crosses the interval in about
+    // sqrt(half) jumps
+    let root = half.isqrt();
+    let count = (1..)
+        .find(|&n: &u32| ((1u128 << n) - 1) / n as u128 >= root / 2)
+        .unwrap();
+    let jumps = (0..count)
+        .map(|i| Ok(((base * to_ufeat(1 << i))?, 1u128 << i)))
+        .collect::<Result<Vec<_>, PointError>>()?;
+    let distinguished_bits = (128 - root.leading_zeros()).saturating_sub(3);
+    let budget = BUDGET_FACTOR.saturating_mul(root as u64);
+
+    // (position, distance travelled, kind): the tame one counts the distance from infinity, the wild ones from the start
+    let mut herd = [
+        ((base * to_ufeat(half / 2))?, half / 2, Kind::Tame),
+        (centered.clone(), 0, Kind::Wild { negated: false }),
+        (-&centered, 0, Kind::Wild { negated: true }),
+    ];
+    let mut traps: HashMap<Ufeat, (u128, Kind)> = HashMap::new();
+    let check = |k_prime: i128| -> Result<Option<Ufeat>, PointError> {
+        // k = middle + k'
+        let k = if k_prime < 0 {
+            middle.wrapping_sub(&to_ufeat(k_prime.unsigned_abs()))
+        } else {
+            middle.wrapping_add(&to_ufeat(k_prime as u128))
+        };
+        Ok((k >= low && k < high && (base * k)? == *target).then_some(k))
+    };
+
+    for _ in 0..budget {
+        for (position, distance, kind) in herd.iter_mut() {
+            let index = match position.x() {
+                Some(x) => {
+                    let x = x.retrieve();
+                    if is_distinguished(&x, distinguished_bits) {
+                        if let Some(&(other_distance, other_kind)) = traps.get(&x) {
+                            for candidate in collision(*distance, *kind, other_distance, other_kind)
+                            {
+                                if let Some(k) = check(candidate)? {
+                                    return Ok(Some(k));
+                                }
+                            }
+                        }
+                        traps.entry(x).or_insert((*distance, *kind));
+                    }
+                    step_index(&x, jumps.len())
+                }
+                // a wild kangaroo at infinity has run exactly the negation of its start
+                None => {
+                    if let Kind::Wild { negated } = *kind {
+                        let k_prime = -(*distance as i128);
+                        if let Some(k) = check(if negated { -k_prime } else { k_prime })? {
+                            return Ok(Some(k));
+                        }
+                    }
+                    0
+                }
+            };
+            let (jump, length) = &jumps[index];
+            *position = (&*position + jump)?;
+            *distance += length;
+        }
+    }
+    Ok(None)
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Kind {
+    Tame,
+    /// Started at $\pm(target - middle.base) = \pm k'.base$
+    Wild {
+        negated: bool,
+    },
+}
+
+/// The candidates for $k'$ when kangaroos are found at the same `x`, that is at the same point or at the negations
+///
+/// A tame kangaroo is at $t.base$ and a wild one at $(\pm k' + d).base$, so each pair gives an equation for $k'$ for
+/// each sign; the pairs of wild ones give $2k'$.
+fn collision(distance: u128, kind: Kind, other_distance: u128, other_kind: Kind) -> Vec<i128> {
+    let (d, e) = (distance as i128, other_distance as i128);
+    let half = |double: i128| {
+        (double % 2 == 0)
+            .then_some(double / 2)
+            .into_iter()
+            .collect()
+    };
+    match (kind, other_kind) {
+        (Kind::Tame, Kind::Tame) => vec![],
+        (Kind::Tame, Kind::Wild { negated }) | (Kind::Wild { negated }, Kind::Tame) => {
+            let (t, w) = if kind == Kind::Tame { (d, e) } else { (e, d) };
+            // k' + w = ±t or -k' + w = ±t
+            let candidates = [t - w, -t - w];
+            if negated {
+                candidates.map(|candidate| -candidate).to_vec()
+            } else {
+                candidates.to_vec()
+            }
+        }
+        (
+            Kind::Wild { negated },
+            Kind::Wild {
+                negated: other_negated,
+            },
+        ) if negated == other_negated => {
+            // k' + d = -(k' + e), the other sign means d = e
+            half(if negated { d + e } else { -d - e })
+        }
+        (Kind::Wild { negated }, Kind::Wild { .. }) => {
+            // k' + d = -k' + e, the other sign means d = -e
+            half(if negated { d - e } else { e - d })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use super::*;
+    use crate::EllipticCurve;
+
+    /// $y^2 = x^3 + 3x + 5$ over 10007 has $10125 = 3^4.5^3$ points
+    const SMOOTH_ORDER: u16 = 10125;
+
+    fn smooth() -> Rc<EllipticCurve> {
+        Rc::new(EllipticCurve::new(
+            "smooth".to_string(),
+            Ufeat::from(SMOOTH_ORDER),
+            Ufeat::from(10007u16),
+            [0, 3, 5],
+        ))
+    }
+
+    /// $y^2 = x^3 + 2x + 5$ over 131101 has a prime number of points, just past `SMALL_BITS`
+    const PRIME_ORDER: u32 = 131749;
+
+    fn prime() -> Rc<EllipticCurve> {
+        Rc::new(EllipticCurve::new(
+            "prime".to_string(),
+            Ufeat::from(PRIME_ORDER),
+            Ufeat::from(131101u32),
+            [0, 2, 5],
+        ))
+    }
+
+    /// A point generating the whole group of `smooth`, which is cyclic
+    fn smooth_generator(curve: &Rc<EllipticCurve>) -> Point {
+        let factorization = primality::factor(Ufeat::from(SMOOTH_ORDER));
+        curve
+            .points()
+            .find(|point| point.order(&factorization) == Ok(Ufeat::from(SMOOTH_ORDER)))
+            .unwrap()
+    }
+
+    fn anomalous(p: u64, coefficients: [isize; 3]) -> Rc<EllipticCurve> {
+        Rc::new(
+            EllipticCurve::try_new_anomalous(
+                format!("anomalous over {p}"),
+                Ufeat::from(p),
+                Ufeat::from(p),
+                coefficients,
+            )
+            .unwrap(),
+        )
+    }
+
+    #[test]
+    fn smart_solves_known_anomalous_curves() {
+        for (p, coefficients) in [
+            (10007u64, [0, 22, 9]),
+            // the example of the p-adic elliptic logarithm in Sage's documentation
+            (235322474717419, [0, 0, 8856682]),
+        ] {
+            let curve = anomalous(p, coefficients);
+            assert_eq!(curve.compute_order(), Ufeat::from(p));
+            let base = curve.points().nth(1).unwrap();
+            for k in [1, 2, p / 3, p - 1] {
+                let target = (&base * Ufeat::from(k)).unwrap();
+                assert_eq!(smart(&base, &target).unwrap(), Some(Ufeat::from(k)));
+            }
+            let infinity = (&base * Ufeat::from(p)).unwrap();
+            assert_eq!(smart(&base, &infinity).unwrap(), Some(Ufeat::ZERO));
+        }
+    }
+
+    #[test]
+    fn smart_refuses_points_of_other_order() {
+        let curve = Rc::new(EllipticCurve::new(
+            "E".to_string(),
+            Ufeat::from(5003u16),
+            Ufeat::from(10007u16),
+            [0, 2, 3],
+        ));
+        let point = curve.points().nth(1).unwrap();
+        assert!(matches!(
+            smart(&point, &point),
+            Err(PointError::NotAnnihilated { .. })
+        ));
+    }
+
+    #[test]
+    fn bsgs_finds_logarithms_below_the_bound() {
+        let curve = smooth();
+        let base = smooth_generator(&curve);
+        let order = Ufeat::from(SMOOTH_ORDER);
+        for k in [0u16, 1, 2, 5000, SMOOTH_ORDER - 1] {
+            let target = (&base * Ufeat::from(k)).unwrap();
+            assert_eq!(bsgs(&base, &target, order), Ok(Some(Ufeat::from(k))));
+        }
+        let target = (&base * Ufeat::from(5000u16)).unwrap();
+        assert_eq!(bsgs(&base, &target, Ufeat::from(4000u16)), Ok(None));
+        assert_eq!(bsgs(&base, &target, Ufeat::ZERO), Ok(None));
+        // the baby steps go round the group when the bound is past the order
+        let subgroup = (&base * Ufeat::from(3u8)).unwrap();
+        let target = (&subgroup * Ufeat::from(3000u16)).unwrap();
+        let wide = Ufeat::ONE.shl_vartime(30);
+        assert_eq!(
+            bsgs(&subgroup, &target, wide),
+            Ok(Some(Ufeat::from(3000u16)))
+        );
+    }
+
+    #[test]
+    fn bsgs_misses_a_point_out_of_the_subgroup() {
+        let curve = smooth();
+        let base = smooth_generator(&curve);
+        let subgroup = (&base * Ufeat::from(3u8)).unwrap();
+        let order = Ufeat::from(SMOOTH_ORDER);
+        assert_eq!(bsgs(&subgroup, &base, order), Ok(None));
+        assert_eq!(bsgs(&subgroup, &base, Ufeat::ONE.shl_vartime(30)), Ok(None));
+    }
+
+    #[test]
+    fn bsgs_refuses_a_table_too_large() {
+        let curve = smooth();
+        let base = curve.points().nth(1).unwrap();
+        assert_eq!(
+            bsgs(&base, &base, Ufeat::ONE.shl_vartime(200)),
+            Err(PointError::SearchTooWide {
+                curve: curve.name.clone(),
+                method: "baby-step giant-step",
+                bits: 201,
+            })
+        );
+    }
+
+    #[test]
+    fn pohlig_hellman_recovers_logarithms_in_a_smooth_group() {
+        let curve = smooth();
+        let base = smooth_generator(&curve);
+        for k in [0u16, 1, 81, 125, 7777, SMOOTH_ORDER - 1] {
+            let target = (&base * Ufeat::from(k)).unwrap();
+            // any multiple of the order of `base` does
+            for order in [SMOOTH_ORDER as u32, 2 * SMOOTH_ORDER as u32] {
+                assert_eq!(
+                    pohlig_hellman(&base, &target, Ufeat::from(order)),
+                    Ok(Some(Ufeat::from(k)))
+                );
+            }
+        }
+        // the subgroup of order 405, whose order is found from the one of the curve
+        let subgroup = (&base * Ufeat::from(25u8)).unwrap();
+        let target = (&subgroup * Ufeat::from(400u16)).unwrap();
+        assert_eq!(
+            pohlig_hellman(&subgroup, &target, Ufeat::from(SMOOTH_ORDER)),
+            Ok(Some(Ufeat::from(400u16)))
+        );
+    }
+
+    #[test]
+    fn pohlig_hellman_misses_a_point_out_of_the_subgroup() {
+        let curve = smooth();
+        let base = smooth_generator(&curve);
+        let subgroup = (&base * Ufeat::from(3u8)).unwrap();
+        assert_eq!(
+            pohlig_hellman(&subgroup, &base, Ufeat::from(SMOOTH_ORDER)),
+            Ok(None)
+        );
+        // a wrong order isn't annihilating
+        assert!(matches!(
+            pohlig_hellman(&base, &subgroup, Ufeat::from(3375u16)),
+            Err(PointError::NotAnnihilated { .. })
+        ));
+    }
+
+    #[test]
+    fn crt_combines_coprime_residues() {
+        let crt = |a: u32, m: u32, b: u32, n: u32| {
+            crt(
+                Ufeat::from(a),
+                Ufeat::from(m),
+                Ufeat::from(b),
+                Ufeat::from(n),
+            )
+            .as_words()[0]
+        };
+        assert_eq!(crt(2, 3, 3, 5), 8);
+        assert_eq!(crt(0, 1, 4, 7), 4);
+        for (m, n) in [(81, 125), (125, 81), (4, 9999)] {
+            for x in [0, 1, 17, m * n - 1] {
+                assert_eq!(crt(x % m, m, x % n, n), x as u64, "{x} mod {m}.{n}");
+            }
+        }
+    }
+
+    #[test]
+    fn rho_recovers_known_logarithms() {
+        let curve = prime();
+        let base = curve.points().nth(1).unwrap();
+        let order = Ufeat::from(PRIME_ORDER);
+        for k in [12345, PRIME_ORDER - 1] {
+            let target = (&base * Ufeat::from(k)).unwrap();
+            assert_eq!(rho(&base, &target, order), Ok(Some(Ufeat::from(k))));
+        }
+    }
+
+    #[test]
+    fn rho_refuses_orders_it_cant_walk() {
+        let curve = prime();
+        let base = curve.points().nth(1).unwrap();
+        for order in [
+            Ufeat::from(PRIME_ORDER - 1),
+            Ufeat::from(PRIME_ORDER).wrapping_mul(&Ufeat::from(3u8)),
+            Ufeat::from(SMOOTH_ORDER),
+            Ufeat::ZERO,
+        ] {
+            assert_eq!(
+                rho(&base, &base, order),
+                Err(PointError::CompositeOrder {
+                    curve: curve.name.clone(),
+                    order
+                })
+            );
+        }
+
+        let secp256k1 = Rc::new(crate::curves::secp256k1());
+        let g = secp256k1.generator().unwrap();
+        assert_eq!(
+            rho(&g, &g, *secp256k1.order()),
+            Err(PointError::SearchTooWide {
+                curve: secp256k1.name.clone(),
+                method: "Pollard's rho",
+                bits: 256,
+            })
+        );
+    }
+
+    #[test]
+    fn kangaroo_catches_logarithms_in_the_interval() {
+        let curve = prime();
+        let base = curve.points().nth(1).unwrap();
+        let (low, width) = (1000u32, 1 << 17);
+        let high = low + width;
+        for k in [low + 5, low + width / 2 + 3, high - 1] {
+            let target = (&base * Ufeat::from(k)).unwrap();
+            assert_eq!(
+                kangaroo(&base, &target, Ufeat::from(low), Ufeat::from(high)),
+                Ok(Some(Ufeat::from(k)))
+            );
+        }
+        // below `SMALL_BITS` it's `bsgs` shifted to the interval
+        let target = (&base * Ufeat::from(low + 700)).unwrap();
+        assert_eq!(
+            kangaroo(&base, &target, Ufeat::from(low), Ufeat::from(low + 1000)),
+            Ok(Some(Ufeat::from(low + 700)))
+        );
+        assert_eq!(
+            kangaroo(&base, &target, Ufeat::from(low), Ufeat::from(low)),
+            Ok(None)
+        );
+    }
+
+    #[test]
+    fn kangaroo_refuses_intervals_too_wide() {
+        let secp256k1 = Rc::new(crate::curves::secp256k1());
+        let g = secp256k1.generator().unwrap();
+        assert_eq!(
+            kangaroo(&g, &g, Ufeat::ZERO, Ufeat::ONE.shl_vartime(127)),
+            Err(PointError::SearchTooWide {
+                curve: secp256k1.name.clone(),
+                method: "Pollard's kangaroos",
+                bits: 128,
+            })
+        );
+    }
+}
@@ -0,0 +1,188 @@
+//! SEC1 encoding of points (section 2.3.3 of https://www.secg.org/sec1-v2.pdf) and the hex notation of big numbers
+//!
+//! A coordinate takes as many bytes as the modulus does. The point at infinity is the single zero byte, a compressed
+//! point is the parity of `y` (`0x02` or `0x03`) followed by `x`, an uncompressed one is `0x04` followed by `x` and `y`.
+use std::rc::Rc;
+
+use crypto_bigint::{modular::runtime_mod::DynResidue, Encoding};
+
+use super::{field, EllipticCurve, EncodingError, Point, PointError, Ufeat};
+
+const INFINITY: u8 = 0x00;
+const EVEN: u8 = 0x02;
+const ODD: u8 = 0x03;
+const UNCOMPRESSED: u8 = 0x04;
+
+/// `0x` followed by the digits without the leading zeros of the whole `Ufeat` width
+pub(crate) fn to_hex(n: &Ufeat) -> String {
+    let digits = format!("{n:x}");
+    match digits.trim_start_matches('0') {
+        "" => "0x0".to_string(),
+        digits => format!("0x{digits}"),
+    }
+}
+
+/// Reads big-endian hex, with or without `0x`, of any length fitting `Ufeat`
+pub(crate) fn parse_hex(text: &str) -> Result<Ufeat, EncodingError> {
+    let digits = text.strip_prefix("0x").unwrap_or(text);
+    if digits.is_empty()
+        || digits.len() > Ufeat::BITS / 4
+        || !digits.bytes().all(|digit| digit.is_ascii_hexdigit())
+    {
+        return Err(EncodingError::InvalidHex {
+            input: text.to_string(),
+        });
+    }
+    Ok(Ufeat::from_be_hex(&format!(
+        "{digits:0>width$}",
+        width = Ufeat::BITS / 4
+    )))
+}
+
+#[cfg(feature = "serde")]
+pub(crate) fn bytes_to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+#[cfg(feature = "serde")]
+pub(crate) fn hex_to_bytes(text: &str) -> Result<Vec<u8>, EncodingError> {
+    let invalid = || EncodingError::InvalidHex {
+        input: text.to_string(),
+    };
+    // `from_str_radix` alone would take a sign, as in "+f"
+    if text.len() % 2 == 1 || !text.bytes().all(|digit| digit.is_ascii_hexdigit()) {
+        return Err(invalid());
+    }
+    (0..text.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&text[i..i + 2], 16).map_err(|_| invalid()))
+        .collect()
+}
+
+impl EllipticCurve {
+    /// The length of an encoded coordinate
+    pub fn field_bytes(&self) -> usize {
+        self.original_p().bits().div_ceil(8)
+    }
+
+    /// The coordinates of an encoded point, `None` for the point at infinity; they're yet to be checked against the
+    /// curve equation unless the point was compressed
+    pub(crate) fn decode_sec1(
+        &self,
+        bytes: &[u8],
+    ) -> Result<Option<(Ufeat, Ufeat)>, EncodingError> {
+        let length = self.field_bytes();
+        let (&tag, coordinates) = bytes.split_first().ok_or(EncodingError::InvalidLength {
+            expected: 1,
+            found: 0,
+        })?;
+        let expected = match tag {
+            INFINITY => 0,
+            EVEN | ODD => length,
+            UNCOMPRESSED => 2 * length,
+            tag => return Err(EncodingError::UnknownTag { tag }),
+        };
+        if coordinates.len() != expected {
+            return Err(EncodingError::InvalidLength {
+                expected: expected + 1,
+                found: bytes.len(),
+            });
+        }
+        let integer = |bytes: &[u8]| {
+            let mut buffer = vec![0u8; Ufeat::BYTES];
+            buffer[Ufeat::BYTES - bytes.len()..].copy_from_slice(bytes);
+            Ufeat::from_be_slice(&buffer)
+        };
+        match tag {
+            INFINITY => Ok(None),
+            UNCOMPRESSED => Ok(Some((
+                integer(&coordinates[..length]),
+                integer(&coordinates[length..]),
+            ))),
+            _ => {
+                let x = integer(coordinates);
+                if x >= self.original_p() {
+                    return Err(PointError::CoordinateOutOfField {
+                        curve: self.name.clone(),
+                        coordinate: x,
+                    }
+                    .into());
+                }
+                let y =
+                    field::sqrt(&self.rhs(&DynResidue::new(&x, *self.p()))).ok_or_else(|| {
+                        PointError::NoPointWithX {
+                            curve: self.name.clone(),
+                            x,
+                        }
+                    })?;
+                // the roots are `y` and `p - y`, one of them is odd unless both are zero
+                let y = if y.retrieve().bit_vartime(0) == (tag == ODD) {
+                    y
+                } else {
+                    -y
+                };
+                if y.retrieve().bit_vartime(0) != (tag == ODD) {
+                    return Err(PointError::NoPointWithX {
+                        curve: self.name.clone(),
+                        x,
+                    }
+                    .into());
+                }
+                Ok(Some((x, y.retrieve())))
+            }
+        }
+    }
+
+    /// The counterpart of `decode_sec1`
+    pub(crate) fn encode_sec1(
+        &self,
+        coordinates: Option<(Ufeat, Ufeat)>,
+        compressed: bool,
+    ) -> Vec<u8> {
+        let (x, y) = match coordinates {
+            Some(coordinates) => coordinates,
+            None => return vec![INFINITY],
+        };
+        let length = self.field_bytes();
+        let tail = |n: Ufeat| n.to_be_bytes()[Ufeat::BYTES - length..].to_vec();
+        let mut bytes = Vec::with_capacity(1 + 2 * length);
+        if compressed {
+            bytes.push(if y.bit_vartime(0) { ODD } else { EVEN });
+            bytes.extend(tail(x));
+        } else {
+            bytes.push(UNCOMPRESSED);
+            bytes.extend(tail(x));
+            bytes.extend(tail(y));
+        }
+        bytes
+    }
+}
+
+impl Point {
+    /// SEC1 encoding of the point, compressed to `x` and the parity of `y` if asked
+    pub fn to_sec1(&self, compressed: bool) -> Vec<u8> {
+        let (curve, x, y) = self.get();
+        curve.encode_sec1(x.zip(y), compressed)
+    }
+
+    /// Decodes either form of SEC1 and validates the point with `Point::new`
+    pub fn from_sec1(curve: Rc<EllipticCurve>, bytes: &[u8]) -> Result<Point, EncodingError> {
+        Ok(match curve.decode_sec1(bytes)? {
+            Some((x, y)) => Point::new(curve, Some(x), Some(y))?,
+            None => Point::new(curve, None, None)?,
+        })
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hex_to_bytes_takes_digits_only() {
+        assert_eq!(hex_to_bytes("00ff7A").unwrap(), [0, 0xff, 0x7a]);
+        for text in ["+f", "0+", "-1", " 1", "0x", "abc", "é0"] {
+            assert!(hex_to_bytes(text).is_err(), "{text}");
+        }
+    }
+}
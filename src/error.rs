@@ -0,0 +1,176 @@
+//! Errors of the crate, one type per realm: curve parameters, extension fields, points, encodings and protocols on top of
+//! the curves
+//!
+//! Each type carries the offending values and the name of the curve, so a message tells what went wrong without a
+//! debugger. `Error` unites them for the code which deals with several realms at once.
+// this replaced `Errs`, which mirrored the exception names of the source and said nothing about the values
+use thiserror::Error;
+
+use super::{encoding::to_hex as hex, Ufeat};
+
+/// Reasons to refuse curve parameters or to fail a computation concerning the whole curve
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum CurveError {
+    #[error("the order {} should exceed 2: binary fields are a different realm", hex(.order))]
+    OrderTooSmall { order: Ufeat },
+    #[error("the modulus {} is even: such fields are useless here, also modulus can't be zero", hex(.modulus))]
+    EvenModulus { modulus: Ufeat },
+    #[error("the modulus {} isn't prime, so the residues don't form a field", hex(.modulus))]
+    CompositeModulus { modulus: Ufeat },
+    #[error("the coefficient `{coefficient}` isn't less than the modulus {}", hex(.modulus))]
+    CoefficientOutOfField {
+        coefficient: &'static str,
+        modulus: Ufeat,
+    },
+    #[error("the curve {name} is singular: its discriminant is zero modulo `p`")]
+    Singular { name: String },
+    #[error("no multiple of the order {} of the curve {name} fits Hasse interval", hex(.order))]
+    WrongOrder { name: String, order: Ufeat },
+    #[error("the order {} of the curve {name} doesn't divide its number of points {}", hex(.order), hex(.count))]
+    OrderMismatch {
+        name: String,
+        order: Ufeat,
+        count: Ufeat,
+    },
+    #[error("the curve {name} has exactly `p` points and is susceptible to Smart's attack")]
+    Anomalous { name: String },
+    #[error("the order {} of the curve {name} isn't prime", hex(.order))]
+    CompositeOrder { name: String, order: Ufeat },
+    #[error("the field of the curve {name} has {bits} bits, too large for naive point counting")]
+    FieldTooLarge { name: String, bits: usize },
+    #[error("the generator of the curve {name} is invalid")]
+    InvalidGenerator {
+        name: String,
+        #[source]
+        source: PointError,
+    },
+    #[error("the twist of the curve {name} by the square {} is the curve itself", hex(.d))]
+    SquareTwist { name: String, d: Ufeat },
+    #[error("no curve fitting the requirements was found within {attempts} attempts")]
+    GenerationFailed { attempts: usize },
+}
+
+/// Reasons to refuse the parameters of an extension field
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+// with `u8192` the integers dwarf the other variants, yet boxing them would clutter every match for the sake of rare paths
+#[cfg_attr(feature = "u8192", allow(clippy::large_enum_variant))]
+pub enum FieldError {
+    #[error("the modulus of the extension of degree {degree} isn't irreducible, so the residues don't form a field")]
+    Reducible { degree: usize },
+    #[error("the field of characteristic {} can't hold the coefficients modulo {}", hex(.found), hex(.expected))]
+    CharacteristicMismatch { expected: Ufeat, found: Ufeat },
+    #[error("the field of degree {degree} has no roots of unity of order {}: the degree is below the embedding degree", hex(.order))]
+    NoRootsOfUnity { order: Ufeat, degree: usize },
+    #[error("the field of degree {degree} has no square root of {}, so the twist by it doesn't map there", hex(.d))]
+    NoSquareRoot { d: Ufeat, degree: usize },
+}
+
+/// Reasons to refuse coordinates or to fail the group operation
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+// see `FieldError`
+#[cfg_attr(feature = "u8192", allow(clippy::large_enum_variant))]
+pub enum PointError {
+    #[error("the coordinate {} is out of the field of the curve {curve}", hex(.coordinate))]
+    CoordinateOutOfField { curve: String, coordinate: Ufeat },
+    #[error("the point ({}, {}) isn't on the curve {curve}", hex(.x), hex(.y))]
+    NotOnCurve { curve: String, x: Ufeat, y: Ufeat },
+    #[error("the point ({}, {}) isn't on the curve {curve}", hexes(.x), hexes(.y))]
+    NotOnCurveOver {
+        curve: String,
+        x: Vec<Ufeat>,
+        y: Vec<Ufeat>,
+    },
+    #[error("no point of the curve {curve} has `x` equal to {}", hex(.x))]
+    NoPointWithX { curve: String, x: Ufeat },
+    #[error("the operands are on different curves: {left} and {right}")]
+    DifferentCurves { left: String, right: String },
+    /// Only possible when an operand was built bypassing `Point::new`
+    #[error("the result of the group operation on the curve {curve} is off the curve")]
+    ResultOffCurve { curve: String },
+    #[error("the multiple {} doesn't annihilate the point of the curve {curve}", hex(.multiple))]
+    NotAnnihilated { curve: String, multiple: Ufeat },
+    #[error("no multiple of the point of the curve {curve} in {low}..={high} is at infinity")]
+    OrderOutOfRange { curve: String, low: u64, high: u64 },
+    #[error("the factorization given for the point of the curve {curve} overflows the integers")]
+    FactorizationOverflow { curve: String },
+    #[error("the factor {} given for the point of the curve {curve} isn't prime", hex(.factor))]
+    CompositeFactor { curve: String, factor: Ufeat },
+    #[error("the order {} given for the point of the curve {curve} isn't prime", hex(.order))]
+    CompositeOrder { curve: String, order: Ufeat },
+    #[error("the logarithm on the curve {curve} is searched among {bits}-bit numbers, too many for {method}")]
+    SearchTooWide {
+        curve: String,
+        method: &'static str,
+        bits: usize,
+    },
+    /// Miller's loop met a zero or a pole of its function at the second point, typically a multiple of the first one
+    #[error("the pairing of the points of the curve {curve} can't be evaluated: a line of Miller's loop vanishes at the second one")]
+    DegeneratePairing { curve: String },
+    #[error("the points given as a kernel on the curve {curve} aren't closed under addition")]
+    NotSubgroup { curve: String },
+    #[error("an isogeny of the curve {curve} can't have degree zero")]
+    ZeroDegree { curve: String },
+}
+
+/// Reasons to refuse a serialized curve or point
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum EncodingError {
+    #[error("`{input}` isn't a hexadecimal string")]
+    InvalidHex { input: String },
+    #[error("expected {expected} bytes, found {found}")]
+    InvalidLength { expected: usize, found: usize },
+    #[error("unknown point encoding tag {tag:#04x}")]
+    UnknownTag { tag: u8 },
+    #[error("no curve named {name} in the registry")]
+    UnknownCurve { name: String },
+    #[error("malformed text: {reason}")]
+    Malformed { reason: String },
+    #[error("the encoded point is invalid")]
+    Point(#[from] PointError),
+    #[error("the encoded curve is invalid")]
+    Curve(#[from] CurveError),
+}
+
+/// Reasons for the protocols built on the curves to refuse their inputs
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum ProtocolError {
+    #[error("the {role} can't be the point at infinity")]
+    Identity { role: &'static str },
+    #[error("the {role} isn't in the prime order subgroup of the curve {curve}")]
+    NotInSubgroup { role: &'static str, curve: String },
+    #[error("the signature doesn't verify")]
+    InvalidSignature,
+    #[error("the key material has {length} bytes, at least 32 are needed")]
+    ShortKeyMaterial { length: usize },
+    #[error(
+        "the messages signed with the basic scheme must be distinct to aggregate the signatures"
+    )]
+    DuplicateMessage,
+    #[error("the {operation} isn't part of the scheme")]
+    Unsupported { operation: &'static str },
+    #[error("a point of the protocol is invalid")]
+    Point(#[from] PointError),
+    #[error("an input of the protocol can't be decoded")]
+    Encoding(#[from] EncodingError),
+}
+
+/// Any error of the crate
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    #[error(transparent)]
+    Curve(#[from] CurveError),
+    #[error(transparent)]
+    Field(#[from] FieldError),
+    #[error(transparent)]
+    Point(#[from] PointError),
+    #[error(transparent)]
+    Encoding(#[from] EncodingError),
+    #[error(transparent)]
+    Protocol(#[from] ProtocolError),
+}
+
+/// The coefficients of an extension field element over the prime field
+fn hexes(coefficients: &[Ufeat]) -> String {
+    let coefficients: Vec<_> = coefficients.iter().map(hex).collect();
+    format!("[{}]", coefficients.join(", "))
+}
@@ -0,0 +1,848 @@
+//! Extension fields of the prime fields: the tower $F_{p^2} \subset F_{p^6} \subset F_{p^{12}}$ the pairings are computed
+//! in, and $F_{p^k}$ for any irreducible polynomial of degree `k`
+//!
+//! The fields are chosen at runtime like the prime field is, so every element carries the parameters of its field the way
+//! a `DynResidue` does, and the constants of a field are taken from any of its elements: `x.one_like()`,
+//! `x.embed(&c)`. The tower is
+//!
+//! - $F_{p^2} = F_p[u] / (u^2 - \beta)$ for a non-square $\beta$,
+//! - $F_{p^6} = F_{p^2}[v] / (v^3 - \xi)$ for a non-cube $\xi$,
+//! - $F_{p^{12}} = F_{p^6}[w] / (w^2 - v)$,
+//!
+//! which is the one of BN and BLS12 curves: each step is small enough for schoolbook formulas, and `w` squares to `v`.
+use std::{
+    fmt,
+    ops::{Add, Mul, Neg, Sub},
+};
+
+#[cfg(feature = "u8192")]
+use crypto_bigint::U32768;
+#[cfg(not(feature = "u8192"))]
+use crypto_bigint::U8192;
+use crypto_bigint::{
+    modular::runtime_mod::{DynResidue, DynResidueParams},
+    rand_core::OsRng,
+    Checked, NonZero, RandomMod, Uint,
+};
+
+use super::{
+    encoding::to_hex,
+    field,
+    poly::{CoefficientField, Poly, PolyRing},
+    FieldError, Ufeat,
+};
+
+/// The prime field
+pub type Fp = DynResidue<{ Ufeat::LIMBS }>;
+
+/// Room for the sizes of the fields, up to $p^{12}$ for a 512-bit `p`
+#[cfg(not(feature = "u8192"))]
+pub type Exponent = U8192;
+#[cfg(feature = "u8192")]
+pub type Exponent = U32768;
+
+/// The arithmetic the curves need from the field of their coordinates
+///
+/// The polynomials over the field come with it, so the fields are those of the crate.
+pub trait Field: CoefficientField {
+    /// The degree over the prime field
+    const DEGREE: usize;
+
+    fn zero_like(&self) -> Self;
+    fn one_like(&self) -> Self;
+    /// An element of the prime field as an element of the field of `self`
+    fn embed(&self, c: &Fp) -> Self;
+    fn prime_params(&self) -> DynResidueParams<{ Ufeat::LIMBS }>;
+    /// The coordinates over the prime field, `DEGREE` of them: the coefficients of the polynomial or, in the tower, of
+    /// its basis $u^i.v^j.w^k$ with `i` varying fastest
+    fn to_coefficients(&self) -> Vec<Ufeat>;
+    /// `None` for zero
+    fn inverse(&self) -> Option<Self>;
+    /// A uniformly random element of the field of `self`
+    fn random(&self) -> Self;
+
+    fn is_zero(&self) -> bool {
+        *self == self.zero_like()
+    }
+    fn square(&self) -> Self {
+        *self * *self
+    }
+    fn double(&self) -> Self {
+        *self + *self
+    }
+    fn pow<const E: usize>(&self, exponent: &Uint<E>) -> Self {
+        let mut result = self.one_like();
+        for i in (0..exponent.bits_vartime()).rev() {
+            result = result.square();
+            if exponent.bit_vartime(i) {
+                result = result * *self;
+            }
+        }
+        result
+    }
+    fn characteristic(&self) -> Ufeat {
+        *self.prime_params().modulus()
+    }
+    /// The number of elements $p^{DEGREE}$; panics if it doesn't fit `Exponent`
+    fn size(&self) -> Exponent {
+        let p = Checked::new(self.characteristic().resize::<{ Exponent::LIMBS }>());
+        let size = (1..Self::DEGREE).fold(p, |size, _| size * p);
+        Option::from(size.0).expect("the field is too large for `Exponent`")
+    }
+    /// $x^p$, the generator of the Galois group
+    fn frobenius(&self) -> Self {
+        self.pow(&self.characteristic())
+    }
+    /// Whether the element is a square, with Euler's criterion
+    fn is_square(&self) -> bool {
+        let half = self.size().wrapping_sub(&Exponent::ONE).shr_vartime(1);
+        self.is_zero() || self.pow(&half) == self.one_like()
+    }
+    /// Square root with Tonelli-Shanks; `None` for non-squares
+    fn sqrt(&self) -> Option<Self> {
+        if self.is_zero() {
+            return Some(*self);
+        }
+        if !self.is_square() {
+            return None;
+        }
+        let one = self.one_like();
+        // q - 1 = t.2^s with odd t
+        let q_minus_one = self.size().wrapping_sub(&Exponent::ONE);
+        let s = q_minus_one.trailing_zeros();
+        let t = q_minus_one.shr_vartime(s);
+        let non_square = loop {
+            let z = self.random();
+            if !z.is_square() {
+                break z;
+            }
+        };
+        let (mut m, mut c) = (s, non_square.pow(&t));
+        let (mut t, mut r) = (
+            self.pow(&t),
+            self.pow(&t.wrapping_add(&Exponent::ONE).shr_vartime(1)),
+        );
+        while t != one {
+            let (mut i, mut t_squared) = (0, t);
+            while t_squared != one {
+                t_squared = t_squared.square();
+                i += 1;
+            }
+            let mut b = c;
+            for _ in 0..(m - i - 1) {
+                b = b.square();
+            }
+            m = i;
+            c = b.square();
+            t = t * c;
+            r = r * b;
+        }
+        Some(r)
+    }
+}
+
+impl Field for Fp {
+    const DEGREE: usize = 1;
+
+    fn zero_like(&self) -> Self {
+        DynResidue::zero(*self.params())
+    }
+    fn one_like(&self) -> Self {
+        DynResidue::one(*self.params())
+    }
+    fn embed(&self, c: &Fp) -> Self {
+        *c
+    }
+    fn prime_params(&self) -> DynResidueParams<{ Ufeat::LIMBS }> {
+        *self.params()
+    }
+    fn to_coefficients(&self) -> Vec<Ufeat> {
+        vec![self.retrieve()]
+    }
+    fn inverse(&self) -> Option<Self> {
+        let (inverse, invertible) = self.invert();
+        bool::from(invertible).then_some(inverse)
+    }
+    fn random(&self) -> Self {
+        let modulus = NonZero::new(*self.params().modulus()).unwrap();
+        DynResidue::new(&Ufeat::random_mod(&mut OsRng, &modulus), *self.params())
+    }
+    fn square(&self) -> Self {
+        DynResidue::square(self)
+    }
+    fn frobenius(&self) -> Self {
+        *self
+    }
+    fn sqrt(&self) -> Option<Self> {
+        field::sqrt(self)
+    }
+}
+
+/// Writes the coordinates over the prime field
+fn display(coefficients: Vec<Ufeat>, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let coefficients: Vec<_> = coefficients.iter().map(to_hex).collect();
+    write!(f, "[{}]", coefficients.join(", "))
+}
+
+/// $c0 + c1.u$ with $u^2 = \beta$
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Fp2 {
+    c0: Fp,
+    c1: Fp,
+    non_residue: Fp,
+}
+
+impl Fp2 {
+    /// The one of $F_p[u] / (u^2 - \beta)$, refusing a square $\beta$
+    pub fn one(non_residue: Fp) -> Result<Self, FieldError> {
+        if non_residue.is_square() {
+            return Err(FieldError::Reducible { degree: 2 });
+        }
+        Ok(Fp2 {
+            c0: non_residue.one_like(),
+            c1: non_residue.zero_like(),
+            non_residue,
+        })
+    }
+    /// $c0 + c1.u$ in the field of `self`
+    pub fn from_coefficients(&self, c0: Fp, c1: Fp) -> Self {
+        Fp2 { c0, c1, ..*self }
+    }
+    pub fn c0(&self) -> &Fp {
+        &self.c0
+    }
+    pub fn c1(&self) -> &Fp {
+        &self.c1
+    }
+    pub fn non_residue(&self) -> &Fp {
+        &self.non_residue
+    }
+    /// $c0 - c1.u$, which is also the Frobenius image
+    pub fn conjugate(&self) -> Self {
+        self.from_coefficients(self.c0, -self.c1)
+    }
+}
+
+impl Add for Fp2 {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self {
+        self.from_coefficients(self.c0 + rhs.c0, self.c1 + rhs.c1)
+    }
+}
+impl Sub for Fp2 {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self {
+        self.from_coefficients(self.c0 - rhs.c0, self.c1 - rhs.c1)
+    }
+}
+impl Neg for Fp2 {
+    type Output = Self;
+    fn neg(self) -> Self {
+        self.from_coefficients(-self.c0, -self.c1)
+    }
+}
+impl Mul for Fp2 {
+    type Output = Self;
+    fn mul(self, rhs: Self) -> Self {
+        // Karatsuba: the middle term is (a0 + a1)(b0 + b1) - a0.b0 - a1.b1
+        let (low, high) = (self.c0 * rhs.c0, self.c1 * rhs.c1);
+        let middle = (self.c0 + self.c1) * (rhs.c0 + rhs.c1) - low - high;
+        self.from_coefficients(low + self.non_residue * high, middle)
+    }
+}
+
+impl Field for Fp2 {
+    const DEGREE: usize = 2;
+
+    fn zero_like(&self) -> Self {
+        self.from_coefficients(self.c0.zero_like(), self.c0.zero_like())
+    }
+    fn one_like(&self) -> Self {
+        self.from_coefficients(self.c0.one_like(), self.c0.zero_like())
+    }
+    fn embed(&self, c: &Fp) -> Self {
+        self.from_coefficients(*c, self.c0.zero_like())
+    }
+    fn prime_params(&self) -> DynResidueParams<{ Ufeat::LIMBS }> {
+        *self.c0.params()
+    }
+    fn to_coefficients(&self) -> Vec<Ufeat> {
+        vec![self.c0.retrieve(), self.c1.retrieve()]
+    }
+    fn inverse(&self) -> Option<Self> {
+        // (c0 + c1.u)(c0 - c1.u) = c0^2 - beta.c1^2 is in the prime field
+        let norm = self.c0.square() - self.non_residue * self.c1.square();
+        let inverse = Field::inverse(&norm)?;
+        Some(self.from_coefficients(self.c0 * inverse, -self.c1 * inverse))
+    }
+    fn random(&self) -> Self {
+        self.from_coefficients(self.c0.random(), self.c0.random())
+    }
+    fn frobenius(&self) -> Self {
+        // u^p = beta^((p - 1) / 2).u = -u
+        self.conjugate()
+    }
+}
+
+impl CoefficientField for Fp2 {
+    fn zero(&self) -> Self {
+        self.zero_like()
+    }
+    fn one(&self) -> Self {
+        self.one_like()
+    }
+    fn reciprocal(&self) -> Option<Self> {
+        self.inverse()
+    }
+    fn cardinality(&self) -> Exponent {
+        self.size()
+    }
+    fn sample(&self) -> Self {
+        self.random()
+    }
+}
+
+impl fmt::Display for Fp2 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        display(self.to_coefficients(), f)
+    }
+}
+
+/// $c_0 + c_1.v + c_2.v^2$ with $v^3 = \xi$
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Fp6 {
+    c: [Fp2; 3],
+    xi: Fp2,
+}
+
+impl Fp6 {
+    /// The one of $F_{p^2}[v] / (v^3 - \xi)$, refusing $\xi$ which is a cube
+    pub fn one(xi: Fp2) -> Result<Self, FieldError> {
+        // a cube root of xi is a root, and without one the cubic has no factors at all
+        let q_minus_one = xi.size().wrapping_sub(&Exponent::ONE);
+        let three = Exponent::from(3u8);
+        if q_minus_one.wrapping_rem(&three) != Exponent::ZERO
+            || xi.is_zero()
+            || xi.pow(&q_minus_one.wrapping_div(&three)) == xi.one_like()
+        {
+            return Err(FieldError::Reducible { degree: 6 });
+        }
+        let (zero, one) = (xi.zero_like(), xi.one_like());
+        Ok(Fp6 {
+            c: [one, zero, zero],
+            xi,
+        })
+    }
+    /// $c_0 + c_1.v + c_2.v^2$ in the field of `self`
+    pub fn from_coefficients(&self, c: [Fp2; 3]) -> Self {
+        Fp6 { c, xi: self.xi }
+    }
+    pub fn coefficients(&self) -> &[Fp2; 3] {
+        &self.c
+    }
+    pub fn xi(&self) -> &Fp2 {
+        &self.xi
+    }
+    /// An element of $F_{p^2}$ in the field of `self`
+    pub fn embed2(&self, c: &Fp2) -> Self {
+        let zero = c.zero_like();
+        self.from_coefficients([*c, zero, zero])
+    }
+    /// The product with `v`, a shift of the coefficients
+    pub fn mul_by_v(&self) -> Self {
+        let [c0, c1, c2] = self.c;
+        self.from_coefficients([self.xi * c2, c0, c1])
+    }
+}
+
+impl Add for Fp6 {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self {
+        let [a0, a1, a2] = self.c;
+        let [b0, b1, b2] = rhs.c;
+        self.from_coefficients([a0 + b0, a1 + b1, a2 + b2])
+    }
+}
+impl Sub for Fp6 {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self {
+        self + -rhs
+    }
+}
+impl Neg for Fp6 {
+    type Output = Self;
+    fn neg(self) -> Self {
+        self.from_coefficients(self.c.map(|c| -c))
+    }
+}
+impl Mul for Fp6 {
+    type Output = Self;
+    fn mul(self, rhs: Self) -> Self {
+        let [a0, a1, a2] = self.c;
+        let [b0, b1, b2] = rhs.c;
+        let xi = self.xi;
+        self.from_coefficients([
+            a0 * b0 + xi * (a1 * b2 + a2 * b1),
+            a0 * b1 + a1 * b0 + xi * (a2 * b2),
+            a0 * b2 + a1 * b1 + a2 * b0,
+        ])
+    }
+}
+
+impl Field for Fp6 {
+    const DEGREE: usize = 6;
+
+    fn zero_like(&self) -> Self {
+        let zero = self.xi.zero_like();
+        self.from_coefficients([zero; 3])
+    }
+    fn one_like(&self) -> Self {
+        self.embed2(&self.xi.one_like())
+    }
+    fn embed(&self, c: &Fp) -> Self {
+        self.embed2(&self.xi.embed(c))
+    }
+    fn prime_params(&self) -> DynResidueParams<{ Ufeat::LIMBS }> {
+        self.xi.prime_params()
+    }
+    fn to_coefficients(&self) -> Vec<Ufeat> {
+        self.c.iter().flat_map(Field::to_coefficients).collect()
+    }
+    fn inverse(&self) -> Option<Self> {
+        // the adjugate over the norm, as for a 3x3 circulant-like matrix
+        let [a0, a1, a2] = self.c;
+        let xi = self.xi;
+        let t0 = a0.square() - xi * (a1 * a2);
+        let t1 = xi * a2.square() - a0 * a1;
+        let t2 = a1.square() - a0 * a2;
+        let norm = a0 * t0 + xi * (a2 * t1 + a1 * t2);
+        let inverse = norm.inverse()?;
+        Some(self.from_coefficients([t0 * inverse, t1 * inverse, t2 * inverse]))
+    }
+    fn random(&self) -> Self {
+        self.from_coefficients([self.xi.random(), self.xi.random(), self.xi.random()])
+    }
+}
+
+impl CoefficientField for Fp6 {
+    fn zero(&self) -> Self {
+        self.zero_like()
+    }
+    fn one(&self) -> Self {
+        self.one_like()
+    }
+    fn reciprocal(&self) -> Option<Self> {
+        self.inverse()
+    }
+    fn cardinality(&self) -> Exponent {
+        self.size()
+    }
+    fn sample(&self) -> Self {
+        self.random()
+    }
+}
+
+impl fmt::Display for Fp6 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        display(self.to_coefficients(), f)
+    }
+}
+
+/// $c0 + c1.w$ with $w^2 = v$
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Fp12 {
+    c0: Fp6,
+    c1: Fp6,
+}
+
+impl Fp12 {
+    /// The one of $F_{p^6}[w] / (w^2 - v)$ over the field of `base`, refusing the tower where `v` is a square
+    pub fn one(base: &Fp6) -> Result<Self, FieldError> {
+        let v =
+            base.from_coefficients([base.xi.zero_like(), base.xi.one_like(), base.xi.zero_like()]);
+        if v.is_square() {
+            return Err(FieldError::Reducible { degree: 12 });
+        }
+        Ok(Fp12 {
+            c0: base.one_like(),
+            c1: base.zero_like(),
+        })
+    }
+    /// $c0 + c1.w$ in the field of `self`
+    pub fn from_coefficients(&self, c0: Fp6, c1: Fp6) -> Self {
+        Fp12 { c0, c1 }
+    }
+    pub fn c0(&self) -> &Fp6 {
+        &self.c0
+    }
+    pub fn c1(&self) -> &Fp6 {
+        &self.c1
+    }
+    /// An element of $F_{p^6}$ in the field of `self`
+    pub fn embed6(&self, c: &Fp6) -> Self {
+        self.from_coefficients(*c, c.zero_like())
+    }
+    /// $c0 - c1.w$, the image of $x^{p^6}$; it's the inverse for the elements of norm one, such as the pairing values
+    pub fn conjugate(&self) -> Self {
+        self.from_coefficients(self.c0, -self.c1)
+    }
+}
+
+impl Add for Fp12 {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self {
+        self.from_coefficients(self.c0 + rhs.c0, self.c1 + rhs.c1)
+    }
+}
+impl Sub for Fp12 {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self {
+        self.from_coefficients(self.c0 - rhs.c0, self.c1 - rhs.c1)
+    }
+}
+impl Neg for Fp12 {
+    type Output = Self;
+    fn neg(self) -> Self {
+        self.from_coefficients(-self.c0, -self.c1)
+    }
+}
+impl Mul for Fp12 {
+    type Output = Self;
+    fn mul(self, rhs: Self) -> Self {
+        let (low, high) = (self.c0 * rhs.c0, self.c1 * rhs.c1);
+        let middle = (self.c0 + self.c1) * (rhs.c0 + rhs.c1) - low - high;
+        self.from_coefficients(low + high.mul_by_v(), middle)
+    }
+}
+
+impl Field for Fp12 {
+    const DEGREE: usize = 12;
+
+    fn zero_like(&self) -> Self {
+        self.embed6(&self.c0.zero_like())
+    }
+    fn one_like(&self) -> Self {
+        self.embed6(&self.c0.one_like())
+    }
+    fn embed(&self, c: &Fp) -> Self {
+        self.embed6(&self.c0.embed(c))
+    }
+    fn prime_params(&self) -> DynResidueParams<{ Ufeat::LIMBS }> {
+        self.c0.prime_params()
+    }
+    fn to_coefficients(&self) -> Vec<Ufeat> {
+        let mut coefficients = self.c0.to_coefficients();
+        coefficients.extend(self.c1.to_coefficients());
+        coefficients
+    }
+    fn inverse(&self) -> Option<Self> {
+        let norm = self.c0.square() - self.c1.square().mul_by_v();
+        let inverse = norm.inverse()?;
+        Some(self.from_coefficients(self.c0 * inverse, -self.c1 * inverse))
+    }
+    fn random(&self) -> Self {
+        self.from_coefficients(self.c0.random(), self.c0.random())
+    }
+}
+
+impl CoefficientField for Fp12 {
+    fn zero(&self) -> Self {
+        self.zero_like()
+    }
+    fn one(&self) -> Self {
+        self.one_like()
+    }
+    fn reciprocal(&self) -> Option<Self> {
+        self.inverse()
+    }
+    fn cardinality(&self) -> Exponent {
+        self.size()
+    }
+    fn sample(&self) -> Self {
+        self.random()
+    }
+}
+
+impl fmt::Display for Fp12 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        display(self.to_coefficients(), f)
+    }
+}
+
+/// $F_p[x] / (f)$ for a monic irreducible `f` of degree `K`, the elements being the remainders of degree below `K`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Fpk<const K: usize> {
+    c: [Fp; K],
+    // the coefficients of `f` below `x^K`
+    modulus: [Fp; K],
+}
+
+impl<const K: usize> Fpk<K> {
+    /// The one of $F_p[x] / (x^K + m_{K-1}.x^{K-1} + ... + m_0)$ for `modulus` $= [m_0, ..., m_{K-1}]$, refusing a
+    /// reducible polynomial
+    ///
+    /// Irreducibility is checked with Rabin's test: `f` divides $x^{p^K} - x$ and has no common factor with
+    /// $x^{p^{K/q}} - x$ for the primes `q` dividing `K`.
+    pub fn one(modulus: [Fp; K]) -> Result<Self, FieldError> {
+        assert!(K > 0, "an extension of degree zero");
+        let params = *modulus[0].params();
+        let mut f = modulus.to_vec();
+        f.push(DynResidue::one(params));
+        let f = Poly::new(f, &modulus[0]);
+        let ring = PolyRing::new(f.clone());
+        let x = Poly::x(&modulus[0]);
+        // x^(p^i) for i up to K
+        let mut powers = vec![ring.reduce(&x)];
+        for _ in 0..K {
+            powers.push(ring.pow(powers.last().unwrap(), params.modulus()));
+        }
+        let divides = (&powers[K] - &powers[0]).is_zero();
+        let coprime = (2..=K)
+            .filter(|q| K.is_multiple_of(*q) && (2..*q).all(|d| q % d != 0))
+            .all(|q| (&powers[K / q] - &powers[0]).gcd(&f).degree() == Some(0));
+        if !divides || !coprime {
+            return Err(FieldError::Reducible { degree: K });
+        }
+        let mut c = [DynResidue::zero(params); K];
+        c[0] = DynResidue::one(params);
+        Ok(Fpk { c, modulus })
+    }
+    /// $c_0 + c_1.x + ... + c_{K-1}.x^{K-1}$ in the field of `self`
+    pub fn from_coefficients(&self, c: [Fp; K]) -> Self {
+        Fpk {
+            c,
+            modulus: self.modulus,
+        }
+    }
+    pub fn coefficients(&self) -> &[Fp; K] {
+        &self.c
+    }
+    pub fn modulus(&self) -> &[Fp; K] {
+        &self.modulus
+    }
+    fn params(&self) -> DynResidueParams<{ Ufeat::LIMBS }> {
+        *self.modulus[0].params()
+    }
+    fn polynomial_modulus(&self) -> Poly<Fp> {
+        let mut f = self.modulus.to_vec();
+        f.push(DynResidue::one(self.params()));
+        Poly::new(f, &self.c[0])
+    }
+}
+
+impl<const K: usize> Add for Fpk<K> {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self {
+        let mut c = self.c;
+        for (c, r) in c.iter_mut().zip(rhs.c) {
+            *c += r;
+        }
+        self.from_coefficients(c)
+    }
+}
+impl<const K: usize> Sub for Fpk<K> {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self {
+        self + -rhs
+    }
+}
+impl<const K: usize> Neg for Fpk<K> {
+    type Output = Self;
+    fn neg(self) -> Self {
+        self.from_coefficients(self.c.map(|c| -c))
+    }
+}
+impl<const K: usize> Mul for Fpk<K> {
+    type Output = Self;
+    fn mul(self, rhs: Self) -> Self {
+        let mut product = vec![self.c[0].zero_like(); 2 * K - 1];
+        for (i, a) in self.c.iter().enumerate() {
+            for (j, b) in rhs.c.iter().enumerate() {
+                product[i + j] += a * b;
+            }
+        }
+        // x^K = -(m_0 + ... + m_(K-1).x^(K-1)) from the top down
+        for i in (K..2 * K - 1).rev() {
+            let top = product[i];
+            for (j, m) in self.modulus.iter().enumerate() {
+                product[i - K + j] -= top * m;
+            }
+        }
+        let mut c = self.c;
+        c.copy_from_slice(&product[..K]);
+        self.from_coefficients(c)
+    }
+}
+
+impl<const K: usize> Field for Fpk<K> {
+    const DEGREE: usize = K;
+
+    fn zero_like(&self) -> Self {
+        self.from_coefficients([self.c[0].zero_like(); K])
+    }
+    fn one_like(&self) -> Self {
+        self.embed(&self.c[0].one_like())
+    }
+    fn embed(&self, c: &Fp) -> Self {
+        let mut coefficients = [c.zero_like(); K];
+        coefficients[0] = *c;
+        self.from_coefficients(coefficients)
+    }
+    fn prime_params(&self) -> DynResidueParams<{ Ufeat::LIMBS }> {
+        self.params()
+    }
+    fn to_coefficients(&self) -> Vec<Ufeat> {
+        self.c.iter().map(|c| c.retrieve()).collect()
+    }
+    fn inverse(&self) -> Option<Self> {
+        let ring = PolyRing::new(self.polynomial_modulus());
+        let inverse = ring.invert(&Poly::new(self.c.to_vec(), &self.c[0])).ok()?;
+        let mut c = [self.c[0].zero_like(); K];
+        c[..inverse.coefficients().len()].copy_from_slice(inverse.coefficients());
+        Some(self.from_coefficients(c))
+    }
+    fn random(&self) -> Self {
+        self.from_coefficients(self.c.map(|c| c.random()))
+    }
+}
+
+impl<const K: usize> CoefficientField for Fpk<K> {
+    fn zero(&self) -> Self {
+        self.zero_like()
+    }
+    fn one(&self) -> Self {
+        self.one_like()
+    }
+    fn reciprocal(&self) -> Option<Self> {
+        self.inverse()
+    }
+    fn cardinality(&self) -> Exponent {
+        self.size()
+    }
+    fn sample(&self) -> Self {
+        self.random()
+    }
+}
+
+impl<const K: usize> fmt::Display for Fpk<K> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        display(self.to_coefficients(), f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 3 mod 4, so -1 isn't a square, and 1 mod 3, so F_p^k has cube roots of unity
+    const P: u64 = 103;
+
+    fn fp(n: u64) -> Fp {
+        DynResidue::new(&Ufeat::from(n), DynResidueParams::new(&Ufeat::from(P)))
+    }
+
+    fn fp2() -> Fp2 {
+        Fp2::one(-fp(1)).unwrap()
+    }
+
+    // xi = c + u neither a square nor a cube, so that v isn't a square in F_p^6 either
+    fn fp6() -> Fp6 {
+        let one = fp2();
+        (1..P)
+            .filter_map(|c| Fp6::one(one.from_coefficients(fp(c), fp(1))).ok())
+            .find(|one| !one.xi().is_square())
+            .unwrap()
+    }
+
+    fn fp12() -> Fp12 {
+        Fp12::one(&fp6()).unwrap()
+    }
+
+    fn fp3() -> Fpk<3> {
+        (2..P)
+            .find_map(|c| Fpk::one([-fp(c), fp(0), fp(0)]).ok())
+            .unwrap()
+    }
+
+    /// The laws every field of the crate must obey, checked on random elements of the field of `one`
+    fn laws<F: Field>(one: F) {
+        let zero = one.zero_like();
+        let mut size = Exponent::ONE;
+        for _ in 0..F::DEGREE {
+            size = size.wrapping_mul(&Exponent::from(P));
+        }
+        assert_eq!(one.size(), size);
+        assert_eq!(one.characteristic(), Ufeat::from(P));
+        assert_eq!(zero.inverse(), None);
+        assert_eq!(zero.sqrt(), Some(zero));
+
+        let c = one.embed(&fp(5));
+        assert_eq!(c.frobenius(), c);
+        for _ in 0..4 {
+            let (a, b) = (one.random(), one.random());
+            assert_eq!(a * (a + b), a.square() + a * b);
+            if !a.is_zero() {
+                assert_eq!(a * a.inverse().unwrap(), one);
+            }
+
+            assert_eq!(a.frobenius(), a.pow(&Ufeat::from(P)));
+            assert_eq!((a * b).frobenius(), a.frobenius() * b.frobenius());
+            assert_eq!((a + b).frobenius(), a.frobenius() + b.frobenius());
+            let orbit = (0..F::DEGREE).fold(a, |x, _| x.frobenius());
+            assert_eq!(orbit, a);
+
+            let root = a.square().sqrt().unwrap();
+            assert!(root == a || root == -a);
+            assert!(a.square().is_square());
+        }
+        let non_square = loop {
+            let z = one.random();
+            if !z.is_square() {
+                break z;
+            }
+        };
+        assert_eq!(non_square.sqrt(), None);
+    }
+
+    #[test]
+    fn the_prime_field_is_a_field() {
+        laws(fp(1));
+    }
+
+    #[test]
+    fn fp2_is_a_field() {
+        let one = fp2();
+        laws(one);
+        let a = one.random();
+        assert_eq!(a.conjugate(), a.frobenius());
+        assert_eq!(Fp2::one(fp(4)), Err(FieldError::Reducible { degree: 2 }));
+    }
+
+    #[test]
+    fn fp6_is_a_field() {
+        let one = fp6();
+        laws(one);
+        // a cube has a root in F_p^2, so v^3 - xi splits
+        let cube = one.xi().square() * *one.xi();
+        assert_eq!(Fp6::one(cube), Err(FieldError::Reducible { degree: 6 }));
+    }
+
+    #[test]
+    fn fp12_is_a_field() {
+        let one = fp12();
+        laws(one);
+        let a = one.random();
+        let sixth = (0..6).fold(a, |x, _| x.frobenius());
+        assert_eq!(a.conjugate(), sixth);
+    }
+
+    #[test]
+    fn fpk_is_a_field() {
+        laws(fp3());
+        // 1 is a root of x^3 - 1
+        assert_eq!(
+            Fpk::one([-fp(1), fp(0), fp(0)]),
+            Err(FieldError::Reducible { degree: 3 })
+        );
+    }
+}
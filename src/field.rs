@@ -0,0 +1,75 @@
+//! Helpers over the prime field which `crypto-bigint` doesn't provide for `DynResidue`
+use crypto_bigint::modular::runtime_mod::DynResidue;
+
+use super::Ufeat;
+
+/// Raises to a power not exceeding the modulus: `pow` would run all the `Ufeat::BITS` squarings otherwise
+pub(crate) fn pow_modulus_bounded(
+    base: &DynResidue<{ Ufeat::LIMBS }>,
+    exponent: &Ufeat,
+) -> DynResidue<{ Ufeat::LIMBS }> {
+    base.pow_bounded_exp(exponent, base.params().modulus().bits())
+}
+
+/// Legendre symbol of a residue computed with Euler's criterion: `0`, `1` for squares, `-1` for non-squares
+pub(crate) fn legendre(a: &DynResidue<{ Ufeat::LIMBS }>) -> i8 {
+    let params = *a.params();
+    if *a == DynResidue::zero(params) {
+        return 0;
+    }
+    let exponent = params.modulus().wrapping_sub(&Ufeat::ONE).shr_vartime(1);
+    if pow_modulus_bounded(a, &exponent) == DynResidue::one(params) {
+        1
+    } else {
+        -1
+    }
+}
+
+/// Square root of a residue with Tonelli-Shanks; `None` for non-squares
+pub(crate) fn sqrt(a: &DynResidue<{ Ufeat::LIMBS }>) -> Option<DynResidue<{ Ufeat::LIMBS }>> {
+    let params = *a.params();
+    let one = DynResidue::one(params);
+    match legendre(a) {
+        0 => return Some(*a),
+        -1 => return None,
+        _ => {}
+    }
+
+    // p - 1 = q * 2^s with odd q
+    let p_minus_one = params.modulus().wrapping_sub(&Ufeat::ONE);
+    let s = p_minus_one.trailing_zeros();
+    let q = p_minus_one.shr_vartime(s);
+    if s == 1 {
+        // p = 3 (mod 4) is the easy case: a^((p + 1) / 4)
+        return Some(pow_modulus_bounded(
+            a,
+            &params.modulus().wrapping_add(&Ufeat::ONE).shr_vartime(2),
+        ));
+    }
+
+    let mut z = DynResidue::new(&Ufeat::from(2u8), params);
+    while legendre(&z) != -1 {
+        z += one;
+    }
+    let mut m = s;
+    let mut c = pow_modulus_bounded(&z, &q);
+    let mut t = pow_modulus_bounded(a, &q);
+    let mut r = pow_modulus_bounded(a, &q.wrapping_add(&Ufeat::ONE).shr_vartime(1));
+    while t != one {
+        let mut i = 0;
+        let mut t_squared = t;
+        while t_squared != one {
+            t_squared = t_squared.square();
+            i += 1;
+        }
+        let mut b = c;
+        for _ in 0..(m - i - 1) {
+            b = b.square();
+        }
+        m = i;
+        c = b.square();
+        t *= c;
+        r *= b;
+    }
+    Some(r)
+}
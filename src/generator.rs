@@ -0,0 +1,278 @@
+//! Random curves of prime order (up to a small cofactor) over a given field, reproducible from a seed
+//!
+//! The seed plays the role of the "verifiably random" seed of ANSI X9.62: the coefficients are SHA-256 outputs of it, so
+//! anyone holding the seed can check that the curve wasn't hand-picked for a hidden weakness. A rejected seed is
+//! incremented as a big-endian integer (as Brainpool does), so the returned seed generates the returned curve directly.
+use std::rc::Rc;
+
+use crypto_bigint::{
+    modular::runtime_mod::DynResidue,
+    rand_core::{OsRng, RngCore},
+    NonZero,
+};
+use sha2::{Digest, Sha256};
+
+use super::{field, primality, CurveError, EllipticCurve, Point, Ufeat, COUNTING_BOUND_BITS};
+
+pub type Seed = [u8; 32];
+
+/// Searches for a curve $y^2 = x^3 + a4.x + a6$ of order `h.n` with prime `n` and `h` not exceeding `max_cofactor`
+pub struct CurveGenerator {
+    modulus: Ufeat,
+    max_cofactor: u64,
+    max_attempts: usize,
+}
+
+impl CurveGenerator {
+    /// `modulus` must be an odd prime; by default a curve of prime order is searched for within 1000 seeds
+    pub fn new(modulus: Ufeat) -> Self {
+        CurveGenerator {
+            modulus,
+            max_cofactor: 1,
+            max_attempts: 1000,
+        }
+    }
+    pub fn with_max_cofactor(mut self, max_cofactor: u64) -> Self {
+        self.max_cofactor = max_cofactor.max(1);
+        self
+    }
+    pub fn with_max_attempts(mut self, max_attempts: usize) -> Self {
+        self.max_attempts = max_attempts;
+        self
+    }
+
+    /// Starts the search from a random seed
+    pub fn generate(&self) -> Result<(EllipticCurve, Seed), CurveError> {
+        let mut seed = Seed::default();
+        OsRng.fill_bytes(&mut seed);
+        self.generate_from_seed(seed)
+    }
+
+    /// Tries `seed`, `seed + 1`, ... and returns the first curve which fits along with the seed it came from
+    ///
+    /// The order is counted naively on small fields and with Schoof's algorithm otherwise, so this is practical up to
+    /// about 64-bit fields.
+    pub fn generate_from_seed(&self, mut seed: Seed) -> Result<(EllipticCurve, Seed), CurveError> {
+        for _ in 0..self.max_attempts {
+            if let Some(curve) = self.try_seed(&seed) {
+                return Ok((curve, seed));
+            }
+            increment(&mut seed);
+        }
+        Err(CurveError::GenerationFailed {
+            attempts: self.max_attempts,
+        })
+    }
+
+    /// Checks that `curve` is the one `seed` generates: the same field, coefficients and generator
+    ///
+    /// The order isn't recounted, only checked to annihilate the generator.
+    pub fn verify(&self, curve: &Rc<EllipticCurve>, seed: &Seed) -> bool {
+        let candidate = match self.candidate(seed, self.modulus.wrapping_add(&Ufeat::ONE)) {
+            Some(candidate) => candidate,
+            None => return false,
+        };
+        if curve.original_p() != self.modulus
+            || curve.a2() != candidate.a2()
+            || curve.a4() != candidate.a4()
+            || curve.a6() != candidate.a6()
+        {
+            return false;
+        }
+        let cofactor = match curve.cofactor() {
+            Some(cofactor) => cofactor,
+            None => return false,
+        };
+        match (
+            curve.generator(),
+            self.base_point(&Rc::new(candidate), seed, cofactor),
+        ) {
+            (Some(generator), Some(expected)) => {
+                generator == expected
+                    && (&generator * *curve.order())
+                        .map(|point| point.is_infinity())
+                        .unwrap_or(false)
+            }
+            _ => false,
+        }
+    }
+
+    /// The curve `seed` generates with an `order` which is yet to be found; `None` if it's singular
+    fn candidate(&self, seed: &Seed, order: Ufeat) -> Option<EllipticCurve> {
+        EllipticCurve::try_from_uint_coefficients(
+            "generated".to_string(),
+            order,
+            self.modulus,
+            [
+                Ufeat::ZERO,
+                self.field_element(seed, b'a', 0),
+                self.field_element(seed, b'b', 0),
+            ],
+        )
+        .ok()
+    }
+
+    fn try_seed(&self, seed: &Seed) -> Option<EllipticCurve> {
+        // the middle of Hasse interval is a placeholder until the order is known
+        let curve = Rc::new(self.candidate(seed, self.modulus.wrapping_add(&Ufeat::ONE))?);
+
+        let count = if self.modulus.bits() <= COUNTING_BOUND_BITS {
+            curve.count_points().ok()?
+        } else {
+            // a prime `ell` dividing the count is either a part of the cofactor or the prime order itself, and the latter
+            // is way bigger than the primes Schoof's algorithm goes through
+            let max_cofactor = self.max_cofactor;
+            let p = self.modulus;
+            curve.compute_order_or_abort(&|ell, t| {
+                let p_mod_ell = p.wrapping_rem(&Ufeat::from(ell)).as_words()[0];
+                ell > max_cofactor && (p_mod_ell + 1 + ell - t).is_multiple_of(ell)
+            })?
+        };
+
+        // the prime order must be wider than Hasse interval for `EllipticCurve::cofactor` to recover the cofactor
+        let width = self
+            .modulus
+            .sqrt_vartime()
+            .wrapping_add(&Ufeat::ONE)
+            .shl_vartime(2);
+        let (order, cofactor) = (1..=self.max_cofactor).find_map(|cofactor| {
+            let cofactor = Ufeat::from(cofactor);
+            let (order, remainder) = count.div_rem(&NonZero::new(cofactor).unwrap());
+            (remainder == Ufeat::ZERO
+                && order > width
+                && order != self.modulus // anomalous curves fall to Smart's attack
+                && primality::baillie_psw(&order))
+            .then_some((order, cofactor))
+        })?;
+
+        let generator = self.base_point(&curve, seed, cofactor)?;
+        let (_, x, y) = generator.get();
+        self.candidate(seed, order)?.with_generator(x?, y?).ok()
+    }
+
+    /// The first point derived from `seed` which is not killed by `cofactor`, multiplied by it
+    fn base_point(&self, curve: &Rc<EllipticCurve>, seed: &Seed, cofactor: Ufeat) -> Option<Point> {
+        (0..).find_map(|index| {
+            let x = DynResidue::new(&self.field_element(seed, b'G', index), *curve.p());
+            let y = field::sqrt(&curve.rhs(&x))?;
+            // the smaller root, so that the choice doesn't depend on the square root algorithm
+            let y = if y.retrieve() > (-y).retrieve() {
+                -y
+            } else {
+                y
+            };
+            let point = Point::Regular {
+                x,
+                y,
+                curve: Rc::clone(curve),
+            };
+            (&point * cofactor)
+                .ok()
+                .filter(|point| !point.is_infinity())
+        })
+    }
+
+    /// A residue modulo `modulus` hashed from `seed`, `label` and `index` with rejection sampling
+    fn field_element(&self, seed: &Seed, label: u8, index: u32) -> Ufeat {
+        let bits = self.modulus.bits();
+        let bytes = bits.div_ceil(8);
+        (0u32..)
+            .map(|attempt| {
+                let mut buffer = vec![0u8; Ufeat::BYTES];
+                let mut output = Vec::with_capacity(bytes + 32);
+                for block in 0u32.. {
+                    if output.len() >= bytes {
+                        break;
+                    }
+                    output.extend(
+                        Sha256::new()
+                            .chain_update(seed)
+                            .chain_update([label])
+                            .chain_update(index.to_be_bytes())
+                            .chain_update(attempt.to_be_bytes())
+                            .chain_update(block.to_be_bytes())
+                            .finalize(),
+                    );
+                }
+                buffer[Ufeat::BYTES - bytes..].copy_from_slice(&output[..bytes]);
+                Ufeat::from_be_slice(&buffer).shr_vartime(bytes * 8 - bits)
+            })
+            .find(|candidate| *candidate < self.modulus)
+            .unwrap()
+    }
+}
+
+/// Adds one to `seed` as to a big-endian integer, wrapping around
+fn increment(seed: &mut Seed) {
+    for byte in seed.iter_mut().rev() {
+        let (sum, carry) = byte.overflowing_add(1);
+        *byte = sum;
+        if !carry {
+            break;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Counted with Mestre's method, which is quick even unoptimized
+    const MODULUS: u32 = 1_000_003;
+
+    #[test]
+    fn the_seed_reproduces_the_curve() {
+        let generator = CurveGenerator::new(Ufeat::from(MODULUS));
+        let (curve, seed) = generator.generate_from_seed([7; 32]).unwrap();
+        let curve = Rc::new(curve);
+        assert_eq!(curve.count_points(), Ok(*curve.order()));
+        assert!(primality::baillie_psw(curve.order()));
+        assert!(generator.verify(&curve, &seed));
+
+        // the returned seed is the one which fits, so the search stops at it right away
+        let (again, same_seed) = generator.generate_from_seed(seed).unwrap();
+        assert_eq!(same_seed, seed);
+        let again = Rc::new(again);
+        assert!(again == curve);
+        assert!(again.generator() == curve.generator());
+
+        let mut other = seed;
+        increment(&mut other);
+        assert!(!generator.verify(&curve, &other));
+        assert!(!CurveGenerator::new(Ufeat::from(1_000_033u32)).verify(&curve, &seed));
+    }
+
+    #[test]
+    fn the_cofactor_stays_within_the_bound() {
+        let generator = CurveGenerator::new(Ufeat::from(MODULUS)).with_max_cofactor(4);
+        let (curve, seed) = generator.generate_from_seed([1; 32]).unwrap();
+        let curve = Rc::new(curve);
+        let cofactor = curve.cofactor().unwrap();
+        assert!(Ufeat::ONE <= cofactor && cofactor <= Ufeat::from(4u8));
+        assert_eq!(
+            curve.count_points(),
+            Ok(curve.order().wrapping_mul(&cofactor))
+        );
+        assert!(primality::baillie_psw(curve.order()));
+        assert!(generator.verify(&curve, &seed));
+    }
+
+    #[test]
+    fn gives_up_after_the_attempts() {
+        let generator = CurveGenerator::new(Ufeat::from(MODULUS)).with_max_attempts(0);
+        assert_eq!(
+            generator.generate_from_seed([0; 32]).err(),
+            Some(CurveError::GenerationFailed { attempts: 0 })
+        );
+    }
+
+    #[test]
+    fn increment_carries() {
+        let mut seed = [0xff; 32];
+        seed[0] = 1;
+        increment(&mut seed);
+        let mut expected = [0; 32];
+        expected[0] = 2;
+        assert_eq!(seed, expected);
+    }
+}
@@ -0,0 +1,455 @@
+//! Isogenies from their kernels with Vélu's formulas
+//!
+//! A finite subgroup `K` of a curve is the kernel of exactly one normalized isogeny $\phi: E \to E / K$, and Vélu wrote
+//! the image curve and the map down as sums over the points of `K`. With one `Q` of each pair $\pm Q$ in $K \setminus
+//! \{\infty\}$ and $g_Q = 3x_Q^2 + 2a2.x_Q + a4$, let $v_Q = g_Q$ for the points of order 2 and $2g_Q$ for the others,
+//! $u_Q = 4y_Q^2$, $v = \sum v_Q$ and $w = \sum u_Q + x_Q.v_Q$. Then $E / K$ is
+//! $y^2 = x^3 + a2.x^2 + (a4 - 5v).x + a6 - 4a2.v - 7w$ and
+//!
+//! $\phi(x, y) = (X, y.X')$ with $X = x + \sum \frac{v_Q}{x - x_Q} + \frac{u_Q}{(x - x_Q)^2}$
+//!
+//! The cost grows with the size of the kernel, so it's for the small degrees; a large one of smooth order is a chain of
+//! small prime steps, each kernel the image of the rest of the previous one, which is how CSIDH and SIDH walk.
+use std::{collections::BTreeSet, rc::Rc};
+
+use crypto_bigint::modular::runtime_mod::DynResidue;
+
+use super::{ec::Coefficient, extension::Fp, poly::Poly, EllipticCurve, Point, PointError, Ufeat};
+
+/// `Isogeny::from_generator` gives up on the points of a larger order
+pub const KERNEL_BOUND: u64 = 1 << 12;
+
+/// The part of Vélu's sums one pair $\pm Q$ of the kernel contributes
+#[derive(Clone, Debug)]
+struct Term {
+    x: Fp,
+    v: Fp,
+    u: Fp,
+    order_two: bool,
+}
+
+/// The normalized isogeny with a given kernel, from `domain` onto $domain / kernel$
+#[derive(Clone)]
+pub struct Isogeny {
+    domain: Rc<EllipticCurve>,
+    codomain: Rc<EllipticCurve>,
+    // sorted, starting with infinity
+    kernel: Vec<Point>,
+    terms: Vec<Term>,
+}
+
+impl Isogeny {
+    /// Checks that the points are on `domain` and, with infinity which may be omitted, form a subgroup
+    pub fn new(domain: &Rc<EllipticCurve>, kernel: &[Point]) -> Result<Self, PointError> {
+        let mut points = BTreeSet::from([Point::AtInfinity {
+            curve: Rc::clone(domain),
+        }]);
+        for point in kernel {
+            if *point.curve() != **domain {
+                return Err(PointError::DifferentCurves {
+                    left: domain.name.clone(),
+                    right: point.curve().name.clone(),
+                });
+            }
+            points.insert(point.clone());
+        }
+        for a in &points {
+            for b in &points {
+                if !points.contains(&(a + b)?) {
+                    return Err(PointError::NotSubgroup {
+                        curve: domain.name.clone(),
+                    });
+                }
+            }
+        }
+        Ok(Self::velu(domain, points.into_iter().collect()))
+    }
+
+    /// The isogeny with the kernel spanned by `generator`, of the degree of its order
+    pub fn from_generator(generator: &Point) -> Result<Self, PointError> {
+        let curve = generator.curve();
+        let mut kernel = vec![Point::AtInfinity {
+            curve: Rc::clone(&curve),
+        }];
+        let mut runner = generator.clone();
+        while !runner.is_infinity() {
+            if kernel.len() as u64 >= KERNEL_BOUND {
+                return Err(PointError::OrderOutOfRange {
+                    curve: curve.name.clone(),
+                    low: 1,
+                    high: KERNEL_BOUND,
+                });
+            }
+            kernel.push(runner.clone());
+            runner = (&runner + generator)?;
+        }
+        kernel.sort();
+        Ok(Self::velu(&curve, kernel))
+    }
+
+    /// The steps of the isogeny with the kernel spanned by `generator`, whose order must divide the product of `degrees`
+    ///
+    /// With $n$ the product of the degrees not taken yet, the kernel of each step is spanned by $n.P$ where `P` is the
+    /// image of `generator` under the steps before; for prime degrees a step is the identity of degree 1 where the
+    /// order of `generator` lacks the factor. A zero degree is refused.
+    pub fn chain(generator: &Point, degrees: &[u64]) -> Result<Vec<Self>, PointError> {
+        let curve = generator.curve();
+        if degrees.contains(&0) {
+            return Err(PointError::ZeroDegree {
+                curve: curve.name.clone(),
+            });
+        }
+        let mut n = degrees
+            .iter()
+            .try_fold(1u64, |n, degree| n.checked_mul(*degree))
+            .ok_or_else(|| PointError::FactorizationOverflow {
+                curve: curve.name.clone(),
+            })?;
+        if !(generator * Ufeat::from(n))?.is_infinity() {
+            return Err(PointError::NotAnnihilated {
+                curve: curve.name.clone(),
+                multiple: Ufeat::from(n),
+            });
+        }
+        let mut point = generator.clone();
+        let mut steps = Vec::with_capacity(degrees.len());
+        for degree in degrees {
+            n /= degree;
+            let step = Self::from_generator(&(&point * Ufeat::from(n))?)?;
+            point = step.evaluate(&point)?;
+            steps.push(step);
+        }
+        Ok(steps)
+    }
+
+    /// Vélu's sums over a kernel known to be a subgroup
+    fn velu(domain: &Rc<EllipticCurve>, kernel: Vec<Point>) -> Self {
+        let p = *domain.p();
+        let small = |n: u8| DynResidue::new(&Ufeat::from(n), p);
+        let (a2, a4, a6) = (*domain.a2(), *domain.a4(), *domain.a6());
+        let mut seen = BTreeSet::new();
+        let mut terms = Vec::new();
+        for point in &kernel {
+            let Some((x, y)) = point.x().zip(point.y()) else {
+                continue;
+            };
+            // -Q has the same `x`, and contributes the same as Q
+            if !seen.insert(x.retrieve()) {
+                continue;
+            }
+            let g = small(3) * x.square() + small(2) * a2 * x + a4;
+            let order_two = y.retrieve() == Ufeat::ZERO;
+            terms.push(Term {
+                x: *x,
+                v: if order_two { g } else { small(2) * g },
+                u: small(4) * y.square(),
+                order_two,
+            });
+        }
+        let v = terms
+            .iter()
+            .fold(DynResidue::zero(p), |sum, term| sum + term.v);
+        let w = terms.iter().fold(DynResidue::zero(p), |sum, term| {
+            sum + term.u + term.x * term.v
+        });
+        let coefficients = [a2, a4 - small(5) * v, a6 - small(4) * a2 * v - small(7) * w]
+            .map(|c| Coefficient::Big(c.retrieve()));
+        // isogenous curves have the same number of points, so the order carries over
+        let codomain = EllipticCurve::checked(
+            format!("{}'", domain.name),
+            *domain.order(),
+            domain.original_p(),
+            coefficients,
+            true,
+        )
+        .expect("Vélu's curve is smooth");
+        Isogeny {
+            domain: Rc::clone(domain),
+            codomain: Rc::new(codomain),
+            kernel,
+            terms,
+        }
+    }
+
+    pub fn domain(&self) -> &Rc<EllipticCurve> {
+        &self.domain
+    }
+    pub fn codomain(&self) -> &Rc<EllipticCurve> {
+        &self.codomain
+    }
+    /// The points of the kernel, infinity first
+    pub fn kernel(&self) -> &[Point] {
+        &self.kernel
+    }
+    /// The size of the kernel, the isogeny being separable
+    pub fn degree(&self) -> u64 {
+        self.kernel.len() as u64
+    }
+
+    /// The image of a point of `domain`
+    pub fn evaluate(&self, point: &Point) -> Result<Point, PointError> {
+        if *point.curve() != *self.domain {
+            return Err(PointError::DifferentCurves {
+                left: self.domain.name.clone(),
+                right: point.curve().name.clone(),
+            });
+        }
+        let Some((x, y)) = point.x().zip(point.y()) else {
+            return Ok(Point::AtInfinity {
+                curve: Rc::clone(&self.codomain),
+            });
+        };
+        // only the points of the kernel share `x` with one of it
+        if self.terms.iter().any(|term| term.x == *x) {
+            return Ok(Point::AtInfinity {
+                curve: Rc::clone(&self.codomain),
+            });
+        }
+        let p = *self.domain.p();
+        let (mut image, mut slope) = (*x, DynResidue::one(p));
+        for term in &self.terms {
+            let inverse = (*x - term.x).invert().0;
+            let inverse_square = inverse.square();
+            image += term.v * inverse + term.u * inverse_square;
+            slope -= (term.v + (term.u + term.u) * inverse) * inverse_square;
+        }
+        Ok(Point::Regular {
+            x: image,
+            y: *y * slope,
+            curve: Rc::clone(&self.codomain),
+        })
+    }
+
+    /// $\prod (x - x_Q)$ over one `Q` of each pair $\pm Q$ of the kernel but infinity, from the constant term up
+    pub fn kernel_polynomial(&self) -> Vec<Fp> {
+        self.terms
+            .iter()
+            .fold(
+                Poly::constant(DynResidue::one(*self.domain.p())),
+                |product, term| &product * &self.linear(term),
+            )
+            .coefficients()
+            .to_vec()
+    }
+
+    /// The numerator and the denominator of the `x` of the image as polynomials in `x`, from the constant term up
+    pub fn x_map(&self) -> (Vec<Fp>, Vec<Fp>) {
+        let (numerator, denominator) = self.x_fraction();
+        (
+            numerator.coefficients().to_vec(),
+            denominator.coefficients().to_vec(),
+        )
+    }
+
+    /// The numerator and the denominator of the `y` of the image divided by `y`, as polynomials in `x`
+    pub fn y_map(&self) -> (Vec<Fp>, Vec<Fp>) {
+        let (numerator, denominator) = self.x_fraction();
+        let derivative =
+            &(&numerator.derivative() * &denominator) - &(&numerator * &denominator.derivative());
+        (
+            derivative.coefficients().to_vec(),
+            denominator.square().coefficients().to_vec(),
+        )
+    }
+
+    /// $x - x_Q$
+    fn linear(&self, term: &Term) -> Poly<Fp> {
+        Poly::new(vec![-term.x, DynResidue::one(*self.domain.p())], &term.x)
+    }
+
+    /// `X` over the common denominator, where the points of order 2 appear once and the others squared
+    fn x_fraction(&self) -> (Poly<Fp>, Poly<Fp>) {
+        let one = DynResidue::one(*self.domain.p());
+        let denominator = self
+            .terms
+            .iter()
+            .fold(Poly::constant(one), |product, term| {
+                let linear = self.linear(term);
+                if term.order_two {
+                    &product * &linear
+                } else {
+                    &product * &linear.square()
+                }
+            });
+        let numerator = self
+            .terms
+            .iter()
+            .fold(&Poly::x(&one) * &denominator, |sum, term| {
+                let linear = self.linear(term);
+                let once = denominator.div_rem(&linear).0;
+                let mut sum = &sum + &once.scale(&term.v);
+                if !term.order_two {
+                    sum = &sum + &once.div_rem(&linear).0.scale(&term.u);
+                }
+                sum
+            });
+        (numerator, denominator)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::primality;
+
+    const P: u16 = 10007;
+
+    /// $y^2 = x^3 + x$, supersingular as $p = 3 \bmod 4$, so with $p + 1$ points
+    fn supersingular() -> Rc<EllipticCurve> {
+        Rc::new(EllipticCurve::new(
+            "supersingular".to_string(),
+            Ufeat::from(P + 1),
+            Ufeat::from(P),
+            [0, 1, 0],
+        ))
+    }
+
+    /// $y^2 = x^3 + 3x + 5$, with a cyclic group of $10125 = 3^4.5^3$ points
+    fn smooth() -> Rc<EllipticCurve> {
+        Rc::new(EllipticCurve::new(
+            "smooth".to_string(),
+            Ufeat::from(10125u16),
+            Ufeat::from(P),
+            [0, 3, 5],
+        ))
+    }
+
+    fn fp(curve: &EllipticCurve, n: i64) -> Fp {
+        let c = DynResidue::new(&Ufeat::from(n.unsigned_abs()), *curve.p());
+        if n < 0 {
+            -c
+        } else {
+            c
+        }
+    }
+
+    /// A point of `curve` of order `n` dividing the order of the cyclic group
+    fn of_order(curve: &Rc<EllipticCurve>, n: u64) -> Point {
+        let factorization = primality::factor(*curve.order());
+        let generator = curve
+            .points()
+            .find(|point| point.order(&factorization) == Ok(*curve.order()))
+            .unwrap();
+        let cofactor = curve.order().wrapping_div(&Ufeat::from(n));
+        (&generator * cofactor).unwrap()
+    }
+
+    fn horner(coefficients: &[Fp], x: &Fp) -> Fp {
+        coefficients
+            .iter()
+            .rev()
+            .fold(DynResidue::zero(*x.params()), |sum, c| sum * *x + *c)
+    }
+
+    /// The laws of `isogeny` on the first points of its domain
+    fn check(isogeny: &Isogeny) {
+        for point in isogeny.kernel() {
+            assert!(isogeny.evaluate(point).unwrap().is_infinity());
+        }
+        let (x_numerator, x_denominator) = isogeny.x_map();
+        let (y_numerator, y_denominator) = isogeny.y_map();
+        let points: Vec<Point> = isogeny.domain().points().take(12).collect();
+        for a in &points {
+            let image = isogeny.evaluate(a).unwrap();
+            assert!(image.curve() == *isogeny.codomain());
+            for b in &points {
+                let sum = isogeny.evaluate(&(a + b).unwrap()).unwrap();
+                assert!(sum == (&image + &isogeny.evaluate(b).unwrap()).unwrap());
+            }
+            if let (Some(x), Some(y)) = (a.x(), a.y()) {
+                if !image.is_infinity() {
+                    let ratio = |numerator: &[Fp], denominator: &[Fp]| {
+                        horner(numerator, x) * horner(denominator, x).invert().0
+                    };
+                    assert_eq!(*image.x().unwrap(), ratio(&x_numerator, &x_denominator));
+                    assert_eq!(
+                        *image.y().unwrap(),
+                        *y * ratio(&y_numerator, &y_denominator)
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn halving_by_the_point_of_order_two_matches_silverman() {
+        // Silverman, example III.4.5: y^2 = x^3 + a.x^2 + b.x over (0, 0) is y^2 = x^3 - 2a.x^2 + (a^2 - 4b).x,
+        // and x maps to y^2 / x^2 = x + b / x
+        let curve = supersingular();
+        let two = Point::new(Rc::clone(&curve), Some(Ufeat::ZERO), Some(Ufeat::ZERO)).unwrap();
+        for isogeny in [
+            Isogeny::from_generator(&two).unwrap(),
+            Isogeny::new(&curve, std::slice::from_ref(&two)).unwrap(),
+        ] {
+            let codomain = isogeny.codomain();
+            assert_eq!(isogeny.degree(), 2);
+            assert_eq!(*codomain.a2(), fp(&curve, 0));
+            assert_eq!(*codomain.a4(), fp(&curve, -4));
+            assert_eq!(*codomain.a6(), fp(&curve, 0));
+            assert_eq!(
+                isogeny.x_map(),
+                (
+                    vec![fp(&curve, 1), fp(&curve, 0), fp(&curve, 1)],
+                    vec![fp(&curve, 0), fp(&curve, 1)]
+                )
+            );
+            check(&isogeny);
+        }
+    }
+
+    #[test]
+    fn odd_isogenies_are_homomorphisms() {
+        let curve = smooth();
+        for n in [3, 5] {
+            let isogeny = Isogeny::from_generator(&of_order(&curve, n)).unwrap();
+            assert_eq!(isogeny.degree(), n);
+            assert_eq!(isogeny.kernel_polynomial().len() as u64, n.div_ceil(2));
+            check(&isogeny);
+        }
+    }
+
+    #[test]
+    fn new_refuses_a_non_subgroup() {
+        let curve = smooth();
+        let three = of_order(&curve, 3);
+        assert_eq!(
+            Isogeny::new(&curve, &[three]).err(),
+            Some(PointError::NotSubgroup {
+                curve: "smooth".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn a_chain_kills_its_generator() {
+        let curve = smooth();
+        let generator = of_order(&curve, 45);
+        let steps = Isogeny::chain(&generator, &[3, 3, 5]).unwrap();
+        assert_eq!(
+            steps.iter().map(Isogeny::degree).collect::<Vec<_>>(),
+            [3, 3, 5]
+        );
+        let image = steps
+            .iter()
+            .try_fold(generator.clone(), |point, step| step.evaluate(&point))
+            .unwrap();
+        assert!(image.is_infinity());
+        for pair in steps.windows(2) {
+            assert!(pair[0].codomain() == pair[1].domain());
+        }
+
+        assert_eq!(
+            Isogeny::chain(&generator, &[3, 0, 5]).err(),
+            Some(PointError::ZeroDegree {
+                curve: "smooth".to_string()
+            })
+        );
+        assert_eq!(
+            Isogeny::chain(&generator, &[3, 5]).err(),
+            Some(PointError::NotAnnihilated {
+                curve: "smooth".to_string(),
+                multiple: Ufeat::from(15u8)
+            })
+        );
+    }
+}
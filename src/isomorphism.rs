@@ -0,0 +1,430 @@
+//! The j-invariant, isomorphisms between curves and quadratic twists
+//!
+//! `PartialEq` of `EllipticCurve` compares the coefficients, while the same group hides behind many equations: the
+//! change of variables $x = u^2.x' + r$, $y = u^3.y' + s.u^2.x' + t$ carries a curve onto another one, and the forms
+//! with no $xy$ and $y$ terms the crate uses are kept exactly when $s = t = 0$ in odd characteristic. Curves which
+//! share the j-invariant are isomorphic over the algebraic closure, and over $F_p$ itself either isomorphic or twists
+//! of each other: the quadratic twist by a non-square `d` becomes isomorphic only over $F_p(\sqrt{d})$.
+use std::rc::Rc;
+
+use crypto_bigint::modular::runtime_mod::DynResidue;
+
+use super::{
+    ec::Coefficient,
+    encoding::to_hex,
+    extension::{Field, Fp},
+    poly::Poly,
+    CurveError, EllipticCurve, Error, FieldError, Point, PointError, PointOver, Ufeat,
+};
+
+/// $x = u^2.x' + r$, $y = u^3.y' + s.u^2.x' + t$ from `domain` onto `codomain`
+#[derive(Clone)]
+pub struct Isomorphism {
+    domain: Rc<EllipticCurve>,
+    codomain: Rc<EllipticCurve>,
+    u: Fp,
+    r: Fp,
+}
+
+impl Isomorphism {
+    pub fn domain(&self) -> &Rc<EllipticCurve> {
+        &self.domain
+    }
+    pub fn codomain(&self) -> &Rc<EllipticCurve> {
+        &self.codomain
+    }
+    /// `(u, r, s, t)`, where `s` and `t` are always zero between the curves of the crate
+    pub fn parameters(&self) -> (Fp, Fp, Fp, Fp) {
+        let zero = DynResidue::zero(*self.u.params());
+        (self.u, self.r, zero, zero)
+    }
+
+    /// The isomorphism back, $(1 / u, -r / u^2, 0, 0)$
+    pub fn inverse(&self) -> Self {
+        let inverse = self.u.invert().0;
+        Isomorphism {
+            domain: Rc::clone(&self.codomain),
+            codomain: Rc::clone(&self.domain),
+            u: inverse,
+            r: -self.r * inverse.square(),
+        }
+    }
+
+    /// The image of a point of `domain`: $((x - r) / u^2, y / u^3)$
+    pub fn evaluate(&self, point: &Point) -> Result<Point, PointError> {
+        if *point.curve() != *self.domain {
+            return Err(PointError::DifferentCurves {
+                left: self.domain.name.clone(),
+                right: point.curve().name.clone(),
+            });
+        }
+        Ok(match point.x().zip(point.y()) {
+            None => Point::AtInfinity {
+                curve: Rc::clone(&self.codomain),
+            },
+            Some((x, y)) => {
+                let inverse = self.u.invert().0;
+                let inverse_square = inverse.square();
+                Point::Regular {
+                    x: (*x - self.r) * inverse_square,
+                    y: *y * inverse_square * inverse,
+                    curve: Rc::clone(&self.codomain),
+                }
+            }
+        })
+    }
+}
+
+/// $E^d: y^2 = x^3 + d.a2.x^2 + d^2.a4.x + d^3.a6$, which is $d.y^2 = x^3 + a2.x^2 + a4.x + a6$ in disguise
+///
+/// The `x` of $E(F_p)$ and of $E^d(F_p)$ over `d` cover $F_p$ together, so the two have $2p + 2$ points; an `x` which a
+/// single coordinate ladder accepts without checking lands on the twist when it's off the curve.
+#[derive(Clone)]
+pub struct QuadraticTwist {
+    curve: Rc<EllipticCurve>,
+    twist: Rc<EllipticCurve>,
+    d: Fp,
+}
+
+impl QuadraticTwist {
+    pub fn curve(&self) -> &Rc<EllipticCurve> {
+        &self.curve
+    }
+    /// The twisted curve, with its number of points as the order
+    pub fn twist(&self) -> &Rc<EllipticCurve> {
+        &self.twist
+    }
+    pub fn d(&self) -> &Fp {
+        &self.d
+    }
+
+    /// The point $(d.x, y)$ of the twist for an `x` which no point of the curve over $F_p$ has, or whose right hand side
+    /// is zero
+    pub fn twist_point_at(&self, x: Ufeat) -> Result<Point, PointError> {
+        if x >= self.curve.original_p() {
+            return Err(PointError::CoordinateOutOfField {
+                curve: self.curve.name.clone(),
+                coordinate: x,
+            });
+        }
+        let x = DynResidue::new(&x, *self.curve.p()) * self.d;
+        let Some(y) = self.twist.rhs(&x).sqrt() else {
+            return Err(PointError::NoPointWithX {
+                curve: self.twist.name.clone(),
+                x: x.retrieve(),
+            });
+        };
+        Ok(Point::Regular {
+            x,
+            y,
+            curve: Rc::clone(&self.twist),
+        })
+    }
+
+    /// $(x, y) \mapsto (d.x, d \sqrt{d}.y)$, the isomorphism onto the twist over a field where `d` is a square, with the
+    /// root of the smaller coefficients
+    pub fn map<F: Field>(&self, point: &PointOver<F>) -> Result<PointOver<F>, Error> {
+        let curve = point.curve();
+        let like = curve.a2();
+        if *curve.a2() != like.embed(self.curve.a2())
+            || *curve.a4() != like.embed(self.curve.a4())
+            || *curve.a6() != like.embed(self.curve.a6())
+        {
+            return Err(PointError::DifferentCurves {
+                left: self.curve.name.clone(),
+                right: curve.name.clone(),
+            }
+            .into());
+        }
+        let d = like.embed(&self.d);
+        let root = d.sqrt().ok_or_else(|| FieldError::NoSquareRoot {
+            d: self.d.retrieve(),
+            degree: like.to_coefficients().len(),
+        })?;
+        // `sqrt` may return either root, and a map must stick to one
+        let root = if (-root).to_coefficients() < root.to_coefficients() {
+            -root
+        } else {
+            root
+        };
+        let twist = Rc::new(self.twist.over(like)?);
+        Ok(match point.x().zip(point.y()) {
+            None => twist.infinity(),
+            Some((x, y)) => twist.point(d * *x, d * root * *y)?,
+        })
+    }
+}
+
+impl EllipticCurve {
+    /// $c_4^3 / \Delta$, the same for the curves isomorphic over the algebraic closure
+    pub fn j_invariant(&self) -> Fp {
+        let small = |n: u8| DynResidue::new(&Ufeat::from(n), *self.p());
+        let [a2, a4, a6] = coefficients(self);
+        let (b2, b4, b6) = (small(4) * a2, small(2) * a4, small(4) * a6);
+        let b8 = small(4) * a2 * a6 - a4.square();
+        let c4 = b2.square() - small(24) * b4;
+        let discriminant =
+            -(b2.square() * b8) - small(8) * b4.square() * b4 - small(27) * b6.square()
+                + small(9) * b2 * b4 * b6;
+        c4.square() * c4 * discriminant.invert().0
+    }
+
+    /// The isomorphism over $F_p$ onto `other` with the smallest `u`, then `r`; `None` when there's none, twists
+    /// included
+    ///
+    /// Moving both curves to $y^2 = x^3 + A.x + B$ leaves `u` with $u^2 = B.A' / (B'.A)$, or $u^4 = A / A'$ for $j =
+    /// 1728$ and $u^6 = B / B'$ for $j = 0$, where the extra automorphisms are. Characteristic 3, which has no such form,
+    /// is searched through.
+    pub fn is_isomorphic(self: &Rc<Self>, other: &Rc<Self>) -> Option<Isomorphism> {
+        if self.original_p() != other.original_p() || self.j_invariant() != other.j_invariant() {
+            return None;
+        }
+        let p = *self.p();
+        let small = |n: u8| DynResidue::new(&Ufeat::from(n), p);
+        let (zero, one) = (small(0), small(1));
+        let candidates: Vec<_> = if self.original_p() == Ufeat::from(3u8) {
+            (1..3)
+                .flat_map(|u| (0..3).map(move |r| (small(u), small(r))))
+                .collect()
+        } else {
+            let third = small(3).invert().0;
+            let (r1, r2) = (-*self.a2() * third, -*other.a2() * third);
+            let [_, a, b] = transform(coefficients(self), &one, &r1);
+            let [_, a_other, b_other] = transform(coefficients(other), &one, &r2);
+            let (k, c) = if a.is_zero() {
+                (6, b * b_other.invert().0)
+            } else if b.is_zero() {
+                (4, a * a_other.invert().0)
+            } else {
+                (2, b * a_other * (b_other * a).invert().0)
+            };
+            let mut polynomial = vec![zero; k + 1];
+            (polynomial[0], polynomial[k]) = (-c, one);
+            // x = u^2.x' + r1 - u^2.r2 through the two short forms
+            Poly::new(polynomial, &one)
+                .roots()
+                .into_iter()
+                .map(|u| (u, r1 - u.square() * r2))
+                .collect()
+        };
+        let target = coefficients(other);
+        candidates
+            .into_iter()
+            .filter(|(u, r)| transform(coefficients(self), u, r) == target)
+            .min_by_key(|(u, r)| (u.retrieve(), r.retrieve()))
+            .map(|(u, r)| Isomorphism {
+                domain: Rc::clone(self),
+                codomain: Rc::clone(other),
+                u,
+                r,
+            })
+    }
+
+    /// The twist by a non-square `d`; its order is the number of points $2p + 2 - \#E$, from the cofactor when
+    /// `order` settles it and counted otherwise
+    pub fn quadratic_twist(self: &Rc<Self>, d: Ufeat) -> Result<QuadraticTwist, Error> {
+        let modulus = self.original_p();
+        if d >= modulus {
+            return Err(CurveError::CoefficientOutOfField {
+                coefficient: "d",
+                modulus,
+            }
+            .into());
+        }
+        let twisting = DynResidue::new(&d, *self.p());
+        if twisting.is_square() {
+            return Err(CurveError::SquareTwist {
+                name: self.name.clone(),
+                d,
+            }
+            .into());
+        }
+        let count = match self.cofactor() {
+            Some(cofactor) => cofactor.wrapping_mul(self.order()),
+            None => self.count_points()?,
+        };
+        let twist_count = modulus
+            .wrapping_add(&Ufeat::ONE)
+            .shl_vartime(1)
+            .wrapping_sub(&count);
+        let [a2, a4, a6] = coefficients(self);
+        let coefficients = [
+            twisting * a2,
+            twisting.square() * a4,
+            twisting.square() * twisting * a6,
+        ]
+        .map(|c| Coefficient::Big(c.retrieve()));
+        // an anomalous twist is a finding of the analysis rather than an error
+        let twist = EllipticCurve::checked(
+            format!("{} twisted by {}", self.name, to_hex(&d)),
+            twist_count,
+            modulus,
+            coefficients,
+            true,
+        )?;
+        Ok(QuadraticTwist {
+            curve: Rc::clone(self),
+            twist: Rc::new(twist),
+            d: twisting,
+        })
+    }
+}
+
+fn coefficients(curve: &EllipticCurve) -> [Fp; 3] {
+    [*curve.a2(), *curve.a4(), *curve.a6()]
+}
+
+/// The coefficients after $x = u^2.x' + r$, $y = u^3.y'$
+fn transform([a2, a4, a6]: [Fp; 3], u: &Fp, r: &Fp) -> [Fp; 3] {
+    let small = |n: u8| DynResidue::new(&Ufeat::from(n), *u.params());
+    let inverse = u.invert().0.square();
+    [
+        (a2 + small(3) * r) * inverse,
+        (small(3) * r.square() + small(2) * a2 * r + a4) * inverse.square(),
+        ((r.square() + a2 * r + a4) * r + a6) * inverse.square() * inverse,
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::extension::Fp2;
+
+    // 3 mod 4, so -1 is a non-square to twist by
+    const P: u16 = 10007;
+
+    /// $y^2 = x^3 + 3x + 5$, with 10125 points
+    fn curve() -> Rc<EllipticCurve> {
+        Rc::new(EllipticCurve::new(
+            "curve".to_string(),
+            Ufeat::from(10125u16),
+            Ufeat::from(P),
+            [0, 3, 5],
+        ))
+    }
+
+    fn fp(n: u16) -> Fp {
+        DynResidue::new(&Ufeat::from(n), *curve().p())
+    }
+
+    /// The curve `curve` is carried onto by $x = u^2.x' + r$
+    fn moved(curve: &EllipticCurve, u: u16, r: u16) -> Rc<EllipticCurve> {
+        let coefficients =
+            transform(coefficients(curve), &fp(u), &fp(r)).map(|c| Coefficient::Big(c.retrieve()));
+        Rc::new(
+            EllipticCurve::checked(
+                "moved".to_string(),
+                *curve.order(),
+                curve.original_p(),
+                coefficients,
+                false,
+            )
+            .unwrap(),
+        )
+    }
+
+    #[test]
+    fn j_invariants_of_the_special_curves() {
+        // both supersingular, as p = 3 mod 4 and p = 2 mod 3, so with p + 1 points
+        let (p, order) = (Ufeat::from(P), Ufeat::from(P + 1));
+        let j = |coefficients| {
+            EllipticCurve::new("j".to_string(), order, p, coefficients).j_invariant()
+        };
+        assert_eq!(j([0, 1, 0]), fp(1728));
+        assert_eq!(j([0, 0, 5]), fp(0));
+    }
+
+    #[test]
+    fn isomorphic_curves_share_the_j_invariant() {
+        let curve = curve();
+        let other = moved(&curve, 2, 3);
+        assert_ne!(coefficients(&curve), coefficients(&other));
+        assert_eq!(curve.j_invariant(), other.j_invariant());
+
+        let isomorphism = curve.is_isomorphic(&other).unwrap();
+        assert_eq!(isomorphism.parameters(), (fp(2), fp(3), fp(0), fp(0)));
+        let inverse = isomorphism.inverse();
+        assert!(*inverse.codomain() == curve);
+        for point in curve.points().take(8) {
+            let image = isomorphism.evaluate(&point).unwrap();
+            let (_, x, y) = image.get();
+            assert!(Point::new(Rc::clone(&other), x, y).is_ok());
+            assert!(inverse.evaluate(&image).unwrap() == point);
+        }
+        assert_eq!(
+            inverse.evaluate(&curve.points().next().unwrap()).err(),
+            Some(PointError::DifferentCurves {
+                left: "moved".to_string(),
+                right: "curve".to_string(),
+            })
+        );
+
+        // same j-invariant, yet a twist
+        let twist = curve.quadratic_twist(Ufeat::from(P - 1)).unwrap();
+        assert_eq!(curve.j_invariant(), twist.twist().j_invariant());
+        assert!(curve.is_isomorphic(twist.twist()).is_none());
+    }
+
+    #[test]
+    fn a_curve_and_its_twist_share_2p_plus_2_points() {
+        let curve = curve();
+        let twist = curve.quadratic_twist(Ufeat::from(P - 1)).unwrap();
+        let twisted = twist.twist();
+        assert_eq!(*twisted.order(), Ufeat::from(2 * P + 2 - 10125));
+        assert_eq!(twisted.count_points().unwrap(), *twisted.order());
+
+        assert_eq!(
+            curve.quadratic_twist(Ufeat::from(4u8)).err(),
+            Some(Error::Curve(CurveError::SquareTwist {
+                name: "curve".to_string(),
+                d: Ufeat::from(4u8),
+            }))
+        );
+        assert!(curve.quadratic_twist(Ufeat::from(P)).is_err());
+    }
+
+    #[test]
+    fn twist_points_sit_over_the_x_the_curve_misses() {
+        let curve = curve();
+        let twist = curve.quadratic_twist(Ufeat::from(P - 1)).unwrap();
+        for x in 0..32u16 {
+            let result = twist.twist_point_at(Ufeat::from(x));
+            let rhs = curve.rhs(&fp(x));
+            if rhs.is_square() && !rhs.is_zero() {
+                assert!(matches!(result, Err(PointError::NoPointWithX { .. })));
+            } else {
+                let (_, x, y) = result.as_ref().unwrap().get();
+                assert!(Point::new(Rc::clone(twist.twist()), x, y).is_ok());
+            }
+        }
+        assert!(matches!(
+            twist.twist_point_at(Ufeat::from(P)),
+            Err(PointError::CoordinateOutOfField { .. })
+        ));
+    }
+
+    #[test]
+    fn the_twist_map_lands_on_the_twist() {
+        let curve = curve();
+        let twist = curve.quadratic_twist(Ufeat::from(P - 1)).unwrap();
+        let like = Fp2::one(-fp(1)).unwrap();
+        let over = Rc::new(curve.over(&like).unwrap());
+        let (a, b) = (over.random_point(), over.random_point());
+        let image = twist.map(&a).unwrap();
+        assert_eq!(*image.curve().a4(), like.embed(twist.twist().a4()));
+        assert_eq!(*image.curve().a6(), like.embed(twist.twist().a6()));
+        assert_eq!(
+            twist.map(&(&a + &b).unwrap()).unwrap(),
+            (&image + &twist.map(&b).unwrap()).unwrap()
+        );
+        assert!(twist.map(&over.infinity()).unwrap().is_infinity());
+
+        // -1 has no root over F_p
+        let point = curve.points().nth(1).unwrap();
+        let over = Rc::new(curve.over(&fp(1)).unwrap());
+        assert!(matches!(
+            twist.map(&point.over(&over).unwrap()),
+            Err(Error::Field(FieldError::NoSquareRoot { .. }))
+        ));
+    }
+}
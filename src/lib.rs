@@ -1,10 +1,9 @@
-#![feature(result_option_inspect)]
-#![feature(iter_intersperse)]
-
-/// This is migration of https://github.com/cjeudy/EllipticCurves to Rust. 
-/// 
+// the errors carry the offending integers by value; boxing them would only complicate matching on the paths which are rare anyway
+#![allow(clippy::result_large_err)]
+/// This is migration of https://github.com/cjeudy/EllipticCurves to Rust.
+///
 /// It aims to be as close as possible to "drop-in" replacement. Mentions of "source" through-out
-/// the crate usually means the original code at the forementioned link. Diviations 
+/// the crate usually means the original code at the forementioned link. Diviations
 /// from the source are mostly labeled (in the comments) or induced by Clippy.
 ///
 /// A big up-front deviation is decision to use `crypto-bigint` for big integers. It breaks absent of limit on integers value which source have, but it's a deliberate choice for the exercise
@@ -26,35 +25,55 @@ so only `p` going to be processed with this approach */
 it seems to be constant time (which isn't directly) about safe usage in structs; without `zeroize` feature both are very comparable */
     /*   With default feature flags of crypto bigint crate I see no difference in terms of security between storing `UInt` and DynResidue... .
     Didn't look into `subtle` and zeroize features though. */
+/*     adopted the latter as `zeroize` feature: points wipe their coordinates when dropped; the residue parameters stay as they're public anyway */
 use crypto_bigint::Uint;
 #[cfg(not(feature = "u8192"))]
 use crypto_bigint::U512;
 #[cfg(feature = "u8192")]
 use crypto_bigint::U8192;
 
-use thiserror::Error;
-
 #[cfg(not(feature = "u8192"))]
 type Ufeat = U512;
 #[cfg(feature = "u8192")]
 type Ufeat = U8192;
 
-#[derive(Error, Debug)]
-pub enum Errs {
-    #[error("The point is not on the curve")]
-    ValueError,
-    #[error("Curve is not smooth.")]
-    Exception,
-    #[error("One of coordinates is out of the underlying field.")]
-    NccModulus,
-    #[error("Can't create a point as the coordinates aren't on the given curve.")]
-    NccOutOfTheCurve,
-}
-
+mod audit;
+pub mod bls;
+pub mod bls12_381;
+mod counting;
+pub mod curves;
+pub mod dlog;
 mod ec;
+mod encoding;
+pub mod error;
+pub mod extension;
+mod field;
+mod generator;
+mod isogeny;
+mod isomorphism;
+mod over;
+mod padic;
+pub mod pairing;
+mod parsing;
 mod point;
+mod poly;
+pub mod primality;
+mod schoof;
+#[cfg(feature = "serde")]
+mod serialization;
+mod torsion;
+pub use audit::{AuditReport, EMBEDDING_DEGREE_BOUND};
+pub use counting::{Points, COUNTING_BOUND_BITS};
 pub use ec::EllipticCurve;
-pub use point::Point;
+pub use error::{CurveError, EncodingError, Error, FieldError, PointError, ProtocolError};
+pub use generator::{CurveGenerator, Seed};
+pub use isogeny::{Isogeny, KERNEL_BOUND};
+pub use isomorphism::{Isomorphism, QuadraticTwist};
+pub use over::{CurveOver, PointOver};
+pub use point::{CtPoint, Point};
+#[cfg(feature = "serde")]
+pub use serialization::{CurvePoints, OnCurve};
+pub use torsion::DivisionPolynomial;
 
 /// Computes the non adjacent form of an integer n
 pub fn non_adjacent<const T: usize>(n: Uint<T>) -> Vec<i8> {
@@ -0,0 +1,298 @@
+//! Curves and points over any `Field`, the extension fields foremost
+//!
+//! `EllipticCurve` and `Point` stay the prime field types the rest of the crate is built on; a curve is carried over to
+//! an extension with `EllipticCurve::over` and its points with `Point::over`, where they meet the points which exist only
+//! there, such as the full torsion $E[r] \cong \mathbb{Z}_r^2$ needed for pairings. The formulas are the ones of `Point`;
+//! nothing here is constant time.
+use std::{fmt, rc::Rc};
+
+use crypto_bigint::{modular::runtime_mod::DynResidue, Uint};
+
+use super::{
+    encoding::to_hex, extension::Field, CurveError, EllipticCurve, FieldError, Point, PointError,
+    Ufeat,
+};
+
+/// $y^2 = x^3 + a2.x^2 + a4.x + a6$ over the field of the coefficients
+#[derive(Clone, Debug, PartialEq)]
+pub struct CurveOver<F: Field> {
+    pub name: String,
+    a2: F,
+    a4: F,
+    a6: F,
+}
+
+impl<F: Field> CurveOver<F> {
+    /// Refuses a singular curve, like `EllipticCurve::try_new` does
+    pub fn new(name: String, [a2, a4, a6]: [F; 3]) -> Result<Self, CurveError> {
+        let curve = CurveOver { name, a2, a4, a6 };
+        if curve.discriminant().is_zero() {
+            return Err(CurveError::Singular { name: curve.name });
+        }
+        Ok(curve)
+    }
+    pub fn a2(&self) -> &F {
+        &self.a2
+    }
+    pub fn a4(&self) -> &F {
+        &self.a4
+    }
+    pub fn a6(&self) -> &F {
+        &self.a6
+    }
+
+    /// The discriminant of the cubic on the right hand side, zero exactly for the singular curves in odd characteristic
+    fn discriminant(&self) -> F {
+        let small = |n| small(&self.a2, n);
+        let (b, c, d) = (self.a2, self.a4, self.a6);
+        small(18) * b * c * d - small(4) * b.square() * b * d + b.square() * c.square()
+            - small(4) * c.square() * c
+            - small(27) * d.square()
+    }
+
+    /// The slope of the chord through the points, or of the tangent when they're equal; `None` for a vertical line,
+    /// the one through a point and its negation
+    pub(crate) fn slope(&self, (x1, y1): (&F, &F), (x2, y2): (&F, &F)) -> Option<F> {
+        if x1 == x2 {
+            // a point of order 2 doubled included
+            if y1 != y2 || y1.is_zero() {
+                return None;
+            }
+            Some(
+                (small(x1, 3) * x1.square() + self.a2.double() * *x1 + self.a4)
+                    * y1.double().inverse().unwrap(),
+            )
+        } else {
+            Some((*y2 - *y1) * (*x2 - *x1).inverse().unwrap())
+        }
+    }
+
+    /// $x^3 + a2.x^2 + a4.x + a6$
+    pub fn rhs(&self, x: &F) -> F {
+        (x.square() + self.a2 * *x + self.a4) * *x + self.a6
+    }
+
+    pub fn contains(&self, point: &PointOver<F>) -> bool {
+        match &point.coordinates {
+            None => true,
+            Some((x, y)) => y.square() == self.rhs(x),
+        }
+    }
+
+    pub fn infinity(self: &Rc<Self>) -> PointOver<F> {
+        PointOver {
+            curve: Rc::clone(self),
+            coordinates: None,
+        }
+    }
+
+    /// Validates the coordinates against the curve equation
+    pub fn point(self: &Rc<Self>, x: F, y: F) -> Result<PointOver<F>, PointError> {
+        if y.square() != self.rhs(&x) {
+            return Err(PointError::NotOnCurveOver {
+                curve: self.name.clone(),
+                x: x.to_coefficients(),
+                y: y.to_coefficients(),
+            });
+        }
+        Ok(PointOver {
+            curve: Rc::clone(self),
+            coordinates: Some((x, y)),
+        })
+    }
+
+    /// A point with a random `x`, retried until the right hand side is a square; about half of them are
+    pub fn random_point(self: &Rc<Self>) -> PointOver<F> {
+        loop {
+            let x = self.a2.random();
+            if let Some(y) = self.rhs(&x).sqrt() {
+                return PointOver {
+                    curve: Rc::clone(self),
+                    coordinates: Some((x, y)),
+                };
+            }
+        }
+    }
+}
+
+impl<F: Field> fmt::Display for CurveOver<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "< Elliptic curve {} over a field of degree {} >\n\ty^2 = x^3 + a2.x^2 + a4.x + a6\n\ta2 = {}\n\ta4 = {}\n\ta6 = {}",
+            self.name,
+            F::DEGREE,
+            coefficients(&self.a2),
+            coefficients(&self.a4),
+            coefficients(&self.a6)
+        )
+    }
+}
+
+/// A small constant of the formulas in the field of `like`
+fn small<F: Field>(like: &F, n: u8) -> F {
+    like.embed(&DynResidue::new(&Ufeat::from(n), like.prime_params()))
+}
+
+impl EllipticCurve {
+    /// The same curve over the field of `like`, which must have the characteristic `p`
+    pub fn over<F: Field>(&self, like: &F) -> Result<CurveOver<F>, FieldError> {
+        if like.characteristic() != self.original_p() {
+            return Err(FieldError::CharacteristicMismatch {
+                expected: self.original_p(),
+                found: like.characteristic(),
+            });
+        }
+        Ok(CurveOver {
+            name: self.name.clone(),
+            a2: like.embed(self.a2()),
+            a4: like.embed(self.a4()),
+            a6: like.embed(self.a6()),
+        })
+    }
+}
+
+impl Point {
+    /// The point on the curve carried over to an extension with `EllipticCurve::over`
+    pub fn over<F: Field>(&self, curve: &Rc<CurveOver<F>>) -> Result<PointOver<F>, PointError> {
+        match (self.x(), self.y()) {
+            (Some(x), Some(y)) => curve.point(curve.a2.embed(x), curve.a2.embed(y)),
+            _ => Ok(curve.infinity()),
+        }
+    }
+}
+
+/// A point of `CurveOver`; `None` coordinates stand for the point at infinity
+#[derive(Clone, Debug)]
+pub struct PointOver<F: Field> {
+    curve: Rc<CurveOver<F>>,
+    coordinates: Option<(F, F)>,
+}
+
+impl<F: Field> PointOver<F> {
+    pub fn curve(&self) -> &Rc<CurveOver<F>> {
+        &self.curve
+    }
+    pub fn x(&self) -> Option<&F> {
+        self.coordinates.as_ref().map(|(x, _)| x)
+    }
+    pub fn y(&self) -> Option<&F> {
+        self.coordinates.as_ref().map(|(_, y)| y)
+    }
+    pub fn is_infinity(&self) -> bool {
+        self.coordinates.is_none()
+    }
+
+    pub(crate) fn require_same_curve(&self, other: &Self) -> Result<(), PointError> {
+        if Rc::ptr_eq(&self.curve, &other.curve) || self.curve == other.curve {
+            Ok(())
+        } else {
+            Err(PointError::DifferentCurves {
+                left: self.curve.name.clone(),
+                right: other.curve.name.clone(),
+            })
+        }
+    }
+
+    fn with(&self, coordinates: Option<(F, F)>) -> Self {
+        PointOver {
+            curve: Rc::clone(&self.curve),
+            coordinates,
+        }
+    }
+
+    /// $(x^p, y^p)$: the Frobenius endomorphism, which fixes exactly the points over the prime field
+    pub fn frobenius(&self) -> Self {
+        self.with(
+            self.coordinates
+                .map(|(x, y)| (x.frobenius(), y.frobenius())),
+        )
+    }
+
+    /// `k * self` for `k` of any width, as the cofactors of the extensions outgrow `Ufeat`
+    pub fn mul_vartime<const L: usize>(&self, k: &Uint<L>) -> Self {
+        let mut result = self.with(None);
+        for i in (0..k.bits_vartime()).rev() {
+            result = result.double();
+            if k.bit_vartime(i) {
+                result = result.add_unchecked(self);
+            }
+        }
+        result
+    }
+
+    fn double(&self) -> Self {
+        self.add_unchecked(self)
+    }
+
+    /// The chord and tangent rule for points known to be on the same curve
+    fn add_unchecked(&self, other: &Self) -> Self {
+        let ((x1, y1), (x2, y2)) = match (&self.coordinates, &other.coordinates) {
+            (None, _) => return other.clone(),
+            (_, None) => return self.clone(),
+            (Some(first), Some(second)) => (first, second),
+        };
+        match self.curve.slope((x1, y1), (x2, y2)) {
+            None => self.with(None),
+            Some(slope) => {
+                let x = slope.square() - self.curve.a2 - *x1 - *x2;
+                let y = slope * (*x1 - x) - *y1;
+                self.with(Some((x, y)))
+            }
+        }
+    }
+}
+
+impl<F: Field> PartialEq for PointOver<F> {
+    fn eq(&self, other: &Self) -> bool {
+        (Rc::ptr_eq(&self.curve, &other.curve) || self.curve == other.curve)
+            && self.coordinates == other.coordinates
+    }
+}
+
+impl<F: Field> std::ops::Add for &PointOver<F> {
+    type Output = Result<PointOver<F>, PointError>;
+    fn add(self, other: Self) -> Self::Output {
+        self.require_same_curve(other)?;
+        Ok(self.add_unchecked(other))
+    }
+}
+impl<F: Field> std::ops::Neg for &PointOver<F> {
+    type Output = PointOver<F>;
+    fn neg(self) -> Self::Output {
+        self.with(self.coordinates.map(|(x, y)| (x, -y)))
+    }
+}
+impl<F: Field> std::ops::Sub for &PointOver<F> {
+    type Output = Result<PointOver<F>, PointError>;
+    fn sub(self, other: Self) -> Self::Output {
+        self + &-other
+    }
+}
+impl<F: Field> std::ops::Mul<Ufeat> for &PointOver<F> {
+    type Output = PointOver<F>;
+    fn mul(self, k: Ufeat) -> Self::Output {
+        self.mul_vartime(&k)
+    }
+}
+
+impl<F: Field> fmt::Display for PointOver<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.coordinates {
+            None => write!(f, "< Point at infinity of {} >", self.curve.name),
+            Some((x, y)) => write!(
+                f,
+                "< Point of {} >\n\tx = {}\n\ty = {}",
+                self.curve.name,
+                coefficients(x),
+                coefficients(y)
+            ),
+        }
+    }
+}
+
+/// The coordinates of an element over the prime field in hex
+fn coefficients<F: Field>(element: &F) -> String {
+    let coefficients: Vec<_> = element.to_coefficients().iter().map(to_hex).collect();
+    format!("[{}]", coefficients.join(", "))
+}
@@ -0,0 +1,114 @@
+//! Anomalous curves lifted to $\mathbb{Z}/p^2$, the ground of Smart's attack
+//!
+//! A curve over $\mathbb{F}_p$ with exactly `p` points lifts to a curve over the p-adic integers on which `p` times any
+//! point reduces to infinity. There the elliptic logarithm $\psi = -x / y$ turns the group law into the addition of
+//! p-adic numbers divisible by `p`, and their first digit is all the attack needs, so working modulo $p^2$ is enough.
+use crypto_bigint::{
+    modular::runtime_mod::{DynResidue, DynResidueParams},
+    rand_core::OsRng,
+    Concat, NonZero, RandomMod,
+};
+
+use super::{EllipticCurve, Ufeat};
+
+/// Room for $p^2$
+type Wide = <Ufeat as Concat>::Output;
+type Residue = DynResidue<{ Wide::LIMBS }>;
+
+/// A lift of the curve with `a4` and `a6` shifted by random multiples of `p`
+///
+/// The attack fails on the canonical lift, the one whose endomorphisms lift as well; a random lift is that one with
+/// probability $1 / p^2$, and another draw fixes it.
+pub(crate) struct Lift {
+    p: Ufeat,
+    params: DynResidueParams<{ Wide::LIMBS }>,
+    a2: Residue,
+    a4: Residue,
+    a6: Residue,
+}
+
+impl Lift {
+    pub(crate) fn random(curve: &EllipticCurve) -> Self {
+        let p = curve.original_p();
+        let wide_p = p.resize::<{ Wide::LIMBS }>();
+        let params = DynResidueParams::new(&wide_p.wrapping_mul(&wide_p));
+        let modulus = NonZero::new(p).unwrap();
+        let lift = |coefficient: &DynResidue<{ Ufeat::LIMBS }>, shift: Ufeat| {
+            DynResidue::new(&coefficient.retrieve().resize(), params)
+                + DynResidue::new(
+                    &shift.resize::<{ Wide::LIMBS }>().wrapping_mul(&wide_p),
+                    params,
+                )
+        };
+        let random = || Ufeat::random_mod(&mut OsRng, &modulus);
+        Lift {
+            p,
+            params,
+            a2: lift(curve.a2(), Ufeat::ZERO),
+            a4: lift(curve.a4(), random()),
+            a6: lift(curve.a6(), random()),
+        }
+    }
+
+    fn residue(&self, n: &Ufeat) -> Residue {
+        DynResidue::new(&n.resize(), self.params)
+    }
+
+    fn rhs(&self, x: &Residue) -> Residue {
+        (x.square() + self.a2 * x + self.a4) * x + self.a6
+    }
+
+    /// The point with the same `x` and $y + p.t$, where Hensel's lemma gives $t = (rhs(x) - y^2) / p / 2y \mod p$
+    fn lift_point(&self, x: &Ufeat, y: &Ufeat) -> (Residue, Residue) {
+        let (x, y) = (self.residue(x), self.residue(y));
+        // `y` is a root modulo `p`, so the error is a multiple of `p` and `p.t` is the error over 2y modulo p^2
+        let error = self.rhs(&x) - y.square();
+        (x, y + error * (y + y).invert().0)
+    }
+
+    /// The chord and tangent rule; the caller keeps the points away from infinity modulo `p`, where the denominators
+    /// stop being units
+    fn add(
+        &self,
+        (x1, y1): &(Residue, Residue),
+        (x2, y2): &(Residue, Residue),
+    ) -> (Residue, Residue) {
+        let slope = if x1 == x2 && y1 == y2 {
+            let three = DynResidue::new(&Wide::from(3u8), self.params);
+            (three * x1.square() + (self.a2 + self.a2) * x1 + self.a4) * (*y1 + y1).invert().0
+        } else {
+            (*y2 - y1) * (*x2 - x1).invert().0
+        };
+        let x = slope.square() - self.a2 - x1 - x2;
+        (x, slope * (*x1 - x) - y1)
+    }
+
+    /// $\psi(p.P) / p \mod p$ for the lift of the point $P = (x, y)$ of order `p`
+    ///
+    /// `(p - 1).P` is computed left to right, so no intermediate multiple is `±P` modulo `p`. Adding `P` to it leaves
+    /// the sum in the kernel of reduction, where the slope $\lambda$ has valuation $-1$, and $\psi = 1 / \lambda$ up to
+    /// the terms divisible by $p^3$.
+    pub(crate) fn logarithm(&self, x: &Ufeat, y: &Ufeat) -> Ufeat {
+        let point = self.lift_point(x, y);
+        let p_minus_one = self.p.wrapping_sub(&Ufeat::ONE);
+        let mut multiple = point;
+        for bit in (0..p_minus_one.bits() - 1).rev() {
+            multiple = self.add(&multiple, &multiple);
+            if p_minus_one.bit_vartime(bit) {
+                multiple = self.add(&multiple, &point);
+            }
+        }
+        // 1 / lambda = (x_P - x_A) / (y_P - y_A) with the numerator divisible by `p`
+        let numerator = (point.0 - multiple.0)
+            .retrieve()
+            .wrapping_div(&self.p.resize())
+            .resize::<{ Ufeat::LIMBS }>();
+        let denominator = (point.1 - multiple.1)
+            .retrieve()
+            .wrapping_rem(&self.p.resize())
+            .resize::<{ Ufeat::LIMBS }>();
+        let params = DynResidueParams::new(&self.p);
+        (DynResidue::new(&numerator, params) * DynResidue::new(&denominator, params).invert().0)
+            .retrieve()
+    }
+}
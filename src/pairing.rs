@@ -0,0 +1,219 @@
+//! Tate and Weil pairings with Miller's algorithm, and the embedding degree telling which field they land in
+//!
+//! For `r` dividing the order of a curve over $F_p$ with embedding degree `k`, the pairings map the `r`-torsion to the
+//! `r`-th roots of unity of $F_{p^k}$, bilinearly. That turns a logarithm on the curve into one in the field (the MOV
+//! reduction), and it's the ground of the pairing based protocols. The points come as `PointOver` a field containing
+//! the roots, see `EllipticCurve::over`; nothing here is constant time.
+use super::{
+    extension::{Exponent, Field},
+    EllipticCurve, Error, FieldError, PointError, PointOver, Ufeat, EMBEDDING_DEGREE_BOUND,
+};
+
+impl EllipticCurve {
+    /// The smallest `k` with $p^k = 1 \mod order$, the degree of the extension holding the pairings of the points of
+    /// order `order`; `None` if it exceeds `EMBEDDING_DEGREE_BOUND`
+    pub fn embedding_degree(&self) -> Option<u64> {
+        let order = self.order();
+        let p = self.original_p().wrapping_rem(order);
+        let mut power = p;
+        for k in 1..=EMBEDDING_DEGREE_BOUND {
+            if power == Ufeat::ONE {
+                return Some(k);
+            }
+            power = Ufeat::const_rem_wide(power.mul_wide(&p), order).0;
+        }
+        None
+    }
+}
+
+/// $f_{n,P}(Q)$ for the function with the divisor $n(P) - (n.P) - (n - 1)(\infty)$, normalized at infinity
+///
+/// Each step multiplies by the line through the current multiple `T` and `T` or `P`, over the vertical line through
+/// their sum; the numerators and the denominators are kept apart to invert once. Fails when a line vanishes at `Q`,
+/// which happens when `Q` is infinity or one of the multiples of `P` up to sign.
+pub fn miller<F: Field>(p: &PointOver<F>, q: &PointOver<F>, n: &Ufeat) -> Result<F, PointError> {
+    p.require_same_curve(q)?;
+    let one = p.curve().a2().one_like();
+    if p.is_infinity() || *n == Ufeat::ZERO {
+        return Ok(one);
+    }
+    let degenerate = || PointError::DegeneratePairing {
+        curve: q.curve().name.clone(),
+    };
+    let (xq, yq) = q.x().zip(q.y()).ok_or_else(degenerate)?;
+    let (mut numerator, mut denominator) = (one, one);
+    // the line through `t` and `s` over the vertical through their sum, both evaluated at `Q`
+    let line = |t: &PointOver<F>, s: &PointOver<F>| {
+        let (x1, y1) = t.x().zip(t.y()).unwrap();
+        let (x2, y2) = s.x().zip(s.y()).unwrap();
+        match t.curve().slope((x1, y1), (x2, y2)) {
+            None => (*xq - *x1, one),
+            Some(slope) => {
+                let x3 = slope.square() - *t.curve().a2() - *x1 - *x2;
+                (*yq - *y1 - slope * (*xq - *x1), *xq - x3)
+            }
+        }
+    };
+    let mut t = p.clone();
+    for i in (0..n.bits_vartime() - 1).rev() {
+        // the multiples stay finite until the last step, where `t` is `(n - 1).P` at most
+        if t.is_infinity() {
+            return Err(degenerate());
+        }
+        let (l, v) = line(&t, &t);
+        numerator = numerator.square() * l;
+        denominator = denominator.square() * v;
+        t = (&t + &t)?;
+        if n.bit_vartime(i) {
+            if t.is_infinity() {
+                return Err(degenerate());
+            }
+            let (l, v) = line(&t, p);
+            numerator = numerator * l;
+            denominator = denominator * v;
+            t = (&t + p)?;
+        }
+    }
+    match (numerator.is_zero(), denominator.inverse()) {
+        (false, Some(inverse)) => Ok(numerator * inverse),
+        _ => Err(degenerate()),
+    }
+}
+
+/// `NotAnnihilated` unless $order.P = \infty$
+fn require_torsion<F: Field>(point: &PointOver<F>, order: &Ufeat) -> Result<(), PointError> {
+    if (point * *order).is_infinity() {
+        Ok(())
+    } else {
+        Err(PointError::NotAnnihilated {
+            curve: point.curve().name.clone(),
+            multiple: *order,
+        })
+    }
+}
+
+/// The reduced Tate pairing $f_{r,P}(Q)^{(q - 1) / r}$ of `p` of order dividing `order` and any `q`, an `order`-th root
+/// of unity of the field $F_q$ of the points
+///
+/// The final exponentiation makes the value depend on `Q` only modulo `order` times the group. It's trivial when `P`
+/// and `Q` are both over the prime field and the embedding degree is above 1, and Miller's loop can't evaluate it at
+/// the multiples of `P`.
+pub fn tate<F: Field>(p: &PointOver<F>, q: &PointOver<F>, order: &Ufeat) -> Result<F, Error> {
+    require_torsion(p, order)?;
+    let one = p.curve().a2().one_like();
+    let size_minus_one = one.size().wrapping_sub(&Exponent::ONE);
+    let order_wide = order.resize::<{ Exponent::LIMBS }>();
+    if size_minus_one.wrapping_rem(&order_wide) != Exponent::ZERO {
+        return Err(FieldError::NoRootsOfUnity {
+            order: *order,
+            degree: F::DEGREE,
+        }
+        .into());
+    }
+    if q.is_infinity() {
+        return Ok(one);
+    }
+    Ok(miller(p, q, order)?.pow(&size_minus_one.wrapping_div(&order_wide)))
+}
+
+/// The Weil pairing $(-1)^r f_{r,P}(Q) / f_{r,Q}(P)$ of points of order dividing `order` (Miller's normalization)
+///
+/// It's alternating: $e(P, P) = 1$, which Miller's loop can't evaluate though, so the points must be independent. The
+/// field must hold the whole `order`-torsion, which for a prime `order` takes the embedding degree.
+pub fn weil<F: Field>(p: &PointOver<F>, q: &PointOver<F>, order: &Ufeat) -> Result<F, PointError> {
+    require_torsion(p, order)?;
+    require_torsion(q, order)?;
+    if p.is_infinity() || q.is_infinity() {
+        return Ok(p.curve().a2().one_like());
+    }
+    // both values are nonzero as Miller's loop succeeded
+    let ratio = miller(p, q, order)? * miller(q, p, order)?.inverse().unwrap();
+    Ok(if order.bit_vartime(0) { -ratio } else { ratio })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use crypto_bigint::modular::runtime_mod::{DynResidue, DynResidueParams};
+
+    use super::*;
+    use crate::extension::Fp2;
+
+    /// The supersingular $y^2 = x^3 + x$ over $F_{10007}$ of embedding degree 2, a point `P` of order 139 over $F_p$
+    /// and its image `Q` under the distortion map $(x, y) \to (-x, i.y)$
+    fn distorted() -> (PointOver<Fp2>, PointOver<Fp2>) {
+        let p = Ufeat::from(10007u16);
+        let curve = Rc::new(EllipticCurve::new(
+            "y^2 = x^3 + x".to_string(),
+            Ufeat::from(139u8),
+            p,
+            [0, 1, 0],
+        ));
+        assert_eq!(curve.embedding_degree(), Some(2));
+        let base = curve
+            .points()
+            .map(|point| (&point * Ufeat::from(72u8)).unwrap())
+            .find(|point| !point.is_infinity())
+            .unwrap();
+        let one = Fp2::one(-DynResidue::one(DynResidueParams::new(&p))).unwrap();
+        let over = Rc::new(curve.over(&one).unwrap());
+        let p_point = base.over(&over).unwrap();
+        let (x, y) = (*p_point.x().unwrap(), *p_point.y().unwrap());
+        let i = one.from_coefficients(DynResidue::zero(*one.c0().params()), *one.c0());
+        let q_point = over.point(-x, i * y).unwrap();
+        (p_point, q_point)
+    }
+
+    #[test]
+    fn tate_and_weil_are_bilinear() {
+        let (p_point, q_point) = distorted();
+        let r = Ufeat::from(139u8);
+        let one = p_point.curve().a2().one_like();
+        for pairing in [
+            |p: &_, q: &_, r: &_| tate(p, q, r).unwrap(),
+            |p: &_, q: &_, r: &_| weil(p, q, r).unwrap(),
+        ] {
+            let e = pairing(&p_point, &q_point, &r);
+            assert_ne!(e, one);
+            assert_eq!(e.pow(&r), one);
+            for (a, b) in [(1u8, 2u8), (2, 1), (5, 7), (138, 3), (100, 138)] {
+                let (a, b) = (Ufeat::from(a), Ufeat::from(b));
+                assert_eq!(
+                    pairing(&(&p_point * a), &(&q_point * b), &r),
+                    e.pow(&a.wrapping_mul(&b))
+                );
+            }
+            // linear in each argument on its own: e(P1 + P2, Q) = e(P1, Q).e(P2, Q)
+            let (p1, p2) = (&p_point * Ufeat::from(3u8), &p_point * Ufeat::from(11u8));
+            assert_eq!(
+                pairing(&(&p1 + &p2).unwrap(), &q_point, &r),
+                pairing(&p1, &q_point, &r) * pairing(&p2, &q_point, &r)
+            );
+        }
+        // Weil is alternating
+        assert_eq!(
+            weil(&p_point, &q_point, &r).unwrap() * weil(&q_point, &p_point, &r).unwrap(),
+            one
+        );
+    }
+
+    #[test]
+    fn miller_refuses_points_of_different_curves() {
+        let (p_point, _) = distorted();
+        let p = Ufeat::from(10007u16);
+        let other = Rc::new(EllipticCurve::new(
+            "y^2 = x^3 + 2x".to_string(),
+            Ufeat::from(3u8),
+            p,
+            [0, 2, 0],
+        ));
+        let one = Fp2::one(-DynResidue::one(DynResidueParams::new(&p))).unwrap();
+        let point = other.points().nth(1).unwrap();
+        let point = point.over(&Rc::new(other.over(&one).unwrap())).unwrap();
+        assert!(matches!(
+            miller(&p_point, &point, &Ufeat::from(139u8)),
+            Err(PointError::DifferentCurves { .. })
+        ));
+    }
+}
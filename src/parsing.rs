@@ -0,0 +1,214 @@
+//! Reading curves and points back from what their `Display` prints, so that the output of the source tool can be
+//! compared with the values directly
+//!
+//! The lines are `key: value` pairs, the header, the separator and the equation are skipped. Numbers are hex, with or
+//! without `0x` and leading zeros. A coefficient which fits `isize` is a small one as `EllipticCurve::new` takes it,
+//! negative only with a leading minus as `Display` prints it (`-0x3`); a bigger one is a residue. `Display` doesn't
+//! show the base point, so a parsed curve has none; it adds `anomalous: true` for the curves of
+//! `EllipticCurve::try_new_anomalous`, which are refused without that line.
+use std::{collections::HashMap, rc::Rc, str::FromStr};
+
+use super::{curves, ec::Coefficient, encoding::parse_hex, EllipticCurve, EncodingError, Point};
+
+const POINT_HEADER: &str = "< Point object of Elliptic curve ";
+const INFINITE_POINT: &str = "Infinite Point";
+
+/// The `key: value` lines of the text, split at the first colon; the values are left as they are but for the space
+/// after it, since a name may have spaces and colons of its own
+fn fields(text: &str) -> HashMap<&str, &str> {
+    text.lines()
+        .filter_map(|line| line.split_once(':'))
+        .map(|(key, value)| (key.trim(), value.strip_prefix(' ').unwrap_or(value)))
+        .collect()
+}
+
+fn field<'a>(fields: &HashMap<&str, &'a str>, key: &str) -> Result<&'a str, EncodingError> {
+    fields
+        .get(key)
+        .copied()
+        .ok_or_else(|| EncodingError::Malformed {
+            reason: format!("no `{key}` line"),
+        })
+}
+
+/// A small coefficient when it fits `isize`, a residue otherwise; only a leading minus makes it negative
+fn coefficient(text: &str) -> Result<Coefficient, EncodingError> {
+    let text = text.trim();
+    let (negative, magnitude) = match text.strip_prefix('-') {
+        Some(magnitude) => (true, magnitude),
+        None => (false, text),
+    };
+    let invalid = || EncodingError::InvalidHex {
+        input: text.to_string(),
+    };
+    let value = parse_hex(magnitude).map_err(|_| invalid())?;
+    if value.bits() < isize::BITS as usize {
+        let value = value.as_words()[0] as isize;
+        Ok(Coefficient::Small(if negative { -value } else { value }))
+    } else if negative {
+        // a residue is never negative, it's the small coefficients which wrap around
+        Err(invalid())
+    } else {
+        Ok(Coefficient::Big(value))
+    }
+}
+
+/// Validates the parameters as `EllipticCurve::try_new` does, or as `try_new_anomalous` when the text says `anomalous: true`
+impl FromStr for EllipticCurve {
+    type Err = EncodingError;
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let fields = fields(text);
+        let anomalous = match fields.get("anomalous").map(|value| value.trim()) {
+            None | Some("false") => false,
+            Some("true") => true,
+            Some(value) => {
+                return Err(EncodingError::Malformed {
+                    reason: format!("`anomalous` is `{value}` rather than `true` or `false`"),
+                })
+            }
+        };
+        Ok(EllipticCurve::checked(
+            field(&fields, "name")?.to_string(),
+            parse_hex(field(&fields, "order")?.trim())?,
+            parse_hex(field(&fields, "p")?.trim())?,
+            [
+                coefficient(field(&fields, "a2")?)?,
+                coefficient(field(&fields, "a4")?)?,
+                coefficient(field(&fields, "a6")?)?,
+            ],
+            anomalous,
+        )?)
+    }
+}
+
+impl Point {
+    /// Reads the point onto `curve`, which must have the name the text mentions; validates it as `Point::new` does
+    pub fn parse_on(curve: &Rc<EllipticCurve>, text: &str) -> Result<Point, EncodingError> {
+        let name = curve_name(text)?;
+        if name != curve.name {
+            return Err(EncodingError::Malformed {
+                reason: format!("the point is on {name} rather than {}", curve.name),
+            });
+        }
+        if text.lines().any(|line| line.trim() == INFINITE_POINT) {
+            return Ok(Point::new(Rc::clone(curve), None, None)?);
+        }
+        let fields = fields(text);
+        Ok(Point::new(
+            Rc::clone(curve),
+            Some(parse_hex(field(&fields, "x")?.trim())?),
+            Some(parse_hex(field(&fields, "y")?.trim())?),
+        )?)
+    }
+}
+
+fn curve_name(text: &str) -> Result<&str, EncodingError> {
+    text.lines()
+        .find_map(|line| line.trim().strip_prefix(POINT_HEADER)?.strip_suffix(" >"))
+        .ok_or_else(|| EncodingError::Malformed {
+            reason: "no point header".to_string(),
+        })
+}
+
+/// Looks the curve up in `curves` by the name the text mentions; `Point::parse_on` reads the points of other curves
+impl FromStr for Point {
+    type Err = EncodingError;
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let name = curve_name(text)?;
+        let curve = curves::by_name(name).ok_or_else(|| EncodingError::UnknownCurve {
+            name: name.to_string(),
+        })?;
+        Point::parse_on(&Rc::new(curve), text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Ufeat;
+
+    fn curve(name: &str) -> EllipticCurve {
+        EllipticCurve::new(
+            name.to_string(),
+            Ufeat::from(5003u16),
+            Ufeat::from(10007u16),
+            [0, -3, 3],
+        )
+    }
+
+    #[test]
+    fn names_are_read_back_verbatim() {
+        for name in [
+            "E",
+            "curve: the second",
+            " padded ",
+            "ends with a colon:",
+            "",
+        ] {
+            let curve = Rc::new(curve(name));
+            let parsed: EllipticCurve = curve.to_string().parse().unwrap();
+            assert_eq!(parsed.name, name);
+            let point = curve.points().nth(7).unwrap();
+            assert!(Point::parse_on(&curve, &point.to_string()).unwrap() == point);
+        }
+    }
+
+    #[test]
+    fn anomalous_curves_are_read_back() {
+        let anomalous = EllipticCurve::try_new_anomalous(
+            "anomalous".to_string(),
+            Ufeat::from(10007u16),
+            Ufeat::from(10007u16),
+            [0, 22, 9],
+        )
+        .unwrap();
+        let text = anomalous.to_string();
+        let parsed: EllipticCurve = text.parse().unwrap();
+        assert!(parsed == anomalous && parsed.order() == anomalous.order());
+        let unflagged = text.replace("\n    anomalous: true", "");
+        assert!(unflagged.parse::<EllipticCurve>().is_err());
+        assert!(text
+            .replace("anomalous: true", "anomalous: yes")
+            .parse::<EllipticCurve>()
+            .is_err());
+        assert!(!curve("E").to_string().contains("anomalous"));
+    }
+
+    #[test]
+    fn coefficients_past_isize_are_residues() {
+        let big = |text| Ok(Coefficient::Big(parse_hex(text).unwrap()));
+        assert!(coefficient("0x7fffffffffffffff") == Ok(Coefficient::Small(isize::MAX)));
+        assert!(coefficient("-0x7fffffffffffffff") == Ok(Coefficient::Small(-isize::MAX)));
+        assert!(coefficient("0x8000000000000000") == big("0x8000000000000000"));
+        assert!(coefficient("0xfffffffffffffffd") == big("0xfffffffffffffffd"));
+        assert!(coefficient("0x0000000000000000003") == Ok(Coefficient::Small(3)));
+        assert!(coefficient("-0x8000000000000000").is_err());
+
+        // 2^127 - 1, so that the coefficients of 64 bits are far from wrapping around
+        let p = Ufeat::ONE.shl_vartime(127).wrapping_sub(&Ufeat::ONE);
+        let wide = EllipticCurve::from_uint_coefficients(
+            "wide".to_string(),
+            Ufeat::from(3u8),
+            p,
+            [
+                Ufeat::ZERO,
+                Ufeat::ONE.shl_vartime(63),
+                Ufeat::from(u64::MAX - 2),
+            ],
+        );
+        let small = EllipticCurve::new("small".to_string(), Ufeat::from(3u8), p, [0, -3, 7]);
+        for curve in [wide, small] {
+            let parsed: EllipticCurve = curve.to_string().parse().unwrap();
+            assert!(parsed == curve && parsed.get() == curve.get());
+            assert_eq!([parsed.a4(), parsed.a6()], [curve.a4(), curve.a6()]);
+        }
+    }
+
+    #[test]
+    fn coefficients_take_digits_only() {
+        assert!(coefficient("-0x3") == Ok(Coefficient::Small(-3)));
+        for text in ["0x+3", "-+3", "+3", "-", "0x", "3 3"] {
+            assert!(coefficient(text).is_err(), "{text}");
+        }
+    }
+}
@@ -1,8 +1,19 @@
-use super::{non_adjacent, EllipticCurve, Errs, Ufeat};
-use crypto_bigint::modular::runtime_mod::DynResidue;
-use std::{fmt, ops::Neg, rc::Rc};
+use super::{non_adjacent, primality, EllipticCurve, PointError, Ufeat};
+use crypto_bigint::{
+    modular::runtime_mod::DynResidue,
+    subtle::{Choice, ConditionallyNegatable, ConditionallySelectable, ConstantTimeEq},
+    CheckedMul,
+};
+use std::{
+    cmp::Ordering,
+    fmt,
+    hash::{Hash, Hasher},
+    ops::Neg,
+    rc::Rc,
+};
 
-const MSG_ASSIGNS_SHOULD_NOT_FAIL: &str = "use Assign traits only if you're sure it won't fail";
+const MSG_ASSIGNS_SHOULD_NOT_FAIL: &str =
+    "use Assign traits only if you're sure it won't fail, `checked_...` methods return the error instead";
 
 // #[derive(PartialEq, Eq, Clone, Copy)]
 // enum PointType {
@@ -36,7 +47,7 @@ impl Point /* <'_> */ {
         curve: Rc<EllipticCurve>,
         x: Option<Ufeat>,
         y: Option<Ufeat>,
-    ) -> Result<Point, Errs> {
+    ) -> Result<Point, PointError> {
         let (x_original, y_original) = (x.unwrap_or_default(), y.unwrap_or_default()); // dunb line to logically separate source migration and NCC-based addition
 
         // should notice that it's disputable design from a few angles; can't say I fond of the approach
@@ -51,13 +62,23 @@ impl Point /* <'_> */ {
         );
 
         // here's start of the added checks listed at https://research.nccgroup.com/2021/11/18/an-illustrated-guide-to-elliptic-curve-cryptography-validation
-        if x_original >= curve.original_p() || y_original >= curve.original_p() {
-            return Err(Errs::NccModulus);
+        if let Some(coordinate) = [x_original, y_original]
+            .into_iter()
+            .find(|coordinate| *coordinate >= curve.original_p())
+        {
+            return Err(PointError::CoordinateOutOfField {
+                curve: curve.name.clone(),
+                coordinate,
+            });
         }
         let curve_ = Rc::clone(&curve);
         let result = Point::Regular { x, y, curve };
         if !EllipticCurve::contains(curve_.as_ref(), &result) {
-            return Err(Errs::NccOutOfTheCurve);
+            return Err(PointError::NotOnCurve {
+                curve: curve_.name.clone(),
+                x: x_original,
+                y: y_original,
+            });
         }
         // points at infinity are allowed to create, just not as a silent default
         // this implementation won't check subgroups as defining acceptable criteria would drive the exercise way off
@@ -91,6 +112,147 @@ impl Point /* <'_> */ {
             Point::Regular { x, y, curve } => (curve, Some(x.retrieve()), Some(y.retrieve())),
         }
     }
+    pub fn is_infinity(&self) -> bool {
+        matches!(self, Point::AtInfinity { curve: _ })
+    }
+
+    /// Computes the exact order of the point given the factorization of the group order (or of any other multiple of the point order) as `(prime, exponent)` pairs
+    // the classic approach: start from the whole group order and divide out each prime while the point is still annihilated
+    pub fn order(&self, factorization: &[(Ufeat, u32)]) -> Result<Ufeat, PointError> {
+        let curve = || self.curve().name.clone();
+        // a zero would have `wrapping_div` panic below, and any other composite gives a multiple of the order at best
+        if let Some((factor, _)) = factorization
+            .iter()
+            .find(|(prime, _)| !primality::baillie_psw(prime))
+        {
+            return Err(PointError::CompositeFactor {
+                curve: curve(),
+                factor: *factor,
+            });
+        }
+        // every prime is at least 2, so an exponent past the bit length overflows anyway: no need to walk it to the end
+        let group_order = factorization
+            .iter()
+            .try_fold(Ufeat::ONE, |acc, (prime, exponent)| {
+                (0..*exponent).try_fold(acc, |acc, _| Option::<Ufeat>::from(acc.checked_mul(prime)))
+            })
+            .ok_or_else(|| PointError::FactorizationOverflow { curve: curve() })?;
+        if !(self * group_order)?.is_infinity() {
+            return Err(PointError::NotAnnihilated {
+                curve: self.curve().name.clone(),
+                multiple: group_order,
+            });
+        }
+
+        let mut order = group_order;
+        for (prime, exponent) in factorization {
+            for _ in 0..*exponent {
+                let candidate = order.wrapping_div(prime);
+                if (self * candidate)?.is_infinity() {
+                    order = candidate;
+                } else {
+                    break;
+                }
+            }
+        }
+        Ok(order)
+    }
+
+    /// `self + other`, refusing the points of different curves; the same as `+`, only not consuming the operands
+    pub fn checked_add(&self, other: &Point) -> Result<Point, PointError> {
+        self + other
+    }
+    /// `self - other`, refusing the points of different curves
+    pub fn checked_sub(&self, other: &Point) -> Result<Point, PointError> {
+        self - other
+    }
+    /// `k * self`; the counterpart of `checked_add` for `MulAssign`
+    pub fn checked_mul(&self, k: Ufeat) -> Result<Point, PointError> {
+        self * k
+    }
+    /// Whether the point is at infinity, as `subtle::Choice`
+    pub fn is_identity(&self) -> Choice {
+        // `matches!` compiles to a comparison of the discriminant rather than a jump
+        Choice::from(matches!(self, Point::AtInfinity { .. }) as u8)
+    }
+    /// `a` if `choice` is 0 and `b` if it's 1; see `CtPoint` for what is constant time here
+    ///
+    /// Not `subtle::ConditionallySelectable`: the trait requires `Copy`, which the point can't be while it holds its curve
+    /// in `Rc` (and implements `Drop` with `zeroize`), and its `conditional_select` has no way to refuse points of
+    /// different curves. The curves are compared as public data, the coordinates and the infinity flag are selected as
+    /// `CtPoint`, and only building the variant of the result branches on whether it's at infinity.
+    pub fn conditional_select(a: &Point, b: &Point, choice: Choice) -> Result<Point, PointError> {
+        a.require_same_curve(b)?;
+        Ok(Point::from_ct_unchecked(
+            CtPoint::conditional_select(&a.to_ct(), &b.to_ct(), choice),
+            a.curve_ref(),
+        ))
+    }
+    /// Replaces `self` with `other` if `choice` is 1; see `conditional_select`
+    pub fn conditional_assign(&mut self, other: &Point, choice: Choice) -> Result<(), PointError> {
+        *self = Point::conditional_select(self, other, choice)?;
+        Ok(())
+    }
+    /// The point in the shape where both variants look alike, for a secret-dependent choice among several of them
+    pub fn to_ct(&self) -> CtPoint {
+        let zero = DynResidue::zero(*self.curve_ref().p());
+        // the only branch on the variant, as close to constant time as the enum allows
+        let (x, y) = match self {
+            Point::Regular { x, y, .. } => (*x, *y),
+            Point::AtInfinity { .. } => (zero, zero),
+        };
+        CtPoint {
+            x,
+            y,
+            infinity: self.is_identity(),
+        }
+    }
+    /// Back from `CtPoint` to the point of `curve`, checked as `new` checks the coordinates
+    ///
+    /// The check and the branch on the infinity flag aren't constant time: convert when the choice is already made.
+    pub fn from_ct(point: CtPoint, curve: &Rc<EllipticCurve>) -> Result<Point, PointError> {
+        if point.infinity.into() {
+            return Point::new(Rc::clone(curve), None, None);
+        }
+        Point::new(
+            Rc::clone(curve),
+            Some(point.x.retrieve()),
+            Some(point.y.retrieve()),
+        )
+    }
+    fn from_ct_unchecked(point: CtPoint, curve: &Rc<EllipticCurve>) -> Point {
+        if point.infinity.into() {
+            Point::AtInfinity {
+                curve: Rc::clone(curve),
+            }
+        } else {
+            Point::Regular {
+                x: point.x,
+                y: point.y,
+                curve: Rc::clone(curve),
+            }
+        }
+    }
+    fn curve_ref(&self) -> &Rc<EllipticCurve> {
+        match self {
+            Point::AtInfinity { curve } | Point::Regular { curve, .. } => curve,
+        }
+    }
+    /// Cheap as `EllipticCurve` equality is pointer comparison or a fingerprint one
+    fn same_curve(&self, other: &Point) -> bool {
+        let (left, right) = (self.curve_ref(), other.curve_ref());
+        Rc::ptr_eq(left, right) || left == right
+    }
+    pub(crate) fn require_same_curve(&self, other: &Point) -> Result<(), PointError> {
+        if self.same_curve(other) {
+            Ok(())
+        } else {
+            Err(PointError::DifferentCurves {
+                left: self.curve_ref().name.clone(),
+                right: other.curve_ref().name.clone(),
+            })
+        }
+    }
 
     // #[inline]
     // fn helper_new_point_at_infinity(&self) -> Self {
@@ -139,41 +301,8 @@ impl PartialEq for Point {
     fn eq(&self, other: &Self) -> bool {
         // ~~TODO replace to `return false`, but for debugging it's better to panic~~
         // if self.curve != other.curve {panic!("comparison of points from different curves")}
-        if self.curve() != other.curve() {
-            return false;
-        }
-
-        match (self, other) {
-            (
-                Point::AtInfinity { curve: _ },
-                Point::Regular {
-                    curve: _,
-                    x: _,
-                    y: _,
-                },
-            ) => false,
-            (
-                Point::Regular {
-                    x: _,
-                    y: _,
-                    curve: _,
-                },
-                Point::AtInfinity { curve: _ },
-            ) => false,
-            (Point::AtInfinity { curve: _ }, Point::AtInfinity { curve: _ }) => true,
-            (
-                Point::Regular {
-                    x: self_x,
-                    y: self_y,
-                    curve: _,
-                },
-                Point::Regular {
-                    x: other_x,
-                    y: other_y,
-                    curve: _,
-                },
-            ) => (self_x == other_x) && (self_y == other_y),
-        }
+        // the variants and coordinates used to be matched with early returns, which tells the timing of the answer
+        self.ct_eq(other).into()
         // /* it would be cleaner if source would derive point type from presence of value in both of its coordinates */
         // if self.type_ != other.type_ {return false;}
         // if let (PointType::infinite, PointType::infinite) = (self.type_, other.type_) {
@@ -187,6 +316,121 @@ impl PartialEq for Point {
         // else {false}
     }
 }
+/// Goes over what `PartialEq` compares: the curve fingerprint and the coordinates
+impl Hash for Point {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.curve_ref().hash(state);
+        // the Montgomery form is as good as the coordinate itself for the residues of one curve, and cheaper
+        self.x().map(|x| x.as_montgomery().as_words()).hash(state);
+        self.y().map(|y| y.as_montgomery().as_words()).hash(state);
+    }
+}
+/// Canonical total order: by the curve fingerprint, then the point at infinity first, then by `x` and `y` as integers
+impl Ord for Point {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.curve_ref()
+            .id()
+            .cmp(other.curve_ref().id())
+            .then_with(|| {
+                let (_, self_x, self_y) = self.get();
+                let (_, other_x, other_y) = other.get();
+                // `None` comes before `Some`
+                (self_x, self_y).cmp(&(other_x, other_y))
+            })
+    }
+}
+impl PartialOrd for Point {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+/// Wipes the coordinates, leaving the point $(0, 0)$ which likely isn't on the curve anymore
+#[cfg(feature = "zeroize")]
+impl zeroize::Zeroize for Point {
+    fn zeroize(&mut self) {
+        if let Point::Regular { x, y, .. } = self {
+            x.zeroize();
+            y.zeroize();
+        }
+    }
+}
+#[cfg(feature = "zeroize")]
+impl Drop for Point {
+    fn drop(&mut self) {
+        zeroize::Zeroize::zeroize(self);
+    }
+}
+#[cfg(feature = "zeroize")]
+impl zeroize::ZeroizeOnDrop for Point {}
+/// Compares the coordinates and the variants in constant time; the curves are public and compared as usual
+impl ConstantTimeEq for Point {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        if !self.same_curve(other) {
+            return Choice::from(0);
+        }
+        self.to_ct().ct_eq(&other.to_ct())
+    }
+}
+
+/// The selection of `y` is constant time, the check for the variant isn't; unlike `ConditionallySelectable` this trait
+/// doesn't need `Copy`
+impl ConditionallyNegatable for Point {
+    fn conditional_negate(&mut self, choice: Choice) {
+        if let Point::Regular { y, .. } = self {
+            *y = DynResidue::conditional_select(y, &y.neg(), choice);
+        }
+    }
+}
+
+/// The point without its curve: fixed-size coordinates, zero at infinity, and the infinity flag as `subtle::Choice`
+///
+/// Unlike `Point` it's `Copy`, so it's `ConditionallySelectable` and `ConditionallyNegatable`, and choosing among
+/// such doesn't branch at all. It's up to the caller to keep the curve at hand and to convert back with
+/// `Point::from_ct`.
+#[derive(Clone, Copy, Debug)]
+pub struct CtPoint {
+    x: DynResidue<{ Ufeat::LIMBS }>,
+    y: DynResidue<{ Ufeat::LIMBS }>,
+    infinity: Choice,
+}
+impl CtPoint {
+    /// Whether the point is at infinity
+    pub fn is_identity(&self) -> Choice {
+        self.infinity
+    }
+}
+impl ConditionallySelectable for CtPoint {
+    fn conditional_select(a: &Self, b: &Self, choice: Choice) -> Self {
+        CtPoint {
+            x: DynResidue::conditional_select(&a.x, &b.x, choice),
+            y: DynResidue::conditional_select(&a.y, &b.y, choice),
+            infinity: Choice::conditional_select(&a.infinity, &b.infinity, choice),
+        }
+    }
+}
+impl ConstantTimeEq for CtPoint {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.x.ct_eq(&other.x) & self.y.ct_eq(&other.y) & self.infinity.ct_eq(&other.infinity)
+    }
+}
+/// The point at infinity keeps its zero `y`, so negation needs no branch either
+impl Neg for &CtPoint {
+    type Output = CtPoint;
+    fn neg(self) -> Self::Output {
+        CtPoint {
+            y: self.y.neg(),
+            ..*self
+        }
+    }
+}
+#[cfg(feature = "zeroize")]
+impl zeroize::Zeroize for CtPoint {
+    fn zeroize(&mut self) {
+        self.x.zeroize();
+        self.y.zeroize();
+        self.infinity = Choice::from(0);
+    }
+}
 ///         Gives the symmetric point of the object
 impl std::ops::Neg for &Point /* <'_> */ {
     type Output = Point;
@@ -225,8 +469,10 @@ impl std::ops::Neg for &Point /* <'_> */ {
 // }
 ///        Overload of the + operator for two `&Point` objects (+ is commutative)
 impl std::ops::Add for &Point /* <'_> */ {
-    type Output = Result<Point, Errs>;
+    type Output = Result<Point, PointError>;
     fn add(self, point: Self) -> Self::Output {
+        // otherwise the sum would be computed with `self`'s coefficients whatever curve `point` is on
+        self.require_same_curve(point)?;
         match point {
             Point::AtInfinity { curve: _ } => Ok(self.clone()),
             Point::Regular {
@@ -295,7 +541,9 @@ impl std::ops::Add for &Point /* <'_> */ {
                         // ~~TODO is there a more graceful way? Given that trait isn't suitable for `Result`.~~
                         //      turns out that `Add` actually *can* return `Result`!
                         else {
-                            Err(Errs::ValueError)
+                            Err(PointError::ResultOffCurve {
+                                curve: curve.name.clone(),
+                            })
                         }
                     }
                 }
@@ -305,7 +553,7 @@ impl std::ops::Add for &Point /* <'_> */ {
 }
 /// Overload of the + operator for two Point objects (+ is commutative)
 impl std::ops::Add for Point /* <'_> */ {
-    type Output = Result<Self, Errs>;
+    type Output = Result<Self, PointError>;
     fn add(self, rhs: Self) -> Self::Output {
         &self + &rhs
     }
@@ -318,29 +566,30 @@ impl std::ops::Add for Point /* <'_> */ {
 //     }
 // }
 // impl std::ops::Add<&Point> for &mut Point {
-//     type Output = Result<Point, Errs>;
+//     type Output = Result<Point, PointError>;
 //     fn add(self, rhs: &Point) -> Self::Output {
 //         Ok((*self + *rhs)?)
 //     }
 // }
 ///         Overload of the += operator for two Point objects
+// panics where `checked_add` returns an error
 impl std::ops::AddAssign for Point {
     fn add_assign(&mut self, rhs: Self) {
         // let tmp = self;
         // *self = tmp.add(rhs);
-        *self = (self.clone() + rhs).expect(MSG_ASSIGNS_SHOULD_NOT_FAIL);
+        *self = self.checked_add(&rhs).expect(MSG_ASSIGNS_SHOULD_NOT_FAIL);
     }
 }
 ///         Overload of the - operator for two `&Point` objects
 impl std::ops::Sub for &Point /* <'_> */ {
-    type Output = Result<Point, Errs>;
+    type Output = Result<Point, PointError>;
     fn sub(self, rhs: Self) -> Self::Output {
         self + &-rhs
     }
 }
 ///         Overload of the - operator for two Point objects
 impl std::ops::Sub for Point /* <'_> */ {
-    type Output = Result<Self, Errs>;
+    type Output = Result<Self, PointError>;
     fn sub(self, rhs: Self) -> Self::Output {
         &self - &rhs
         // let tmp = &self;
@@ -348,16 +597,17 @@ impl std::ops::Sub for Point /* <'_> */ {
     }
 }
 ///        Overload of the - operator for two Point objects
+// panics where `checked_sub` returns an error
 impl std::ops::SubAssign for Point {
     fn sub_assign(&mut self, rhs: Self) {
         // let tmp = self;
         // *self = tmp.add(rhs);
-        *self = (self.clone() - rhs).expect(MSG_ASSIGNS_SHOULD_NOT_FAIL);
+        *self = self.checked_sub(&rhs).expect(MSG_ASSIGNS_SHOULD_NOT_FAIL);
     }
 }
 /// Overload of the * operator for a Point and an integer
 impl std::ops::Mul<Ufeat> for &Point /* <'_> */ {
-    type Output = Result<Point, Errs>;
+    type Output = Result<Point, PointError>;
     fn mul(self, rhs: Ufeat) -> Self::Output {
         // println!("DEBUG: is `self` a correct point? {}", self.curve().contains(self));
         // println!("DEBUG: is double `self` a correct point? {}", (self + self).is_ok()); //.curve().contains(self));
@@ -373,22 +623,21 @@ impl std::ops::Mul<Ufeat> for &Point /* <'_> */ {
         // let mut self_ = self + &self.helper_new_point_at_infinity();
         Ok(non_adj_repr
             .iter()
-            .fold(
+            .try_fold(
                 // Ok((Point{curve: Rc::clone(&self.curve), ..Default::default()}, self.clone())),
                 // TODO return here to understand `Rc` dereferencing
                 // Ok((Point::new(self.curve.clone(), None, Default::default())?, self.clone())),
-                Ok((
+                (
                     Point::AtInfinity {
                         curve: self.curve(),
                     },
                     self.clone(),
-                )),
-                |b_tuple_result_runner, ternary_sign| {
-                    let (result, runner) = b_tuple_result_runner?;
+                ),
+                |(result, runner), ternary_sign| {
                     // println!("DEBUG: res is {result}");
                     // println!("DEBUG: runner is {runner}");
                     // println!("DEBUG: sign is {ternary_sign}");
-                    Ok((
+                    Ok::<_, PointError>((
                         match ternary_sign {
                             1 => (&result + &runner)?,
                             -1 => (&result - &runner)?,
@@ -414,30 +663,257 @@ impl std::ops::Mul<Ufeat> for &Point /* <'_> */ {
     }
 }
 impl std::ops::Mul<Ufeat> for Point {
-    type Output = Result<Point, Errs>;
+    type Output = Result<Point, PointError>;
     fn mul(self, rhs: Ufeat) -> Self::Output {
         &self * rhs
     }
 }
 ///   Overload of the * operator for a Point and an integer (* is commutative)
 impl std::ops::Mul<&Point> for Ufeat {
-    type Output = Result<Point, Errs>;
+    type Output = Result<Point, PointError>;
     fn mul(self, rhs: &Point) -> Self::Output {
         rhs * self
     }
 }
 // impl std::ops::Mul<Ufeat> for &mut Point {
-//     type Output = Result<Point, Errs>;
+//     type Output = Result<Point, PointError>;
 //     fn mul(self, rhs: Ufeat) -> Self::Output {
 //         Ok((*self * rhs)?)
 //     }
 // }
 ///         Overload of the *= operator for a Point and an integer
+// panics where `checked_mul` returns an error
 // ~~TODO is it possible not to `clone` in ...Assign traits `impl`s?~~
 //      it seems to me that with plain borrowing the answer is more or less "no": `AddAssign` `impl` would need to clone to be able to move the value out of exclusive borrow
 //      I guess it could be possible with more complex and smart pointers, but as soon as `Point` consists of lightweight `Rc` and `Copy`-types -- there will be no benefit from taking this path
 impl std::ops::MulAssign<Ufeat> for Point {
     fn mul_assign(&mut self, rhs: Ufeat) {
-        *self = (self.clone() * rhs).expect(MSG_ASSIGNS_SHOULD_NOT_FAIL);
+        *self = self.checked_mul(rhs).expect(MSG_ASSIGNS_SHOULD_NOT_FAIL);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::{hash_map::DefaultHasher, BTreeSet, HashSet},
+        rc::Rc,
+    };
+
+    use crypto_bigint::{rand_core::OsRng, NonZero, RandomMod};
+
+    use super::*;
+    use crate::curves;
+
+    fn hash(point: &Point) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        point.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// Equality, ordering and hashes agree for the pair, and the ordering is antisymmetric
+    fn assert_consistent(a: &Point, b: &Point) {
+        let equal = a == b;
+        assert_eq!(equal, a.cmp(b) == Ordering::Equal);
+        assert!(!equal || hash(a) == hash(b));
+        assert_eq!(a.cmp(b), b.cmp(a).reverse());
+    }
+
+    fn small_curve(name: &str, order: u16, coefficients: [isize; 3]) -> Rc<EllipticCurve> {
+        Rc::new(EllipticCurve::new(
+            name.to_string(),
+            Ufeat::from(order),
+            Ufeat::from(10007u16),
+            coefficients,
+        ))
+    }
+
+    #[test]
+    fn hash_and_ord_agree_with_eq_on_every_point() {
+        // the same curve behind two `Rc`s, so that the points are equal without sharing the pointer
+        let (curve, twin) = (
+            small_curve("E", 5003, [0, 2, 3]),
+            small_curve("E", 5003, [0, 2, 3]),
+        );
+        let points = curve.points().collect::<Vec<_>>();
+        let twins = twin.points().collect::<Vec<_>>();
+
+        let mut sorted = points.clone();
+        sorted.sort();
+        assert!(sorted.windows(2).all(|pair| pair[0] < pair[1]));
+        assert!(sorted[0].is_infinity());
+        for (point, twin) in points.iter().zip(&twins) {
+            assert_consistent(point, twin);
+            assert!(point == twin);
+        }
+        for window in points.windows(3) {
+            assert_consistent(&window[0], &window[1]);
+            assert_consistent(&window[0], &window[2]);
+            if window[0] < window[1] && window[1] < window[2] {
+                assert!(window[0] < window[2]);
+            }
+        }
+        let all = || points.iter().chain(&twins).cloned();
+        assert_eq!(all().collect::<HashSet<_>>().len(), points.len());
+        assert_eq!(all().collect::<BTreeSet<_>>().len(), points.len());
+    }
+
+    #[test]
+    fn points_of_different_curves_differ() {
+        let infinity = |curve| Point::new(curve, None, None).unwrap();
+        let (a, b) = (
+            infinity(small_curve("E", 5003, [0, 2, 3])),
+            infinity(small_curve("F", 5039, [0, 1, 1])),
+        );
+        assert_consistent(&a, &b);
+        assert!(a != b);
+    }
+
+    #[test]
+    fn order_is_the_least_annihilating_divisor() {
+        let curve = small_curve("E", 5003, [0, 2, 3]);
+        let count = curve.points().count() as u64;
+        let factorization = primality::factor(Ufeat::from(count));
+        let divisors: Vec<_> = (1..=count).filter(|d| count.is_multiple_of(*d)).collect();
+        for point in curve.points().step_by(997) {
+            let order = divisors
+                .iter()
+                .find(|d| (&point * Ufeat::from(**d)).unwrap().is_infinity())
+                .unwrap();
+            assert_eq!(point.order(&factorization), Ok(Ufeat::from(*order)));
+        }
+
+        let point = curve.points().nth(1).unwrap();
+        let name = || curve.name.clone();
+        for factor in [0u8, 1, 4] {
+            assert_eq!(
+                point.order(&[(Ufeat::from(factor), 1), (Ufeat::from(count), 1)]),
+                Err(PointError::CompositeFactor {
+                    curve: name(),
+                    factor: Ufeat::from(factor)
+                })
+            );
+        }
+        assert_eq!(
+            point.order(&[(Ufeat::from(2u8), u32::MAX)]),
+            Err(PointError::FactorizationOverflow { curve: name() })
+        );
+        assert_eq!(
+            point.order(&[(Ufeat::from(3u8), 1)]),
+            Err(PointError::NotAnnihilated {
+                curve: name(),
+                multiple: Ufeat::from(3u8)
+            })
+        );
+    }
+
+    #[test]
+    fn selects_negates_and_spots_the_identity_in_constant_time() {
+        let curve = small_curve("E", 5003, [0, 2, 3]);
+        let infinity = Point::new(Rc::clone(&curve), None, None).unwrap();
+        let (a, b) = (
+            curve.points().nth(1).unwrap(),
+            curve.points().nth(3).unwrap(),
+        );
+        assert!(bool::from(infinity.is_identity()));
+        assert!(!bool::from(a.is_identity()));
+        assert!(bool::from(a.ct_eq(&a.clone())) && !bool::from(a.ct_eq(&b)));
+        assert!(!bool::from(a.ct_eq(&infinity)));
+
+        for (left, right) in [(&a, &b), (&a, &infinity), (&infinity, &b)] {
+            let select =
+                |choice| Point::conditional_select(left, right, Choice::from(choice)).unwrap();
+            assert!(select(0) == *left && select(1) == *right);
+            let mut assigned = left.clone();
+            assigned.conditional_assign(right, Choice::from(1)).unwrap();
+            assert!(assigned == *right);
+        }
+        let other = small_curve("F", 5039, [0, 1, 1]).points().nth(1).unwrap();
+        assert!(Point::conditional_select(&a, &other, Choice::from(0)).is_err());
+        assert!(!bool::from(a.ct_eq(&other)));
+
+        for point in [&a, &infinity] {
+            let mut negated = point.clone();
+            negated.conditional_negate(Choice::from(0));
+            assert!(negated == *point);
+            negated.conditional_negate(Choice::from(1));
+            assert!(negated == -point);
+        }
+        let ct = a.to_ct();
+        let mut negated = ct;
+        negated.conditional_negate(Choice::from(1));
+        assert!(bool::from(negated.ct_eq(&(-&a).to_ct())));
+        assert!(bool::from(
+            CtPoint::conditional_select(&ct, &negated, Choice::from(0)).ct_eq(&ct)
+        ));
+        assert!(Point::from_ct(negated, &curve).unwrap() == -&a);
+    }
+
+    #[test]
+    fn hash_and_ord_agree_with_eq_on_random_points() {
+        let curve = Rc::new(curves::secp256k1());
+        let g = curve.generator().unwrap();
+        let order = NonZero::new(*curve.order()).unwrap();
+        for _ in 0..8 {
+            let (k, l) = (
+                Ufeat::random_mod(&mut OsRng, &order),
+                Ufeat::random_mod(&mut OsRng, &order),
+            );
+            let (a, b) = ((&g * k).unwrap(), (&g * l).unwrap());
+            // the same point computed the other way
+            let a_again = (&(&g * k.wrapping_sub(&Ufeat::ONE)).unwrap() + &g).unwrap();
+            assert_consistent(&a, &b);
+            assert_consistent(&a, &a_again);
+            assert!(a == a_again);
+        }
+    }
+
+    #[cfg(feature = "zeroize")]
+    fn secret() -> Point {
+        let curve = Rc::new(curves::secp256k1());
+        (&curve.generator().unwrap() * Ufeat::from(0xdead_beef_u32)).unwrap()
+    }
+
+    #[cfg(feature = "zeroize")]
+    #[test]
+    fn zeroize_wipes_the_coordinates() {
+        use zeroize::Zeroize;
+
+        let mut point = secret();
+        point.zeroize();
+        assert_eq!(point.x().unwrap().retrieve(), Ufeat::ZERO);
+        assert_eq!(point.y().unwrap().retrieve(), Ufeat::ZERO);
+    }
+
+    #[cfg(feature = "zeroize")]
+    #[test]
+    fn drop_overwrites_the_memory() {
+        use std::{mem::MaybeUninit, ptr, slice};
+
+        let secret = secret();
+        // the bytes the coordinates are stored with, in Montgomery form
+        let coordinates = [secret.x().unwrap(), secret.y().unwrap()].map(|coordinate| {
+            coordinate
+                .as_montgomery()
+                .as_words()
+                .iter()
+                .flat_map(|word| word.to_ne_bytes())
+                .collect::<Vec<_>>()
+        });
+        let holds = |memory: &[u8], bytes: &[u8]| memory.windows(bytes.len()).any(|w| w == bytes);
+
+        let mut slot = MaybeUninit::new(secret.clone());
+        let memory = |slot: &MaybeUninit<Point>| {
+            // SAFETY: the slot is never freed, only the point in it is dropped, and bytes are read regardless of the value
+            unsafe { slice::from_raw_parts(slot.as_ptr() as *const u8, size_of::<Point>()) }
+                .to_vec()
+        };
+        let before = memory(&slot);
+        assert!(coordinates.iter().all(|bytes| holds(&before, bytes)));
+        // SAFETY: the slot holds an initialized point which is never used again
+        unsafe { ptr::drop_in_place(slot.as_mut_ptr()) };
+        let after = memory(&slot);
+        assert!(coordinates.iter().all(|bytes| !holds(&after, bytes)));
+        // the curve outlives the point and stays sound
+        assert!(secret.curve().contains(&secret));
     }
 }
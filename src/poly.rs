@@ -0,0 +1,603 @@
+use std::{
+    fmt,
+    ops::{Add, Mul, Neg, Sub},
+};
+
+use crypto_bigint::{
+    modular::{
+        montgomery_reduction,
+        runtime_mod::{DynResidue, DynResidueParams},
+    },
+    rand_core::OsRng,
+    Limb, NonZero, RandomMod, Uint, Word,
+};
+
+use super::extension::Exponent;
+
+/// Multiplication switches from schoolbook to Karatsuba above this many coefficients
+const KARATSUBA_THRESHOLD: usize = 32;
+/// Bits of the exponent per multiplication in `PolyRing::pow`
+const POW_WINDOW: usize = 4;
+
+/// What the polynomials need of the field of their coefficients
+///
+/// Every `Field` is one, and so is the prime field for any limbs count. The constants are taken from any element of the
+/// field as they are for `Field`, and the sums of products are left to the field since they're all the multiplication
+/// costs.
+pub trait CoefficientField:
+    Copy
+    + PartialEq
+    + fmt::Debug
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Neg<Output = Self>
+{
+    fn zero(&self) -> Self;
+    fn one(&self) -> Self;
+    /// `None` for zero
+    fn reciprocal(&self) -> Option<Self>;
+    /// The number of elements of the field
+    fn cardinality(&self) -> Exponent;
+    /// A uniformly random element of the field
+    fn sample(&self) -> Self;
+
+    /// The coefficients of the product of the nonempty `a` and `b` with schoolbook multiplication
+    fn product(a: &[Self], b: &[Self]) -> Vec<Self> {
+        let mut product = vec![a[0].zero(); a.len() + b.len() - 1];
+        for (i, x) in a.iter().enumerate() {
+            for (j, y) in b.iter().enumerate() {
+                product[i + j] = product[i + j] + *x * *y;
+            }
+        }
+        product
+    }
+    /// `product(a, a)`
+    fn product_square(a: &[Self]) -> Vec<Self> {
+        Self::product(a, a)
+    }
+}
+
+impl<const L: usize> CoefficientField for DynResidue<L> {
+    fn zero(&self) -> Self {
+        DynResidue::zero(*self.params())
+    }
+    fn one(&self) -> Self {
+        DynResidue::one(*self.params())
+    }
+    fn reciprocal(&self) -> Option<Self> {
+        let (inverse, invertible) = self.invert();
+        bool::from(invertible).then_some(inverse)
+    }
+    fn cardinality(&self) -> Exponent {
+        self.params().modulus().resize()
+    }
+    fn sample(&self) -> Self {
+        let modulus = NonZero::new(*self.params().modulus()).unwrap();
+        DynResidue::new(&Uint::random_mod(&mut OsRng, &modulus), *self.params())
+    }
+    fn product(a: &[Self], b: &[Self]) -> Vec<Self> {
+        schoolbook(a, b)
+    }
+    fn product_square(a: &[Self]) -> Vec<Self> {
+        schoolbook_square(a)
+    }
+}
+
+/// Dense polynomial over a field, coefficients from the constant term up
+///
+/// Over the prime field it's generic over the limbs count unlike the rest of the crate: Schoof's algorithm spends all
+/// its time here, and running it with `Ufeat` for a 256-bit field would make it four times slower.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct Poly<C> {
+    // never ends with a zero, so the zero polynomial is empty
+    coefficients: Vec<C>,
+    // the zero polynomial still has to know its field
+    zero: C,
+}
+
+impl<C: CoefficientField> Poly<C> {
+    /// The polynomial with the given coefficients in the field of `like`
+    pub(crate) fn new(mut coefficients: Vec<C>, like: &C) -> Self {
+        let zero = like.zero();
+        while coefficients.last() == Some(&zero) {
+            coefficients.pop();
+        }
+        Poly { coefficients, zero }
+    }
+    pub(crate) fn zero(like: &C) -> Self {
+        Poly::new(Vec::new(), like)
+    }
+    pub(crate) fn constant(c: C) -> Self {
+        Poly::new(vec![c], &c)
+    }
+    /// The polynomial `x`
+    pub(crate) fn x(like: &C) -> Self {
+        Poly::new(vec![like.zero(), like.one()], like)
+    }
+    pub(crate) fn coefficients(&self) -> &[C] {
+        &self.coefficients
+    }
+    pub(crate) fn is_zero(&self) -> bool {
+        self.coefficients.is_empty()
+    }
+    /// `None` for the zero polynomial
+    pub(crate) fn degree(&self) -> Option<usize> {
+        self.coefficients.len().checked_sub(1)
+    }
+    fn leading(&self) -> Option<&C> {
+        self.coefficients.last()
+    }
+
+    pub(crate) fn scale(&self, c: &C) -> Self {
+        Poly::new(
+            self.coefficients.iter().map(|a| *a * *c).collect(),
+            &self.zero,
+        )
+    }
+    pub(crate) fn monic(&self) -> Self {
+        match self.leading() {
+            None => self.clone(),
+            Some(leading) => self.scale(&leading.reciprocal().unwrap()),
+        }
+    }
+    pub(crate) fn square(&self) -> Self {
+        Poly::new(karatsuba_square(&self.coefficients), &self.zero)
+    }
+
+    /// The formal derivative
+    pub(crate) fn derivative(&self) -> Self {
+        let mut i = self.zero;
+        Poly::new(
+            self.coefficients
+                .iter()
+                .skip(1)
+                .map(|c| {
+                    i = i + self.zero.one();
+                    *c * i
+                })
+                .collect(),
+            &self.zero,
+        )
+    }
+
+    /// Schoolbook long division
+    pub(crate) fn div_rem(&self, divisor: &Self) -> (Self, Self) {
+        let divisor_degree = divisor.degree().expect("division by the zero polynomial");
+        let Some(degree) = self.degree().filter(|degree| *degree >= divisor_degree) else {
+            return (Poly::zero(&self.zero), self.clone());
+        };
+        let inverse_leading = divisor.leading().unwrap().reciprocal().unwrap();
+        let mut remainder = self.coefficients.clone();
+        let mut quotient = vec![self.zero; degree - divisor_degree + 1];
+        for i in (0..quotient.len()).rev() {
+            let q = remainder[i + divisor_degree] * inverse_leading;
+            quotient[i] = q;
+            for (j, d) in divisor.coefficients.iter().enumerate() {
+                remainder[i + j] = remainder[i + j] - q * *d;
+            }
+        }
+        remainder.truncate(divisor_degree);
+        (
+            Poly::new(quotient, &self.zero),
+            Poly::new(remainder, &self.zero),
+        )
+    }
+    pub(crate) fn rem(&self, divisor: &Self) -> Self {
+        self.div_rem(divisor).1
+    }
+
+    /// Monic greatest common divisor
+    pub(crate) fn gcd(&self, other: &Self) -> Self {
+        let (mut a, mut b) = (self.clone(), other.clone());
+        while !b.is_zero() {
+            let r = a.rem(&b);
+            a = b;
+            b = r;
+        }
+        a.monic()
+    }
+
+    /// Truncates to the terms below `x^n`
+    fn truncated(&self, n: usize) -> Self {
+        Poly::new(
+            self.coefficients.iter().take(n).copied().collect(),
+            &self.zero,
+        )
+    }
+    /// Reverses the coefficients as a polynomial of the given length
+    fn reversed(&self, length: usize) -> Self {
+        let mut coefficients = self.coefficients.clone();
+        coefficients.resize(length, self.zero);
+        coefficients.reverse();
+        Poly::new(coefficients, &self.zero)
+    }
+
+    /// The distinct roots in the field of the coefficients
+    ///
+    /// They are those of the gcd with $x^q - x$, which splits into linear factors: for a random $\delta$, $(x +
+    /// \delta)^{(q - 1) / 2} - 1$ vanishes at about half of them.
+    pub(crate) fn roots(&self) -> Vec<C> {
+        if self.degree().unwrap_or(0) == 0 {
+            return Vec::new();
+        }
+        let size = self.zero.cardinality();
+        let x = Poly::x(&self.zero);
+        let one = Poly::constant(self.zero.one());
+        let split = self.gcd(&(&PolyRing::new(self.clone()).pow_x(&size) - &x));
+        let half = size.shr_vartime(1);
+        let mut pending = vec![split];
+        let mut found = Vec::new();
+        while let Some(factor) = pending.pop() {
+            match factor.degree() {
+                None | Some(0) => {}
+                // monic, as the gcds and their quotients are
+                Some(1) => found.push(-factor.coefficients[0]),
+                Some(degree) => {
+                    let ring = PolyRing::new(factor.clone());
+                    loop {
+                        let shifted = &x + &Poly::constant(self.zero.sample());
+                        let part = factor.gcd(&(&ring.pow(&shifted, &half) - &one));
+                        if part.degree().is_some_and(|part| part > 0 && part < degree) {
+                            pending.push(factor.div_rem(&part).0);
+                            pending.push(part);
+                            break;
+                        }
+                    }
+                }
+            }
+        }
+        found
+    }
+}
+
+impl<const L: usize> Poly<DynResidue<L>> {
+    pub(crate) fn params(&self) -> DynResidueParams<L> {
+        *self.zero.params()
+    }
+}
+
+impl<C: CoefficientField> Add for &Poly<C> {
+    type Output = Poly<C>;
+    fn add(self, rhs: Self) -> Self::Output {
+        let (long, short) = if self.coefficients.len() >= rhs.coefficients.len() {
+            (self, rhs)
+        } else {
+            (rhs, self)
+        };
+        let mut coefficients = long.coefficients.clone();
+        for (c, s) in coefficients.iter_mut().zip(&short.coefficients) {
+            *c = *c + *s;
+        }
+        Poly::new(coefficients, &self.zero)
+    }
+}
+impl<C: CoefficientField> Neg for &Poly<C> {
+    type Output = Poly<C>;
+    fn neg(self) -> Self::Output {
+        Poly::new(self.coefficients.iter().map(|c| -*c).collect(), &self.zero)
+    }
+}
+impl<C: CoefficientField> Sub for &Poly<C> {
+    type Output = Poly<C>;
+    fn sub(self, rhs: Self) -> Self::Output {
+        self + &-rhs
+    }
+}
+impl<C: CoefficientField> Mul for &Poly<C> {
+    type Output = Poly<C>;
+    fn mul(self, rhs: Self) -> Self::Output {
+        if self.is_zero() || rhs.is_zero() {
+            return Poly::zero(&self.zero);
+        }
+        Poly::new(karatsuba(&self.coefficients, &rhs.coefficients), &self.zero)
+    }
+}
+
+/// A sum of products of residues reduced once at the end rather than after each product, which is most of the cost of
+/// the multiplication for the sizes in use
+struct ProductSum<const L: usize> {
+    // the double width sum with `high` kept below the modulus, which is what Montgomery reduction takes
+    low: Uint<L>,
+    high: Uint<L>,
+}
+
+impl<const L: usize> ProductSum<L> {
+    fn new() -> Self {
+        ProductSum {
+            low: Uint::ZERO,
+            high: Uint::ZERO,
+        }
+    }
+    fn add(&mut self, a: &DynResidue<L>, b: &DynResidue<L>) {
+        let modulus = a.params().modulus();
+        // the product is below `modulus^2`, so its upper half is below `modulus`
+        let (low, high) = a.as_montgomery().mul_wide(b.as_montgomery());
+        let (low, carry) = self.low.adc(&low, Limb::ZERO);
+        let (high, carry) = self.high.adc(&high, carry);
+        self.low = low;
+        self.high = if carry != Limb::ZERO || high >= *modulus {
+            high.wrapping_sub(modulus)
+        } else {
+            high
+        };
+    }
+    fn finish(&self, params: DynResidueParams<L>) -> DynResidue<L> {
+        let modulus = params.modulus();
+        // -modulus^-1 modulo the limb with Newton iteration, each round doubling the correct bits
+        let first = modulus.as_words()[0];
+        let mut inverse: Word = 1;
+        for _ in 0..6 {
+            inverse = inverse.wrapping_mul((2 as Word).wrapping_sub(first.wrapping_mul(inverse)));
+        }
+        DynResidue::from_montgomery(
+            montgomery_reduction(
+                &(self.low, self.high),
+                modulus,
+                Limb(inverse.wrapping_neg()),
+            ),
+            params,
+        )
+    }
+}
+
+/// `CoefficientField::product` reducing each coefficient once
+fn schoolbook<const L: usize>(a: &[DynResidue<L>], b: &[DynResidue<L>]) -> Vec<DynResidue<L>> {
+    let params = *a[0].params();
+    (0..a.len() + b.len() - 1)
+        .map(|k| {
+            let mut sum = ProductSum::new();
+            for i in k.saturating_sub(b.len() - 1)..=k.min(a.len() - 1) {
+                sum.add(&a[i], &b[k - i]);
+            }
+            sum.finish(params)
+        })
+        .collect()
+}
+
+/// `schoolbook` for `a` times itself: each product off the diagonal comes twice
+fn schoolbook_square<const L: usize>(a: &[DynResidue<L>]) -> Vec<DynResidue<L>> {
+    let params = *a[0].params();
+    (0..2 * a.len() - 1)
+        .map(|k| {
+            let (mut off_diagonal, mut diagonal) = (ProductSum::new(), ProductSum::new());
+            let low = k.saturating_sub(a.len() - 1);
+            for i in low..k.div_ceil(2) {
+                off_diagonal.add(&a[i], &a[k - i]);
+            }
+            if k % 2 == 0 {
+                diagonal.add(&a[k / 2], &a[k / 2]);
+            }
+            let off_diagonal = off_diagonal.finish(params);
+            off_diagonal + off_diagonal + diagonal.finish(params)
+        })
+        .collect()
+}
+
+fn karatsuba<C: CoefficientField>(a: &[C], b: &[C]) -> Vec<C> {
+    if a.is_empty() || b.is_empty() {
+        return Vec::new();
+    }
+    if a.len().min(b.len()) < KARATSUBA_THRESHOLD {
+        return C::product(a, b);
+    }
+    let half = a.len().max(b.len()).div_ceil(2);
+    let split = |s: &[C]| -> (Vec<C>, Vec<C>) {
+        if s.len() <= half {
+            (s.to_vec(), Vec::new())
+        } else {
+            (s[..half].to_vec(), s[half..].to_vec())
+        }
+    };
+    let ((a_low, a_high), (b_low, b_high)) = (split(a), split(b));
+    let sum = |low: &[C], high: &[C]| -> Vec<C> {
+        let mut s = low.to_vec();
+        for (i, h) in high.iter().enumerate() {
+            s[i] = s[i] + *h;
+        }
+        s
+    };
+
+    let low = karatsuba(&a_low, &b_low);
+    let high = karatsuba(&a_high, &b_high);
+    let middle = karatsuba(&sum(&a_low, &a_high), &sum(&b_low, &b_high));
+    recombine(low, middle, high, half, a.len() + b.len() - 1)
+}
+
+/// `karatsuba` for `a` times itself, which takes three squares of the halves instead of three products
+fn karatsuba_square<C: CoefficientField>(a: &[C]) -> Vec<C> {
+    if a.is_empty() {
+        return Vec::new();
+    }
+    if a.len() < KARATSUBA_THRESHOLD {
+        return C::product_square(a);
+    }
+    let half = a.len().div_ceil(2);
+    let (low_half, high_half) = a.split_at(half);
+    let mut sum = low_half.to_vec();
+    for (i, h) in high_half.iter().enumerate() {
+        sum[i] = sum[i] + *h;
+    }
+
+    let low = karatsuba_square(low_half);
+    let high = karatsuba_square(high_half);
+    let middle = karatsuba_square(&sum);
+    recombine(low, middle, high, half, 2 * a.len() - 1)
+}
+
+/// `low + (middle - low - high).x^half + high.x^(2.half)` of the given length
+fn recombine<C: CoefficientField>(
+    low: Vec<C>,
+    mut middle: Vec<C>,
+    high: Vec<C>,
+    half: usize,
+    length: usize,
+) -> Vec<C> {
+    for (i, c) in low.iter().enumerate() {
+        middle[i] = middle[i] - *c;
+    }
+    for (i, c) in high.iter().enumerate() {
+        middle[i] = middle[i] - *c;
+    }
+
+    let mut product = vec![low[0].zero(); length];
+    for (i, c) in low.into_iter().enumerate() {
+        product[i] = product[i] + c;
+    }
+    for (i, c) in middle.into_iter().enumerate() {
+        if let Some(slot) = product.get_mut(i + half) {
+            *slot = *slot + c;
+        }
+    }
+    for (i, c) in high.into_iter().enumerate() {
+        product[i + 2 * half] = product[i + 2 * half] + c;
+    }
+    product
+}
+
+/// Arithmetic modulo a fixed polynomial, reducing with a precomputed inverse instead of long division
+pub(crate) struct PolyRing<C> {
+    modulus: Poly<C>,
+    // the reversed modulus inverted modulo `x^deg`
+    inverse_reversed: Poly<C>,
+}
+
+impl<C: CoefficientField> PolyRing<C> {
+    pub(crate) fn new(modulus: Poly<C>) -> Self {
+        let degree = modulus
+            .degree()
+            .expect("can't reduce modulo the zero polynomial");
+        let reversed = modulus.reversed(degree + 1);
+        let precision = degree.max(1);
+
+        // Newton iteration g = g * (2 - f * g) doubling the precision each round
+        let one = modulus.zero.one();
+        let two = Poly::constant(one + one);
+        let mut inverse = Poly::constant(reversed.coefficients[0].reciprocal().unwrap());
+        let mut current = 1;
+        while current < precision {
+            current = (2 * current).min(precision);
+            let correction = &two - &(&reversed.truncated(current) * &inverse).truncated(current);
+            inverse = (&inverse * &correction).truncated(current);
+        }
+        PolyRing {
+            modulus,
+            inverse_reversed: inverse,
+        }
+    }
+    pub(crate) fn modulus(&self) -> &Poly<C> {
+        &self.modulus
+    }
+    fn degree(&self) -> usize {
+        self.modulus.degree().unwrap()
+    }
+    fn one(&self) -> Poly<C> {
+        Poly::constant(self.modulus.zero.one())
+    }
+
+    pub(crate) fn reduce(&self, a: &Poly<C>) -> Poly<C> {
+        let n = self.degree();
+        let Some(degree) = a.degree().filter(|degree| *degree >= n) else {
+            return a.clone();
+        };
+        if degree > 2 * n - 1 {
+            return a.rem(&self.modulus);
+        }
+        // the quotient reversed is the reversed dividend times the reversed modulus inverse
+        let m = degree - n + 1;
+        let quotient = (&a.reversed(degree + 1).truncated(m) * &self.inverse_reversed.truncated(m))
+            .truncated(m)
+            .reversed(m);
+        (a - &(&quotient * &self.modulus)).truncated(n)
+    }
+    pub(crate) fn mul(&self, a: &Poly<C>, b: &Poly<C>) -> Poly<C> {
+        self.reduce(&(a * b))
+    }
+    pub(crate) fn square(&self, a: &Poly<C>) -> Poly<C> {
+        self.reduce(&a.square())
+    }
+    /// Fixed window exponentiation: the powers of `base` below `2^POW_WINDOW` are precomputed
+    pub(crate) fn pow<const E: usize>(&self, base: &Poly<C>, exponent: &Uint<E>) -> Poly<C> {
+        let mut powers = vec![self.one(), self.reduce(base)];
+        for i in 2..1 << POW_WINDOW {
+            powers.push(self.mul(&powers[i - 1], &powers[1]));
+        }
+        let bits = exponent.bits_vartime();
+        let mut result = powers[0].clone();
+        for window in (0..bits.div_ceil(POW_WINDOW)).rev() {
+            let mut digit = 0;
+            for i in (window * POW_WINDOW..((window + 1) * POW_WINDOW).min(bits)).rev() {
+                result = self.square(&result);
+                digit = 2 * digit + exponent.bit_vartime(i) as usize;
+            }
+            if digit != 0 {
+                result = self.mul(&result, &powers[digit]);
+            }
+        }
+        result
+    }
+    /// `x^exponent`: multiplying by `x` is only a shift, so it's all squarings
+    pub(crate) fn pow_x<const E: usize>(&self, exponent: &Uint<E>) -> Poly<C> {
+        let zero = self.modulus.zero;
+        let mut result = self.one();
+        for i in (0..exponent.bits_vartime()).rev() {
+            result = self.square(&result);
+            if exponent.bit_vartime(i) {
+                let mut shifted = vec![zero];
+                shifted.extend_from_slice(&result.coefficients);
+                result = self.reduce(&Poly::new(shifted, &zero));
+            }
+        }
+        result
+    }
+
+    /// Brent-Kung modular composition: `g(a)` for each of `polys`, sharing the powers of `a`
+    ///
+    /// Takes about $2\sqrt{n}$ multiplications in the ring and $n^2$ in the field for each polynomial, where Horner's
+    /// scheme would take `n` multiplications in the ring.
+    pub(crate) fn compose(&self, polys: &[&Poly<C>], a: &Poly<C>) -> Vec<Poly<C>> {
+        let zero = self.modulus.zero;
+        let n = self.degree();
+        let k = n.isqrt() + 1;
+        let mut powers = vec![self.one(), self.reduce(a)];
+        for i in 2..=k {
+            powers.push(self.mul(&powers[i - 1], &powers[1]));
+        }
+        let giant = powers.pop().unwrap();
+
+        polys
+            .iter()
+            .map(|g| {
+                let mut result = Poly::zero(&zero);
+                for block in g.coefficients.chunks(k).rev() {
+                    let mut combination = vec![zero; n];
+                    for (c, power) in block.iter().zip(&powers) {
+                        for (slot, d) in combination.iter_mut().zip(&power.coefficients) {
+                            *slot = *slot + *c * *d;
+                        }
+                    }
+                    result = &self.mul(&result, &giant) + &Poly::new(combination, &zero);
+                }
+                result
+            })
+            .collect()
+    }
+
+    /// Inverts with the extended Euclidean algorithm; a non-trivial common factor with the modulus is returned as the error
+    pub(crate) fn invert(&self, a: &Poly<C>) -> Result<Poly<C>, Poly<C>> {
+        let zero = self.modulus.zero;
+        let (mut r0, mut r1) = (self.modulus.clone(), self.reduce(a));
+        let (mut s0, mut s1) = (Poly::zero(&zero), self.one());
+        while !r1.is_zero() {
+            let (q, r) = r0.div_rem(&r1);
+            let s = &s0 - &self.reduce(&(&q * &s1));
+            (r0, r1) = (r1, r);
+            (s0, s1) = (s1, s);
+        }
+        if r0.degree() == Some(0) {
+            Ok(s0.scale(&r0.coefficients[0].reciprocal().unwrap()))
+        } else {
+            Err(r0.monic())
+        }
+    }
+}
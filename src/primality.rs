@@ -0,0 +1,475 @@
+//! Probabilistic primality tests over `Uint`
+//!
+//! `baillie_psw` is what the crate relies on: no composite passing it is known, while it's deterministic and costs about
+//! three Miller-Rabin rounds. `factor` builds on it to split the orders of points and curves.
+use crypto_bigint::{
+    modular::runtime_mod::{DynResidue, DynResidueParams},
+    rand_core::OsRng,
+    Limb, NonZero, RandomMod, Uint,
+};
+
+/// Odd primes to sieve the candidates with before the expensive tests
+const SMALL_PRIMES: [u16; 24] = [
+    3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47, 53, 59, 61, 67, 71, 73, 79, 83, 89, 97,
+];
+
+/// `factor` divides out the primes below this before turning to Pollard's rho
+const FACTOR_TRIAL_BOUND: u32 = 1 << 12;
+/// Steps of Brent's cycle finding between two gcd computations
+const RHO_BATCH: usize = 128;
+
+/// Miller-Rabin test with `rounds` random bases; a composite passes it with probability at most $4^{-rounds}$
+pub fn miller_rabin<const L: usize>(n: &Uint<L>, rounds: usize) -> bool {
+    if let Some(decided) = trial_division(n) {
+        return decided;
+    }
+    let params = DynResidueParams::new(n);
+    // bases are drawn from 2..=n-2
+    let range = NonZero::new(n.wrapping_sub(&Uint::from(3u8))).unwrap();
+    (0..rounds).all(|_| {
+        let base = Uint::random_mod(&mut OsRng, &range).wrapping_add(&Uint::from(2u8));
+        strong_probable_prime(params, &base)
+    })
+}
+
+/// Baillie-PSW test: Miller-Rabin to base 2 followed by the strong Lucas test
+pub fn baillie_psw<const L: usize>(n: &Uint<L>) -> bool {
+    if let Some(decided) = trial_division(n) {
+        return decided;
+    }
+    strong_probable_prime(DynResidueParams::new(n), &Uint::from(2u8)) && strong_lucas(n)
+}
+
+/// Strong Lucas probable prime test with the parameters of Selfridge's method A: $P = 1$, $Q = (1 - D) / 4$ for the
+/// first `D` in 5, -7, 9, -11, ... with Jacobi symbol $(D / n) = -1$
+///
+/// Taken alone it passes some composites (22499 is the smallest one getting through the trial division here), but not
+/// the ones Miller-Rabin to base 2 misses.
+pub fn strong_lucas<const L: usize>(n: &Uint<L>) -> bool {
+    if let Some(decided) = trial_division(n) {
+        return decided;
+    }
+    // there's no `D` for a square, the search below would never end
+    let root = n.sqrt_vartime();
+    if root.wrapping_mul(&root) == *n {
+        return false;
+    }
+
+    let mut d = 5i64;
+    loop {
+        match jacobi(d, n) {
+            -1 => break,
+            // `n` has a factor in common with `D`, and `n` is bigger than `D` after trial division
+            0 => return false,
+            _ => d = if d > 0 { -(d + 2) } else { -d + 2 },
+        }
+    }
+
+    let params = DynResidueParams::new(n);
+    let residue = |value: i64| {
+        let residue = DynResidue::new(&Uint::from(value.unsigned_abs()), params);
+        if value < 0 {
+            residue.neg()
+        } else {
+            residue
+        }
+    };
+    let (p, q, d) = (DynResidue::one(params), residue((1 - d) / 4), residue(d));
+    // halving is multiplication by (n + 1) / 2
+    let half = DynResidue::new(&n.shr_vartime(1).wrapping_add(&Uint::ONE), params);
+
+    // n + 1 = k.2^s with odd k
+    let n_plus_one = n.wrapping_add(&Uint::ONE);
+    let s = n_plus_one.trailing_zeros();
+    let k = n_plus_one.shr_vartime(s);
+
+    // U_1 = 1, V_1 = P, and then left-to-right over the bits of `k`
+    let (mut u, mut v, mut q_power) = (DynResidue::one(params), p, q);
+    for bit in (0..k.bits() - 1).rev() {
+        // U_2j = U_j.V_j, V_2j = V_j^2 - 2Q^j
+        u *= v;
+        v = v.square() - q_power - q_power;
+        q_power = q_power.square();
+        if k.bit_vartime(bit) {
+            // U_(j+1) = (P.U_j + V_j) / 2, V_(j+1) = (D.U_j + P.V_j) / 2
+            (u, v) = ((p * u + v) * half, (d * u + p * v) * half);
+            q_power *= q;
+        }
+    }
+
+    let zero = DynResidue::zero(params);
+    if u == zero || v == zero {
+        return true;
+    }
+    for _ in 1..s {
+        v = v.square() - q_power - q_power;
+        q_power = q_power.square();
+        if v == zero {
+            return true;
+        }
+    }
+    false
+}
+
+/// Miller-Rabin round for odd `n` behind `params` and a base in 2..=n-2
+fn strong_probable_prime<const L: usize>(params: DynResidueParams<L>, base: &Uint<L>) -> bool {
+    let n = params.modulus();
+    let one = DynResidue::one(params);
+    let minus_one = one.neg();
+    let n_minus_one = n.wrapping_sub(&Uint::ONE);
+    let s = n_minus_one.trailing_zeros();
+    let d = n_minus_one.shr_vartime(s);
+
+    let mut x = DynResidue::new(base, params).pow_bounded_exp(&d, n.bits());
+    if x == one || x == minus_one {
+        return true;
+    }
+    for _ in 1..s {
+        x = x.square();
+        if x == minus_one {
+            return true;
+        }
+    }
+    false
+}
+
+/// Jacobi symbol $(a / n)$ for small odd `a` and odd `n`
+fn jacobi<const L: usize>(a: i64, n: &Uint<L>) -> i8 {
+    let n_mod_4 = n.as_words()[0] % 4;
+    // (-1 / n) = -1 exactly when n = 3 mod 4
+    let mut result = if a < 0 && n_mod_4 == 3 { -1 } else { 1 };
+    let a = a.unsigned_abs();
+    // quadratic reciprocity turns it into (n mod a / a)
+    if a % 4 == 3 && n_mod_4 == 3 {
+        result = -result;
+    }
+    let (_, remainder) = n.div_rem_limb(NonZero::new(Limb::from(a)).unwrap());
+    let (mut a, mut n) = (remainder.0, a);
+    while a != 0 {
+        while a % 2 == 0 {
+            a /= 2;
+            if n % 8 == 3 || n % 8 == 5 {
+                result = -result;
+            }
+        }
+        (a, n) = (n, a);
+        if a % 4 == 3 && n % 4 == 3 {
+            result = -result;
+        }
+        a %= n;
+    }
+    if n == 1 {
+        result
+    } else {
+        0
+    }
+}
+
+/// Settles small and even numbers and the ones with a small factor; `None` when it takes a real test
+fn trial_division<const L: usize>(n: &Uint<L>) -> Option<bool> {
+    if *n < Uint::from(2u8) {
+        return Some(false);
+    }
+    if *n == Uint::from(2u8) {
+        return Some(true);
+    }
+    if n.wrapping_rem(&Uint::from(2u8)) == Uint::ZERO {
+        return Some(false);
+    }
+    for prime in SMALL_PRIMES {
+        let prime = Uint::from(prime);
+        if *n == prime {
+            return Some(true);
+        }
+        if n.wrapping_rem(&prime) == Uint::ZERO {
+            return Some(false);
+        }
+    }
+    // no factor below 100 and less than 100^2
+    if *n < Uint::from(10_000u16) {
+        return Some(true);
+    }
+    None
+}
+
+/// Divides out the primes below `bound`: their exponents and what remains of `n`
+pub(crate) fn trial_factor<const L: usize>(
+    mut n: Uint<L>,
+    bound: u32,
+) -> (Vec<(u32, u32)>, Uint<L>) {
+    let mut factorization = Vec::new();
+    let mut sieve = vec![true; bound as usize];
+    for prime in 2..bound {
+        if !sieve[prime as usize] {
+            continue;
+        }
+        for multiple in (prime as usize * prime as usize..bound as usize).step_by(prime as usize) {
+            sieve[multiple] = false;
+        }
+        let divisor = NonZero::new(Limb::from(prime)).unwrap();
+        let mut exponent = 0;
+        loop {
+            let (quotient, remainder) = n.div_rem_limb(divisor);
+            if remainder != Limb::ZERO || n == Uint::ZERO {
+                break;
+            }
+            n = quotient;
+            exponent += 1;
+        }
+        if exponent > 0 {
+            factorization.push((prime, exponent));
+        }
+    }
+    (factorization, n)
+}
+
+/// The complete factorization of `n` as `(prime, exponent)` pairs in increasing order, empty for 1 and 0
+///
+/// Small primes are divided out by trial division and the rest is split with Pollard's rho, so this is practical as long
+/// as the second largest prime factor has at most about 50 bits; the largest one only goes through `baillie_psw`.
+pub fn factor<const L: usize>(n: Uint<L>) -> Vec<(Uint<L>, u32)> {
+    if n == Uint::ZERO {
+        return Vec::new();
+    }
+    let (small, rest) = trial_factor(n, FACTOR_TRIAL_BOUND);
+    let mut primes: Vec<Uint<L>> = Vec::new();
+    let mut composites = vec![rest];
+    while let Some(n) = composites.pop() {
+        if n == Uint::ONE {
+            continue;
+        }
+        if baillie_psw(&n) {
+            primes.push(n);
+            continue;
+        }
+        let divisor = rho_divisor(&n);
+        composites.push(divisor);
+        composites.push(n.wrapping_div(&divisor));
+    }
+    primes.sort();
+
+    let mut factorization: Vec<(Uint<L>, u32)> = small
+        .into_iter()
+        .map(|(prime, exponent)| (Uint::from(prime), exponent))
+        .collect();
+    for prime in primes {
+        match factorization.last_mut() {
+            Some((last, exponent)) if *last == prime => *exponent += 1,
+            _ => factorization.push((prime, 1)),
+        }
+    }
+    factorization
+}
+
+/// A nontrivial divisor of an odd composite `n` with no factors below `FACTOR_TRIAL_BOUND`, by Pollard's rho with Brent's
+/// cycle finding
+///
+/// The products of the differences are accumulated over `RHO_BATCH` steps to take one gcd per batch; when a batch
+/// overshoots to `n`, it's replayed step by step, and if that gives `n` too, the walk $x \to x^2 + c$ is retried with
+/// another `c`.
+fn rho_divisor<const L: usize>(n: &Uint<L>) -> Uint<L> {
+    // a square isn't split by rho when the walks modulo both factors are the same
+    let root = n.sqrt_vartime();
+    if root.wrapping_mul(&root) == *n {
+        return root;
+    }
+    let params = DynResidueParams::new(n);
+    let range = NonZero::new(*n).unwrap();
+    loop {
+        let random = || DynResidue::new(&Uint::random_mod(&mut OsRng, &range), params);
+        let (c, mut y) = (random(), random());
+        let step = |x: DynResidue<L>| x.square() + c;
+        let (mut x, mut saved) = (y, y);
+        let mut product = DynResidue::one(params);
+        let mut divisor = Uint::ONE;
+        let mut length = 1;
+        while divisor == Uint::ONE {
+            x = y;
+            for _ in 0..length {
+                y = step(y);
+            }
+            let mut done = 0;
+            while done < length && divisor == Uint::ONE {
+                saved = y;
+                for _ in 0..RHO_BATCH.min(length - done) {
+                    y = step(y);
+                    product *= x - y;
+                }
+                divisor = gcd(product.retrieve(), *n);
+                done += RHO_BATCH;
+            }
+            length *= 2;
+        }
+        if divisor == *n {
+            // the batch went past the collision: replay it with one gcd per step
+            loop {
+                saved = step(saved);
+                divisor = gcd((x - saved).retrieve(), *n);
+                if divisor != Uint::ONE {
+                    break;
+                }
+            }
+        }
+        if divisor != *n {
+            return divisor;
+        }
+    }
+}
+
+/// Euclid's algorithm, which is fine with the variable time of `wrapping_rem` for public numbers
+fn gcd<const L: usize>(mut a: Uint<L>, mut b: Uint<L>) -> Uint<L> {
+    while b != Uint::ZERO {
+        (a, b) = (b, a.wrapping_rem(&b));
+    }
+    a
+}
+
+#[cfg(test)]
+mod tests {
+    use crypto_bigint::{U128, U256, U64};
+
+    use super::*;
+
+    fn n(value: u64) -> U64 {
+        U64::from(value)
+    }
+
+    /// Strong pseudoprimes to base 2 with no factor below 100, so that trial division doesn't settle them
+    const BASE_2_PSEUDOPRIMES: [u64; 4] = [1373653, 25326001, 3215031751, 3825123056546413051];
+
+    #[test]
+    fn small_numbers_are_settled_by_trial_division() {
+        let primes = [2, 3, 5, 97, 101, 9973, 10007];
+        let composites = [0, 1, 4, 9, 2047, 561, 41041, 9409];
+        for prime in primes {
+            assert!(baillie_psw(&n(prime)), "{prime}");
+            assert!(miller_rabin(&n(prime), 8), "{prime}");
+            assert!(strong_lucas(&n(prime)), "{prime}");
+        }
+        for composite in composites {
+            assert!(!baillie_psw(&n(composite)), "{composite}");
+            assert!(!miller_rabin(&n(composite), 8), "{composite}");
+            assert!(!strong_lucas(&n(composite)), "{composite}");
+        }
+        // 2047 = 23.89 would pass base 2 without the trial division
+        assert!(strong_probable_prime(
+            DynResidueParams::new(&n(2047)),
+            &n(2)
+        ));
+    }
+
+    #[test]
+    fn lucas_catches_the_base_2_pseudoprimes() {
+        for pseudoprime in BASE_2_PSEUDOPRIMES {
+            let pseudoprime = n(pseudoprime);
+            assert!(strong_probable_prime(
+                DynResidueParams::new(&pseudoprime),
+                &n(2)
+            ));
+            assert!(!strong_lucas(&pseudoprime));
+            assert!(!baillie_psw(&pseudoprime));
+            assert!(!miller_rabin(&pseudoprime, 20));
+        }
+    }
+
+    #[test]
+    fn base_2_catches_the_lucas_pseudoprime() {
+        let pseudoprime = n(22499);
+        assert!(strong_lucas(&pseudoprime));
+        assert!(!strong_probable_prime(
+            DynResidueParams::new(&pseudoprime),
+            &n(2)
+        ));
+        assert!(!baillie_psw(&pseudoprime));
+    }
+
+    #[test]
+    fn carmichael_numbers_are_composite() {
+        // 211.421.631 passes Fermat test to every coprime base
+        let carmichael = n(56052361);
+        let params = DynResidueParams::new(&carmichael);
+        let minus_one = carmichael.wrapping_sub(&U64::ONE);
+        for base in [2u8, 3, 5, 7] {
+            let base = DynResidue::new(&U64::from(base), params);
+            assert_eq!(base.pow(&minus_one), DynResidue::one(params));
+        }
+        assert!(!miller_rabin(&carmichael, 20));
+        assert!(!baillie_psw(&carmichael));
+    }
+
+    #[test]
+    fn large_primes_pass() {
+        let mersenne = |exponent: usize| U256::ONE.shl_vartime(exponent).wrapping_sub(&U256::ONE);
+        let primes = [
+            mersenne(61),
+            mersenne(89),
+            mersenne(127),
+            // the fields of Curve25519 and secp256k1
+            U256::ONE.shl_vartime(255).wrapping_sub(&U256::from(19u8)),
+            U256::ZERO
+                .wrapping_sub(&U256::ONE.shl_vartime(32))
+                .wrapping_sub(&U256::from(977u16)),
+        ];
+        for prime in primes {
+            assert!(baillie_psw(&prime));
+            assert!(miller_rabin(&prime, 8));
+        }
+        assert!(!baillie_psw(&mersenne(61).wrapping_mul(&mersenne(89))));
+        // 2^67 - 1 = 193707721.761838257287
+        assert!(!baillie_psw(&mersenne(67)));
+    }
+
+    #[test]
+    fn jacobi_agrees_with_euler_criterion() {
+        let legendre = |a: i64, p: u64| {
+            let residue = a.rem_euclid(p as i64) as u64;
+            let mut power = 1;
+            for _ in 0..(p - 1) / 2 {
+                power = power * residue % p;
+            }
+            match power {
+                0 => 0,
+                1 => 1,
+                _ => -1,
+            }
+        };
+        for (p, q) in [(101, 103), (10007, 3), (13, 13)] {
+            for a in (-41..=41).step_by(2) {
+                assert_eq!(
+                    jacobi(a, &n(p * q)),
+                    legendre(a, p) * legendre(a, q),
+                    "({a} / {p}.{q})"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn factor_multiplies_back() {
+        let numbers = [
+            U128::from(2u8).shl_vartime(40),
+            U128::from(3u64.pow(5) * 7 * 10007 * 10007),
+            // two primes past the trial division bound, for Pollard's rho
+            U128::from(1_000_003u64 * 1_000_033),
+            U128::from(1_000_003u64 * 1_000_003 * 4099),
+            U128::from((1u64 << 61) - 1).wrapping_mul(&U128::from(65537u32)),
+        ];
+        for number in numbers {
+            let factorization = factor(number);
+            assert!(factorization.windows(2).all(|pair| pair[0].0 < pair[1].0));
+            assert!(factorization.iter().all(|(prime, _)| baillie_psw(prime)));
+            let product = factorization
+                .iter()
+                .fold(U128::ONE, |product, (prime, exponent)| {
+                    (0..*exponent).fold(product, |product, _| product.wrapping_mul(prime))
+                });
+            assert_eq!(product, number);
+        }
+        assert!(factor(U128::ZERO).is_empty());
+        assert!(factor(U128::ONE).is_empty());
+        assert_eq!(
+            factor(U128::from(1_000_003u32)),
+            [(U128::from(1_000_003u32), 1)]
+        );
+    }
+}
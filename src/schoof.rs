@@ -0,0 +1,895 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use crypto_bigint::{
+    modular::runtime_mod::{DynResidue, DynResidueParams},
+    Uint,
+};
+
+use super::{
+    field,
+    poly::{Poly, PolyRing},
+    EllipticCurve, Ufeat,
+};
+
+/// Schoof's algorithm needs primes different from `p`, so tiny fields are left to the naive counting
+const SCHOOF_MIN_BITS: usize = 16;
+/// Baby-step giant-step picks the order among this many bits of candidates left by Schoof's algorithm
+const MATCH_BITS: usize = 42;
+/// Points to try for the match before falling back to plain Schoof's algorithm
+const MATCH_ATTEMPTS: usize = 8;
+/// Baby and giant steps are made this many at once to share the inversions
+const LANES: u64 = 256;
+
+/// Curve coefficients moved to the limbs count the computation runs with
+pub(crate) struct Coefficients<const L: usize> {
+    a2: DynResidue<L>,
+    a4: DynResidue<L>,
+    a6: DynResidue<L>,
+}
+
+impl<const L: usize> Coefficients<L> {
+    pub(crate) fn new(curve: &EllipticCurve) -> Self {
+        let params = DynResidueParams::new(&curve.original_p().resize::<L>());
+        let resize =
+            |r: &DynResidue<{ Ufeat::LIMBS }>| DynResidue::new(&r.retrieve().resize(), params);
+        Coefficients {
+            a2: resize(curve.a2()),
+            a4: resize(curve.a4()),
+            a6: resize(curve.a6()),
+        }
+    }
+    fn params(&self) -> DynResidueParams<L> {
+        *self.a2.params()
+    }
+    /// $x^3 + a2.x^2 + a4.x + a6$ as a polynomial
+    pub(crate) fn rhs(&self) -> Poly<DynResidue<L>> {
+        Poly::new(
+            vec![self.a6, self.a4, self.a2, DynResidue::one(self.params())],
+            &self.a2,
+        )
+    }
+
+    /// Division polynomials up to `n` with `y` factored out of the even ones: $ψ_n = f_n$ for odd `n` and $ψ_n = y.f_n$ for even
+    pub(crate) fn division_polynomials(&self, n: usize) -> Vec<Poly<DynResidue<L>>> {
+        let mut f = self.first_division_polynomials();
+        let rhs_squared = self.rhs().square();
+        for k in f.len()..=n {
+            let next = self.next_division_polynomial(k, &|i| &f[i], &rhs_squared);
+            f.push(next);
+        }
+        f.truncate(n + 1);
+        f
+    }
+
+    /// $f_n$ of `division_polynomials` alone: the recurrences only look around `n / 2`, so it takes a few of them for
+    /// each halving rather than all those below `n`
+    pub(crate) fn division_polynomial(&self, n: usize) -> Poly<DynResidue<L>> {
+        let first = self.first_division_polynomials();
+        let mut needed = BTreeSet::from([n]);
+        let mut pending = vec![n];
+        while let Some(k) = pending.pop() {
+            if k >= first.len() {
+                let m = k / 2;
+                let low = if k % 2 == 1 { m - 1 } else { m - 2 };
+                pending.extend((low..=m + 2).filter(|i| needed.insert(*i)));
+            }
+        }
+        let rhs_squared = self.rhs().square();
+        let mut f = BTreeMap::new();
+        for k in needed {
+            let next = match first.get(k) {
+                Some(known) => known.clone(),
+                None => self.next_division_polynomial(k, &|i| &f[&i], &rhs_squared),
+            };
+            f.insert(k, next);
+        }
+        f.remove(&n).unwrap()
+    }
+
+    /// $f_0$ to $f_4$, which start the recurrences
+    fn first_division_polynomials(&self) -> Vec<Poly<DynResidue<L>>> {
+        let params = self.params();
+        let constant = |c: DynResidue<L>| Poly::constant(c);
+        let small = |c: u8| DynResidue::new(&Uint::from(c), params);
+        // b-invariants of the curve with a1 = a3 = 0
+        let (b2, b4, b6) = (small(4) * self.a2, small(2) * self.a4, small(4) * self.a6);
+        let b8 = small(4) * self.a2 * self.a6 - self.a4.square();
+
+        vec![
+            Poly::zero(&self.a2),
+            constant(DynResidue::one(params)),
+            constant(small(2)),
+            Poly::new(
+                vec![b8, small(3) * b6, small(3) * b4, b2, small(3)],
+                &self.a2,
+            ),
+            Poly::new(
+                vec![
+                    b4 * b8 - b6.square(),
+                    b2 * b8 - b4 * b6,
+                    small(10) * b8,
+                    small(10) * b6,
+                    small(5) * b4,
+                    b2,
+                    small(2),
+                ],
+                &self.a2,
+            )
+            .scale(&small(2)),
+        ]
+    }
+
+    /// $f_k$ for `k` from 5 on, out of the ones from $k / 2 - 2$ to $k / 2 + 2$ given by `f`
+    fn next_division_polynomial<'f>(
+        &self,
+        k: usize,
+        f: &dyn Fn(usize) -> &'f Poly<DynResidue<L>>,
+        rhs_squared: &Poly<DynResidue<L>>,
+    ) -> Poly<DynResidue<L>> {
+        let m = k / 2;
+        if k % 2 == 1 {
+            let (first, second) = (f(m + 2) * &f(m).square(), f(m - 1) * &f(m + 1).square());
+            let (first, second) = (&first * f(m), &second * f(m + 1));
+            // the even ones among them lack y^4 = rhs^2
+            if m.is_multiple_of(2) {
+                &(rhs_squared * &first) - &second
+            } else {
+                &first - &(rhs_squared * &second)
+            }
+        } else {
+            let inner = &(f(m + 2) * &f(m - 1).square()) - &(f(m - 2) * &f(m + 1).square());
+            let half = DynResidue::new(&Uint::from(2u8), self.params()).invert().0;
+            (f(m) * &inner).scale(&half)
+        }
+    }
+}
+
+/// A point with the coordinates `(a(x), b(x).y)` in the ring $F_p[x, y] / (h(x), y^2 - rhs(x))$
+#[derive(Clone, PartialEq)]
+enum RingPoint<const L: usize> {
+    AtInfinity,
+    Regular {
+        a: Poly<DynResidue<L>>,
+        b: Poly<DynResidue<L>>,
+    },
+}
+
+/// The ring the points live in: `h` is a factor of a division polynomial and gets replaced with a smaller one each time a
+/// non-invertible element reveals its factorization
+struct Torsion<'c, const L: usize> {
+    ring: PolyRing<DynResidue<L>>,
+    rhs: Poly<DynResidue<L>>,
+    coefficients: &'c Coefficients<L>,
+}
+
+impl<const L: usize> Torsion<'_, L> {
+    fn invert(&self, a: &Poly<DynResidue<L>>) -> Result<Poly<DynResidue<L>>, Poly<DynResidue<L>>> {
+        self.ring.invert(a)
+    }
+    /// A proper factor of `h` given a common factor with it which is neither trivial nor `h` itself
+    fn split(&self, a: &Poly<DynResidue<L>>, b: &Poly<DynResidue<L>>) -> Poly<DynResidue<L>> {
+        let factor = a.gcd(self.ring.modulus());
+        if factor.degree() == Some(0) {
+            b.gcd(self.ring.modulus())
+        } else {
+            factor
+        }
+    }
+
+    fn double(&self, point: &RingPoint<L>) -> Result<RingPoint<L>, Poly<DynResidue<L>>> {
+        let RingPoint::Regular { a, b } = point else {
+            return Ok(RingPoint::AtInfinity);
+        };
+        if b.is_zero() {
+            return Ok(RingPoint::AtInfinity);
+        }
+        let params = a.params();
+        let small = |c: u8| DynResidue::new(&Uint::from(c), params);
+        // slope (3x^2 + 2.a2.x + a4) / 2y = (3a^2 + 2.a2.a + a4) / (2b.rhs) * y
+        let numerator = &(&self.ring.square(a).scale(&small(3))
+            + &a.scale(&(small(2) * self.coefficients.a2)))
+            + &Poly::constant(self.coefficients.a4);
+        let denominator = self.ring.mul(&b.scale(&small(2)), &self.rhs);
+        if denominator.is_zero() {
+            return Err(self.split(b, &self.rhs));
+        }
+        let lambda = self.ring.mul(&numerator, &self.invert(&denominator)?);
+        let x = &(&self.ring.mul(&self.ring.square(&lambda), &self.rhs)
+            - &Poly::constant(self.coefficients.a2))
+            - &a.scale(&small(2));
+        let y = &self.ring.mul(&lambda, &(a - &x)) - b;
+        Ok(RingPoint::Regular {
+            a: self.ring.reduce(&x),
+            b: y,
+        })
+    }
+
+    fn add(&self, p: &RingPoint<L>, q: &RingPoint<L>) -> Result<RingPoint<L>, Poly<DynResidue<L>>> {
+        let (RingPoint::Regular { a: a1, b: b1 }, RingPoint::Regular { a: a2, b: b2 }) = (p, q)
+        else {
+            return Ok(if let RingPoint::AtInfinity = p {
+                q.clone()
+            } else {
+                p.clone()
+            });
+        };
+        if a1 == a2 {
+            let (difference, sum) = (b1 - b2, b1 + b2);
+            return if difference.is_zero() {
+                self.double(p)
+            } else if sum.is_zero() {
+                Ok(RingPoint::AtInfinity)
+            } else {
+                // equal on some of the torsion points and opposite on the others
+                Err(self.split(&difference, &sum))
+            };
+        }
+        let lambda = self.ring.mul(&(b2 - b1), &self.invert(&(a2 - a1))?);
+        let x = &(&(&self.ring.mul(&self.ring.square(&lambda), &self.rhs)
+            - &Poly::constant(self.coefficients.a2))
+            - a1)
+            - a2;
+        let y = &self.ring.mul(&lambda, &(a1 - &x)) - b1;
+        Ok(RingPoint::Regular {
+            a: self.ring.reduce(&x),
+            b: y,
+        })
+    }
+
+    fn mul(&self, point: &RingPoint<L>, k: u64) -> Result<RingPoint<L>, Poly<DynResidue<L>>> {
+        let mut result = RingPoint::AtInfinity;
+        for i in (0..u64::BITS - k.leading_zeros()).rev() {
+            result = self.double(&result)?;
+            if (k >> i) & 1 == 1 {
+                result = self.add(&result, point)?;
+            }
+        }
+        Ok(result)
+    }
+
+    fn reduce(&self, point: &RingPoint<L>) -> RingPoint<L> {
+        match point {
+            RingPoint::AtInfinity => RingPoint::AtInfinity,
+            RingPoint::Regular { a, b } => RingPoint::Regular {
+                a: self.ring.reduce(a),
+                b: self.ring.reduce(b),
+            },
+        }
+    }
+
+    /// Finds `t mod ell` from the characteristic equation $π^2 - t.π + p = 0$ on the points of `h`
+    fn trace(
+        &self,
+        frobenius: &RingPoint<L>,
+        frobenius_squared: &RingPoint<L>,
+        p_mod_ell: u64,
+        ell: u64,
+    ) -> Result<u64, Poly<DynResidue<L>>> {
+        let point = RingPoint::Regular {
+            a: self.ring.reduce(&Poly::x(&self.coefficients.a2)),
+            b: Poly::constant(DynResidue::one(self.rhs.params())),
+        };
+        let target = self.add(frobenius_squared, &self.mul(&point, p_mod_ell)?)?;
+        let RingPoint::Regular {
+            a: target_a,
+            b: target_b,
+        } = &target
+        else {
+            return Ok(0);
+        };
+
+        let mut multiple = frobenius.clone();
+        for tau in 1..=(ell - 1) / 2 {
+            if let RingPoint::Regular { a, b } = &multiple {
+                if a == target_a {
+                    let (difference, sum) = (b - target_b, b + target_b);
+                    return if difference.is_zero() {
+                        Ok(tau)
+                    } else if sum.is_zero() {
+                        Ok(ell - tau)
+                    } else {
+                        Err(self.split(&difference, &sum))
+                    };
+                }
+            }
+            multiple = self.add(&multiple, frobenius)?;
+        }
+        panic!("Frobenius doesn't satisfy its characteristic equation; is the curve smooth?")
+    }
+
+    /// Lifts `t mod ell^(k - 1)` to `t mod ell^k` on the points of `h`: they have the order exactly `ell^k`, so there are
+    /// only `ell` candidates left, `step = ell^(k - 1)` apart
+    fn lift_trace(
+        &self,
+        frobenius: &RingPoint<L>,
+        frobenius_squared: &RingPoint<L>,
+        p_mod_power: u64,
+        power: u64,
+        (known, step): (u64, u64),
+    ) -> Result<u64, Poly<DynResidue<L>>> {
+        let point = RingPoint::Regular {
+            a: self.ring.reduce(&Poly::x(&self.coefficients.a2)),
+            b: Poly::constant(DynResidue::one(self.rhs.params())),
+        };
+        let target = self.add(frobenius_squared, &self.mul(&point, p_mod_power)?)?;
+        let stride = self.mul(frobenius, step)?;
+
+        let mut multiple = self.mul(frobenius, known)?;
+        for tau in (known..power).step_by(step as usize) {
+            match (&multiple, &target) {
+                (RingPoint::AtInfinity, RingPoint::AtInfinity) => return Ok(tau),
+                (
+                    RingPoint::Regular { a, b },
+                    RingPoint::Regular {
+                        a: target_a,
+                        b: target_b,
+                    },
+                ) if a == target_a => {
+                    let (difference, sum) = (b - target_b, b + target_b);
+                    if difference.is_zero() {
+                        return Ok(tau);
+                    } else if !sum.is_zero() {
+                        return Err(self.split(&difference, &sum));
+                    }
+                    // `-tau` then, which isn't a candidate
+                }
+                _ => {}
+            }
+            multiple = self.add(&multiple, &stride)?;
+        }
+        panic!("Frobenius doesn't satisfy its characteristic equation; is the curve smooth?")
+    }
+}
+
+/// A prime power `ell^k` to find the trace modulo, with the degree of the polynomial which has the `x` of the points of
+/// order exactly `ell^k` as its roots: the cost of the step grows with it
+struct Modulus {
+    ell: u64,
+    power: u64,
+    degree: u64,
+}
+
+/// The prime powers in the order of the bits of the trace per cost, which is the order they're worth computing in
+fn moduli(p: &Ufeat) -> Vec<Modulus> {
+    // primes up to `4 * bits` multiply to way more than Hasse interval
+    let largest = 4 * p.bits() as u64 + 16;
+    let mut moduli = Vec::new();
+    for ell in (2..=largest).filter(|ell| {
+        (2..*ell)
+            .take_while(|d| d * d <= *ell)
+            .all(|d| !ell.is_multiple_of(d))
+            && Ufeat::from(*ell) != *p
+    }) {
+        // the 2-torsion points are the roots of the right hand side
+        let mut degree = if ell == 2 { 3 } else { (ell * ell - 1) / 2 };
+        let mut power = ell;
+        while degree <= largest * largest {
+            moduli.push(Modulus { ell, power, degree });
+            power *= ell;
+            degree = (power * power - (power / ell) * (power / ell)) / 2;
+        }
+    }
+    // the multiplications of Karatsuba take `degree^log2(3)`; the powers of a prime come in order as they bring the same
+    // bits for ever bigger degrees
+    moduli.sort_by(|a, b| {
+        let worth = |modulus: &Modulus| {
+            (modulus.ell as f64).log2() / (modulus.degree as f64).powf(3f64.log2())
+        };
+        worth(b).total_cmp(&worth(a))
+    });
+    moduli
+}
+
+/// Schoof's algorithm proper: the trace modulo the prime powers, up to the product requested
+struct Schoof<'c, const L: usize> {
+    curve: &'c EllipticCurve,
+    coefficients: Coefficients<L>,
+    division_polynomials: Vec<Poly<DynResidue<L>>>,
+    moduli: Vec<Modulus>,
+    done: usize,
+    // `(ell, ell^k, t mod ell^k)` for the highest power of each prime done so far
+    traces: Vec<(u64, u64, u64)>,
+}
+
+impl<'c, const L: usize> Schoof<'c, L> {
+    fn new(curve: &'c EllipticCurve) -> Self {
+        let coefficients = Coefficients::new(curve);
+        Schoof {
+            curve,
+            division_polynomials: Vec::new(),
+            moduli: moduli(&curve.original_p()),
+            done: 0,
+            traces: Vec::new(),
+            coefficients,
+        }
+    }
+
+    /// The product of the coprime moduli the trace is known for
+    fn product(&self) -> Ufeat {
+        self.traces
+            .iter()
+            .fold(Ufeat::ONE, |product, (_, power, _)| {
+                product.wrapping_mul(&Ufeat::from(*power))
+            })
+    }
+
+    /// Goes through the moduli until the trace is known modulo at least `target`; stops with `None` as soon as `abort`
+    /// accepts a pair `(ell^k, t mod ell^k)`
+    fn advance(&mut self, target: &Ufeat, abort: &dyn Fn(u64, u64) -> bool) -> Option<()> {
+        let mut product = self.product();
+        let mut needed = self.done;
+        while product < *target {
+            product = product.wrapping_mul(&Ufeat::from(self.moduli[needed].ell));
+            needed += 1;
+        }
+        let largest = self.moduli[self.done..needed]
+            .iter()
+            .map(|modulus| modulus.power as usize)
+            .max();
+        if let Some(largest) = largest.filter(|largest| *largest >= self.division_polynomials.len())
+        {
+            self.division_polynomials = self.coefficients.division_polynomials(largest);
+        }
+
+        while self.done < needed {
+            let Modulus { ell, power, .. } = self.moduli[self.done];
+            let t = self.trace_mod(ell, power);
+            if abort(power, t) {
+                return None;
+            }
+            match self.traces.iter_mut().find(|(prime, ..)| *prime == ell) {
+                Some(known) => *known = (ell, power, t),
+                None => self.traces.push((ell, power, t)),
+            }
+            self.done += 1;
+        }
+        Some(())
+    }
+
+    /// `t mod ell^k`, knowing `t mod ell^(k - 1)` already
+    fn trace_mod(&self, ell: u64, power: u64) -> u64 {
+        let p = self.curve.original_p().resize::<L>();
+        let x = Poly::x(&self.coefficients.a2);
+        let rhs = self.coefficients.rhs();
+        if power == 2 {
+            // the trace is even exactly when there's a point of order 2, i.e. when `rhs` has a root
+            let ring = PolyRing::new(rhs.clone());
+            let roots = (&ring.pow_x(&p) - &x).gcd(&rhs);
+            return if roots.degree() == Some(0) { 1 } else { 0 };
+        }
+
+        let f = &self.division_polynomials;
+        let h = if power == ell {
+            f[ell as usize].clone()
+        } else {
+            // the points of the lower orders are the roots of the previous division polynomial
+            f[power as usize].div_rem(&f[(power / ell) as usize]).0
+        };
+        let mut torsion = Torsion {
+            ring: PolyRing::new(h.monic()),
+            rhs: rhs.clone(),
+            coefficients: &self.coefficients,
+        };
+        // π = (x^p, rhs^((p - 1) / 2).y) and π^2 = (x^(p^2), rhs^((p^2 - 1) / 2).y); raising to the power `p` commutes
+        // with polynomials over F_p, so π^2 is a composition with x^p
+        let frobenius_x = torsion.ring.pow_x(&p);
+        let frobenius_y = torsion.ring.pow(&rhs, &p.shr_vartime(1));
+        let [frobenius_squared_x, frobenius_y_p] = torsion
+            .ring
+            .compose(&[&frobenius_x, &frobenius_y], &frobenius_x)
+            .try_into()
+            .unwrap();
+        let mut frobenius = RingPoint::Regular {
+            a: frobenius_x,
+            b: frobenius_y.clone(),
+        };
+        let mut frobenius_squared = RingPoint::Regular {
+            a: frobenius_squared_x,
+            b: torsion.ring.mul(&frobenius_y_p, &frobenius_y),
+        };
+        let p_mod_power = self
+            .curve
+            .original_p()
+            .wrapping_rem(&Ufeat::from(power))
+            .as_words()[0];
+        let known = self
+            .traces
+            .iter()
+            .find(|(prime, ..)| *prime == ell)
+            .map(|(_, step, t)| (*t, *step));
+        loop {
+            let trace = match known {
+                None => torsion.trace(&frobenius, &frobenius_squared, p_mod_power, ell),
+                Some(known) => {
+                    torsion.lift_trace(&frobenius, &frobenius_squared, p_mod_power, power, known)
+                }
+            };
+            match trace {
+                Ok(t) => return t,
+                Err(factor) => {
+                    let cofactor = torsion.ring.modulus().div_rem(&factor).0;
+                    let smaller = if factor.degree() <= cofactor.degree() {
+                        factor
+                    } else {
+                        cofactor.monic()
+                    };
+                    torsion.ring = PolyRing::new(smaller);
+                    frobenius = torsion.reduce(&frobenius);
+                    frobenius_squared = torsion.reduce(&frobenius_squared);
+                }
+            }
+        }
+    }
+
+    /// The trace modulo the product of the moduli with Chinese remainder theorem, and the product itself
+    fn crt(&self) -> (Ufeat, Ufeat) {
+        self.traces.iter().fold(
+            (Ufeat::ZERO, Ufeat::ONE),
+            |(trace, modulus), &(_, power, t)| {
+                let power_uint = Ufeat::from(power);
+                let trace_mod_power = trace.wrapping_rem(&power_uint).as_words()[0];
+                let modulus_mod_power = modulus.wrapping_rem(&power_uint).as_words()[0];
+                let k = (t + power - trace_mod_power) % power
+                    * inverse_mod(modulus_mod_power, power)
+                    % power;
+                (
+                    trace.wrapping_add(&modulus.wrapping_mul(&Ufeat::from(k))),
+                    modulus.wrapping_mul(&power_uint),
+                )
+            },
+        )
+    }
+}
+
+/// An affine point over the field with `L` limbs, `None` for the point at infinity
+type Affine<const L: usize> = Option<(DynResidue<L>, DynResidue<L>)>;
+
+impl<const L: usize> Coefficients<L> {
+    fn add(&self, p: &Affine<L>, q: &Affine<L>) -> Affine<L> {
+        let (Some((x1, y1)), Some((x2, y2))) = (p, q) else {
+            return p.or(*q);
+        };
+        let lambda = if x1 != x2 {
+            (*y2 - y1) * (*x2 - x1).invert().0
+        } else if y1 == y2 && *y1 != DynResidue::zero(self.params()) {
+            let small = |c: u8| DynResidue::new(&Uint::from(c), self.params());
+            (small(3) * x1.square() + small(2) * self.a2 * x1 + self.a4)
+                * (small(2) * y1).invert().0
+        } else {
+            return None;
+        };
+        let x = lambda.square() - self.a2 - x1 - x2;
+        Some((x, lambda * (*x1 - x) - y1))
+    }
+    fn mul(&self, point: &Affine<L>, k: &Ufeat) -> Affine<L> {
+        let mut result = None;
+        for i in (0..k.bits_vartime()).rev() {
+            result = self.add(&result, &result);
+            if k.bit_vartime(i) {
+                result = self.add(&result, point);
+            }
+        }
+        result
+    }
+
+    /// Adds `q` to each of `points` with Montgomery's trick: a single inversion for all the slopes
+    fn add_all(&self, points: &mut [Affine<L>], q: &Affine<L>) {
+        let Some((qx, qy)) = q else {
+            return;
+        };
+        // the denominators multiplied up to each point; the ones sharing `x` with `q` are left to `add`
+        let mut before = Vec::with_capacity(points.len());
+        let mut product = DynResidue::one(self.params());
+        for point in points.iter() {
+            before.push(product);
+            if let Some((x, _)) = point.filter(|(x, _)| x != qx) {
+                product *= *qx - x;
+            }
+        }
+        let mut inverse = product.invert().0;
+        for (point, before) in points.iter_mut().zip(before).rev() {
+            match *point {
+                Some((x, y)) if x != *qx => {
+                    let lambda = (*qy - y) * inverse * before;
+                    inverse *= *qx - x;
+                    let sum_x = lambda.square() - self.a2 - x - qx;
+                    *point = Some((sum_x, lambda * (x - sum_x) - y));
+                }
+                _ => *point = self.add(point, q),
+            }
+        }
+    }
+    /// Visits `start + i.step` for each `i` in `0..count` until `visit` returns `false`, which is returned then
+    ///
+    /// The progression runs in `LANES` interleaved parts, so that each step takes only one inversion for all of them.
+    fn progression(
+        &self,
+        start: &Affine<L>,
+        step: &Affine<L>,
+        count: u64,
+        mut visit: impl FnMut(u64, &Affine<L>) -> bool,
+    ) -> bool {
+        let length = count.div_ceil(LANES);
+        let lane_step = self.mul(step, &Ufeat::from(length));
+        let mut points = vec![*start];
+        while (points.len() as u64) < count.min(LANES) {
+            points.push(self.add(points.last().unwrap(), &lane_step));
+        }
+        for t in 0..length {
+            for (lane, point) in points.iter().enumerate() {
+                let i = lane as u64 * length + t;
+                if i < count && !visit(i, point) {
+                    return false;
+                }
+            }
+            self.add_all(&mut points, step);
+        }
+        true
+    }
+
+    /// Finds the group order among `high - i.step` for `i` in `0..count`: baby-step giant-step for `i` with
+    /// $high.P = i.(step.P)$ on a few points until one of them leaves a single candidate
+    fn match_order(
+        &self,
+        curve: &EllipticCurve,
+        high: Ufeat,
+        step: Ufeat,
+        count: u64,
+    ) -> Option<Ufeat> {
+        if count == 1 {
+            return Some(high);
+        }
+        let m = count.isqrt() + 1;
+        // the low word of `x` is enough to find the matches, which are checked anyway
+        let key = |point: &Affine<L>| point.map(|(x, _)| x.retrieve().as_words()[0]);
+        let mut x = DynResidue::zero(*curve.p());
+        let one = DynResidue::one(*curve.p());
+        for _ in 0..MATCH_ATTEMPTS {
+            let point = loop {
+                x += one;
+                if let Some(y) = field::sqrt(&curve.rhs(&x)) {
+                    let resize = |r: DynResidue<{ Ufeat::LIMBS }>| {
+                        DynResidue::new(&r.retrieve().resize(), self.params())
+                    };
+                    break Some((resize(x), resize(y)));
+                }
+            };
+            let stride = self.mul(&point, &step);
+
+            let mut baby_steps = HashMap::new();
+            // a repeated `x` means the order of `stride` is below `2m`, so the point can't tell the candidates apart
+            if !self.progression(&None, &stride, m, |j, point| {
+                baby_steps.insert(key(point), j).is_none()
+            }) {
+                continue;
+            }
+
+            // `high.P - k.m.stride`
+            let giant_step = self.mul(&stride, &Ufeat::from(m)).map(|(x, y)| (x, -y));
+            let mut found = Vec::new();
+            self.progression(
+                &self.mul(&point, &high),
+                &giant_step,
+                count / m + 1,
+                |k, giant| {
+                    if let Some(&j) = baby_steps.get(&key(giant)) {
+                        let i = k * m + j;
+                        if i < count && *giant == self.mul(&stride, &Ufeat::from(j)) {
+                            found.push(i);
+                        }
+                    }
+                    true
+                },
+            );
+            if let [i] = found[..] {
+                return Some(high.wrapping_sub(&step.wrapping_mul(&Ufeat::from(i))));
+            }
+        }
+        None
+    }
+}
+
+/// A computation over $F_p$ generic over the limbs count, see `EllipticCurve::with_limbs`
+pub(crate) trait WithLimbs {
+    type Output;
+    fn run<const L: usize>(self) -> Self::Output;
+}
+
+struct Order<'c> {
+    curve: &'c EllipticCurve,
+    abort: &'c dyn Fn(u64, u64) -> bool,
+}
+
+impl WithLimbs for Order<'_> {
+    type Output = Option<Ufeat>;
+    fn run<const L: usize>(self) -> Option<Ufeat> {
+        self.curve.order_with::<L>(self.abort)
+    }
+}
+
+impl EllipticCurve {
+    /// Computes the number of points on the curve (including the point at infinity) with Schoof's algorithm
+    ///
+    /// The curve must be smooth. The trace is found modulo the prime powers cheapest per bit until only about
+    /// `2^MATCH_BITS` candidates are left in Hasse interval, and baby-step giant-step on a point picks the right one. There
+    /// are no Elkies-Atkin improvements here, so it's still slow: seconds for 128-bit fields and minutes for 256-bit ones.
+    pub fn compute_order(&self) -> Ufeat {
+        self.compute_order_or_abort(&|_, _| false)
+            .expect("nothing to abort on")
+    }
+
+    /// `compute_order` which gives up with `None` once `abort` accepts a pair `(ell^k, t mod ell^k)` of the trace
+    /// modulo a small prime power; the order is `p + 1 - t`
+    pub(crate) fn compute_order_or_abort(&self, abort: &dyn Fn(u64, u64) -> bool) -> Option<Ufeat> {
+        let p = self.original_p();
+        let bits = p.bits();
+        if bits <= SCHOOF_MIN_BITS {
+            return Some(Ufeat::from(self.count_points_legendre(p.as_words()[0])));
+        }
+        self.with_limbs(Order { curve: self, abort })
+    }
+
+    /// Runs `task` with the limbs count fitting `p`, as polynomial arithmetic is where all the time goes
+    pub(crate) fn with_limbs<T: WithLimbs>(&self, task: T) -> T::Output {
+        let bits = self.original_p().bits();
+        if bits <= 64 {
+            task.run::<1>()
+        } else if bits <= 128 {
+            task.run::<2>()
+        } else if bits <= 192 {
+            task.run::<3>()
+        } else if bits <= 256 {
+            task.run::<4>()
+        } else {
+            task.run::<{ Ufeat::LIMBS }>()
+        }
+    }
+
+    fn order_with<const L: usize>(&self, abort: &dyn Fn(u64, u64) -> bool) -> Option<Ufeat> {
+        let p_plus_one = self.original_p().wrapping_add(&Ufeat::ONE);
+        // 4 * sqrt(p) is the width of Hasse interval
+        let width = self
+            .original_p()
+            .sqrt_vartime()
+            .wrapping_add(&Ufeat::ONE)
+            .shl_vartime(2);
+        let mut schoof = Schoof::<L>::new(self);
+
+        schoof.advance(&width.shr_vartime(MATCH_BITS), abort)?;
+        let (trace, modulus) = schoof.crt();
+        // the largest `p + 1 - t` in Hasse interval with `t = trace (mod modulus)`
+        let high = p_plus_one.wrapping_add(&width.shr_vartime(1));
+        let residue = p_plus_one
+            .wrapping_add(&modulus)
+            .wrapping_sub(&trace)
+            .wrapping_rem(&modulus);
+        let high = high.wrapping_sub(&high.wrapping_sub(&residue).wrapping_rem(&modulus));
+        let low = p_plus_one.wrapping_sub(&width.shr_vartime(1));
+        let count = high.wrapping_sub(&low).wrapping_div(&modulus).as_words()[0] + 1;
+        if let Some(order) = schoof.coefficients.match_order(self, high, modulus, count) {
+            return Some(order);
+        }
+
+        // every point failed to tell the candidates apart, like in the groups of the form Z/n x Z/n; plain Schoof's algorithm
+        // leaves a single candidate
+        schoof.advance(&width, abort)?;
+        let (trace, modulus) = schoof.crt();
+        // the trace is the representative closest to zero
+        Some(if trace > modulus.shr_vartime(1) {
+            p_plus_one.wrapping_add(&modulus.wrapping_sub(&trace))
+        } else {
+            p_plus_one.wrapping_sub(&trace)
+        })
+    }
+}
+
+/// The inverse of `a` modulo `m` coprime to it
+fn inverse_mod(a: u64, m: u64) -> u64 {
+    let (mut r0, mut r1) = (m as i128, a as i128);
+    let (mut s0, mut s1) = (0i128, 1i128);
+    while r1 != 0 {
+        let q = r0 / r1;
+        (r0, r1) = (r1, r0 - q * r1);
+        (s0, s1) = (s1, s0 - q * s1);
+    }
+    s0.rem_euclid(m as i128) as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use super::*;
+    use crate::curves;
+
+    #[test]
+    fn agrees_with_naive_counting() {
+        for (p, coefficients) in [
+            (100003u64, [0, 2, 3]),
+            (1000003, [0, -3, 5]),
+            (1048573, [6, 1, 0]),
+            ((1 << 40) - 87, [0, 2, 3]),
+        ] {
+            let curve = Rc::new(EllipticCurve::new(
+                format!("teaching curve over {p}"),
+                Ufeat::from(3u8),
+                Ufeat::from(p),
+                coefficients,
+            ));
+            assert_eq!(curve.compute_order(), curve.count_points().unwrap());
+        }
+    }
+
+    #[test]
+    fn one_division_polynomial_matches_all_of_them() {
+        let curve = EllipticCurve::new(
+            "teaching curve over 1009".to_string(),
+            Ufeat::from(3u8),
+            Ufeat::from(1009u16),
+            [1, 2, 3],
+        );
+        let coefficients = Coefficients::<1>::new(&curve);
+        for (n, f) in coefficients.division_polynomials(40).iter().enumerate() {
+            assert_eq!(coefficients.division_polynomial(n), *f, "f_{n}");
+        }
+    }
+
+    fn check_named(name: &str) {
+        let curve = curves::by_name(name).unwrap();
+        assert_eq!(
+            curve.compute_order(),
+            curve.order().wrapping_mul(&curve.cofactor().unwrap())
+        );
+    }
+
+    #[test]
+    #[ignore = "takes minutes unoptimized"]
+    fn secp160k1() {
+        check_named("secp160k1");
+    }
+    #[test]
+    #[ignore = "takes minutes unoptimized"]
+    fn secp192k1() {
+        check_named("secp192k1");
+    }
+    #[test]
+    #[ignore = "takes a couple of minutes"]
+    fn secp224k1() {
+        check_named("secp224k1");
+    }
+    #[test]
+    #[ignore = "takes a couple of minutes"]
+    fn m221() {
+        check_named("M-221");
+    }
+    #[test]
+    #[ignore = "takes minutes"]
+    fn bn254() {
+        check_named("BN254");
+    }
+    #[test]
+    #[ignore = "takes minutes"]
+    fn curve25519() {
+        check_named("Curve25519");
+    }
+    #[test]
+    #[ignore = "takes minutes"]
+    fn secp256k1() {
+        check_named("Secp256k1");
+    }
+    #[test]
+    #[ignore = "takes hours"]
+    fn bls12_381() {
+        check_named("BLS12-381");
+    }
+    #[test]
+    #[ignore = "takes hours"]
+    fn curve448() {
+        check_named("Curve448");
+    }
+    #[test]
+    #[ignore = "takes hours"]
+    fn m511() {
+        check_named("M-511");
+    }
+}
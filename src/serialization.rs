@@ -0,0 +1,231 @@
+//! `serde` support: a curve is its registry name or its parameters, a point is its SEC1 encoding in hex
+//!
+//! Everything deserialized goes through the same validation as the constructors. A point can't tell on its own which
+//! curve it belongs to, so it's deserialized with `OnCurve` seed holding the shared `Rc` of the curve; `CurvePoints`
+//! keeps a curve together with its points in one message and binds them on the way back.
+use std::{error::Error, rc::Rc};
+
+use serde::{
+    de::{self, DeserializeSeed},
+    ser::SerializeStruct,
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+
+use super::{
+    curves,
+    ec::Coefficient,
+    encoding::{bytes_to_hex, hex_to_bytes, parse_hex, to_hex},
+    EllipticCurve, EncodingError, Point,
+};
+
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum CurveRepr {
+    Named(String),
+    Parameters {
+        name: String,
+        order: String,
+        p: String,
+        coefficients: [CoefficientRepr; 3],
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        generator: Option<String>,
+        /// Set for the curves of `EllipticCurve::try_new_anomalous`, which are refused otherwise
+        #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+        anomalous: bool,
+    },
+}
+
+/// Small coefficients stay numbers, as they're given to `EllipticCurve::new`, and residues are hex
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum CoefficientRepr {
+    Small(isize),
+    Residue(String),
+}
+
+impl Serialize for EllipticCurve {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        // the registry name is enough only when nothing differs, the base point included
+        let registered = curves::by_name(&self.name).is_some_and(|registered| {
+            registered == *self
+                && registered.order() == self.order()
+                && registered.generator_coordinates() == self.generator_coordinates()
+        });
+        if registered {
+            return CurveRepr::Named(self.name.clone()).serialize(serializer);
+        }
+        let (name, order, coefficients, p) = self.get();
+        let coefficients = match coefficients {
+            [a2, a4, a6] => [*a2, *a4, *a6].map(CoefficientRepr::Small),
+            _ => [self.a2(), self.a4(), self.a6()]
+                .map(|coefficient| CoefficientRepr::Residue(to_hex(&coefficient.retrieve()))),
+        };
+        CurveRepr::Parameters {
+            name: name.to_string(),
+            order: to_hex(order),
+            p: to_hex(p),
+            coefficients,
+            generator: self
+                .generator_coordinates()
+                .map(|generator| bytes_to_hex(&self.encode_sec1(Some(generator), false))),
+            anomalous: self.is_anomalous(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for EllipticCurve {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        curve_from_repr(CurveRepr::deserialize(deserializer)?).map_err(custom)
+    }
+}
+
+fn curve_from_repr(repr: CurveRepr) -> Result<EllipticCurve, EncodingError> {
+    let (name, order, p, coefficients, generator, anomalous) = match repr {
+        CurveRepr::Named(name) => {
+            return curves::by_name(&name).ok_or(EncodingError::UnknownCurve { name })
+        }
+        CurveRepr::Parameters {
+            name,
+            order,
+            p,
+            coefficients,
+            generator,
+            anomalous,
+        } => (name, order, p, coefficients, generator, anomalous),
+    };
+    let mut residues = Vec::with_capacity(3);
+    for coefficient in coefficients {
+        residues.push(match coefficient {
+            CoefficientRepr::Small(coefficient) => Coefficient::Small(coefficient),
+            CoefficientRepr::Residue(text) => Coefficient::Big(parse_hex(&text)?),
+        });
+    }
+    let curve = EllipticCurve::checked(
+        name,
+        parse_hex(&order)?,
+        parse_hex(&p)?,
+        [residues[0], residues[1], residues[2]],
+        anomalous,
+    )?;
+    let generator =
+        match generator {
+            Some(text) => curve.decode_sec1(&hex_to_bytes(&text)?)?.ok_or_else(|| {
+                EncodingError::Malformed {
+                    reason: "the generator can't be the point at infinity".to_string(),
+                }
+            })?,
+            None => return Ok(curve),
+        };
+    Ok(curve.with_generator(generator.0, generator.1)?)
+}
+
+/// Serde keeps only the message, so the reasons down the chain of sources go into it
+fn custom<E: de::Error>(error: EncodingError) -> E {
+    let mut message = error.to_string();
+    let mut source = error.source();
+    while let Some(reason) = source {
+        message = format!("{message}: {reason}");
+        source = reason.source();
+    }
+    E::custom(message)
+}
+
+/// Uncompressed SEC1 in hex
+impl Serialize for Point {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&bytes_to_hex(&self.to_sec1(false)))
+    }
+}
+
+/// Deserializes a point onto the curve it holds, accepting either form of SEC1
+#[derive(Clone)]
+pub struct OnCurve(pub Rc<EllipticCurve>);
+
+impl<'de> DeserializeSeed<'de> for OnCurve {
+    type Value = Point;
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Point, D::Error> {
+        let text = String::deserialize(deserializer)?;
+        hex_to_bytes(&text)
+            .and_then(|bytes| Point::from_sec1(self.0, &bytes))
+            .map_err(custom)
+    }
+}
+
+/// A curve with points on it, all sharing one `Rc` of the curve after deserialization
+#[derive(Clone)]
+pub struct CurvePoints {
+    pub curve: Rc<EllipticCurve>,
+    pub points: Vec<Point>,
+}
+
+impl Serialize for CurvePoints {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("CurvePoints", 2)?;
+        state.serialize_field("curve", self.curve.as_ref())?;
+        state.serialize_field("points", &self.points)?;
+        state.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for CurvePoints {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        // the points wait as text for the curve, so that the fields may come in any order
+        #[derive(Deserialize)]
+        struct Raw {
+            curve: EllipticCurve,
+            points: Vec<String>,
+        }
+        let raw = Raw::deserialize(deserializer)?;
+        let curve = Rc::new(raw.curve);
+        let points = raw
+            .points
+            .iter()
+            .map(|text| Point::from_sec1(Rc::clone(&curve), &hex_to_bytes(text)?))
+            .collect::<Result<_, EncodingError>>()
+            .map_err(custom)?;
+        Ok(CurvePoints { curve, points })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Ufeat;
+
+    fn anomalous() -> EllipticCurve {
+        EllipticCurve::try_new_anomalous(
+            "anomalous".to_string(),
+            Ufeat::from(10007u16),
+            Ufeat::from(10007u16),
+            [0, 22, 9],
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn anomalous_curves_round_trip() {
+        let curve = anomalous();
+        let json = serde_json::to_string(&curve).unwrap();
+        assert!(json.contains(r#""anomalous":true"#));
+        let read: EllipticCurve = serde_json::from_str(&json).unwrap();
+        assert!(read == curve && read.order() == curve.order());
+
+        // without the flag it's refused as any anomalous curve is
+        let unflagged = json.replace(r#","anomalous":true"#, "");
+        assert!(serde_json::from_str::<EllipticCurve>(&unflagged).is_err());
+    }
+
+    #[test]
+    fn other_curves_have_no_flag() {
+        let curve = EllipticCurve::new(
+            "E".to_string(),
+            Ufeat::from(5003u16),
+            Ufeat::from(10007u16),
+            [0, 2, 3],
+        );
+        let json = serde_json::to_string(&curve).unwrap();
+        assert!(!json.contains("anomalous"));
+        assert!(serde_json::from_str::<EllipticCurve>(&json).unwrap() == curve);
+    }
+}
@@ -0,0 +1,333 @@
+//! Division polynomials and the torsion subgroups $E[n]$ they cut out
+//!
+//! A point `P` other than infinity has $n.P = \infty$ exactly when $\psi_n(P) = 0$, and $\psi_n$ depends on `x` alone
+//! up to a factor `y` for even `n`, which vanishes at the points of order 2. So the `x` of the `n`-torsion over a field
+//! are the roots there of a polynomial of degree about $n^2 / 2$, found with Cantor-Zassenhaus. All of $E[n]$, $n^2$
+//! points, shows up over an extension of degree at most the order of `p` modulo `n` times `n`; `torsion_degree` finds the
+//! smallest one from the factorization of the division polynomial over $F_p$.
+use std::{fmt, rc::Rc};
+
+use crypto_bigint::{modular::runtime_mod::DynResidue, Uint};
+
+use super::{
+    counting::lcm,
+    encoding::to_hex,
+    extension::{Field, Fp},
+    poly::{Poly, PolyRing},
+    schoof::{Coefficients, WithLimbs},
+    CurveOver, EllipticCurve, FieldError, Point, PointOver, Ufeat,
+};
+
+/// The division polynomial $\psi_n$ of a curve, kept as the polynomial $f_n$ in `x` with $\psi_n = f_n$ for odd `n` and
+/// $\psi_n = y.f_n$ for even `n`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DivisionPolynomial {
+    n: usize,
+    // from the constant term up, never ending with a zero
+    coefficients: Vec<Fp>,
+}
+
+impl DivisionPolynomial {
+    pub fn n(&self) -> usize {
+        self.n
+    }
+    /// The coefficients of $f_n$ from the constant term up; empty for $\psi_0 = 0$
+    pub fn coefficients(&self) -> &[Fp] {
+        &self.coefficients
+    }
+    /// The degree of $f_n$ in `x`: $(n^2 - 1) / 2$ for odd `n`, $(n^2 - 4) / 2$ for even `n`, unless `n` is a multiple of
+    /// `p`
+    pub fn degree(&self) -> Option<usize> {
+        self.coefficients.len().checked_sub(1)
+    }
+    /// Whether $\psi_n$ has the factor `y`, for even `n`
+    pub fn has_y(&self) -> bool {
+        self.n.is_multiple_of(2)
+    }
+    /// $\psi_n(x, y)$ in the field of the coordinates
+    pub fn evaluate<F: Field>(&self, x: &F, y: &F) -> F {
+        let value = self
+            .coefficients
+            .iter()
+            .rev()
+            .fold(x.zero_like(), |value, c| value * *x + x.embed(c));
+        if self.has_y() {
+            value * *y
+        } else {
+            value
+        }
+    }
+}
+
+impl fmt::Display for DivisionPolynomial {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let terms: Vec<_> = self
+            .coefficients
+            .iter()
+            .enumerate()
+            .rev()
+            .filter(|(_, c)| c.retrieve() != Ufeat::ZERO)
+            .map(|(i, c)| match i {
+                0 => to_hex(&c.retrieve()),
+                1 => format!("{}.x", to_hex(&c.retrieve())),
+                _ => format!("{}.x^{i}", to_hex(&c.retrieve())),
+            })
+            .collect();
+        let polynomial = if terms.is_empty() {
+            "0".to_string()
+        } else {
+            terms.join(" + ")
+        };
+        if self.has_y() {
+            write!(f, "psi_{} = y.({polynomial})", self.n)
+        } else {
+            write!(f, "psi_{} = {polynomial}", self.n)
+        }
+    }
+}
+
+impl EllipticCurve {
+    /// $\psi_n$ with the coefficients in $F_p$
+    pub fn division_polynomial(&self, n: usize) -> DivisionPolynomial {
+        DivisionPolynomial {
+            n,
+            coefficients: self.with_limbs(DivisionPolynomialOf { curve: self, n }),
+        }
+    }
+
+    /// The points of $E(F_p)[n]$, infinity first, the others in the order of `x` with `y` before `-y`
+    ///
+    /// Panics for `n = 0`, which would be the whole group.
+    pub fn torsion_points(self: &Rc<Self>, n: usize) -> Vec<Point> {
+        assert!(n > 0, "every point is of order dividing 0");
+        let one = DynResidue::one(*self.p());
+        let over = Rc::new(self.over(&one).expect("the prime field of the curve"));
+        let xs = self.with_limbs(TorsionXs { curve: self, n });
+        let mut points: Vec<_> = lift(&over, xs)
+            .into_iter()
+            .map(|point| match point.x().zip(point.y()) {
+                None => Point::new(Rc::clone(self), None, None),
+                Some((x, y)) => Point::new(Rc::clone(self), Some(x.retrieve()), Some(y.retrieve())),
+            })
+            .collect::<Result<_, _>>()
+            .expect("the roots give points of the curve");
+        points[1..].sort_by_key(|point| point.x().map(|x| x.retrieve()));
+        points
+    }
+
+    /// The points of $E(F)[n]$ for the field `F` of `one`, which must have the characteristic `p`; infinity comes first
+    ///
+    /// That's all of $E[n]$ when the degree of `F` is a multiple of `torsion_degree(n)`. Panics for `n = 0`.
+    pub fn torsion_points_over<F: Field>(
+        &self,
+        one: &F,
+        n: usize,
+    ) -> Result<Vec<PointOver<F>>, FieldError> {
+        assert!(n > 0, "every point is of order dividing 0");
+        let over = Rc::new(self.over(one)?);
+        let embed = |coefficients: &[Fp]| {
+            Poly::new(coefficients.iter().map(|c| one.embed(c)).collect(), one)
+        };
+        let mut xs = embed(self.division_polynomial(n).coefficients()).roots();
+        if n.is_multiple_of(2) {
+            let rhs = [
+                *self.a6(),
+                *self.a4(),
+                *self.a2(),
+                DynResidue::one(*self.p()),
+            ];
+            xs.extend(embed(&rhs).roots());
+        }
+        Ok(lift(&over, xs))
+    }
+
+    /// The smallest degree `k` with all of $E[n]$ in $E(F_{p^k})$
+    ///
+    /// The `x` of a point lies in the field of the degree of its irreducible factor over $F_p$, and `y` in the same
+    /// field when the right hand side is a square there, in the one of twice the degree otherwise: squares are told
+    /// apart by their norms to $F_p$. Panics for `n = 0`.
+    pub fn torsion_degree(&self, n: usize) -> usize {
+        assert!(n > 0, "every point is of order dividing 0");
+        self.with_limbs(TorsionDegree { curve: self, n })
+    }
+}
+
+/// The points with the given `x`, and infinity first
+fn lift<F: Field>(over: &Rc<CurveOver<F>>, xs: Vec<F>) -> Vec<PointOver<F>> {
+    let mut points = vec![over.infinity()];
+    for x in xs {
+        if points.iter().any(|point| point.x() == Some(&x)) {
+            continue;
+        }
+        if let Some(y) = over.rhs(&x).sqrt() {
+            points.push(over.point(x, y).expect("the root is on the curve"));
+            if !y.is_zero() {
+                points.push(over.point(x, -y).expect("the root is on the curve"));
+            }
+        }
+    }
+    points
+}
+
+/// Back from the limbs count of `with_limbs`
+fn to_fp<const L: usize>(curve: &EllipticCurve, c: &DynResidue<L>) -> Fp {
+    DynResidue::new(&c.retrieve().resize(), *curve.p())
+}
+
+struct DivisionPolynomialOf<'c> {
+    curve: &'c EllipticCurve,
+    n: usize,
+}
+
+impl WithLimbs for DivisionPolynomialOf<'_> {
+    type Output = Vec<Fp>;
+    fn run<const L: usize>(self) -> Vec<Fp> {
+        Coefficients::<L>::new(self.curve)
+            .division_polynomial(self.n)
+            .coefficients()
+            .iter()
+            .map(|c| to_fp(self.curve, c))
+            .collect()
+    }
+}
+
+/// The `x` of $E(F_p)[n]$: the roots of $f_n$, and of the right hand side for even `n`
+struct TorsionXs<'c> {
+    curve: &'c EllipticCurve,
+    n: usize,
+}
+
+impl WithLimbs for TorsionXs<'_> {
+    type Output = Vec<Fp>;
+    fn run<const L: usize>(self) -> Vec<Fp> {
+        let coefficients = Coefficients::<L>::new(self.curve);
+        let mut xs = coefficients.division_polynomial(self.n).roots();
+        if self.n.is_multiple_of(2) {
+            xs.extend(coefficients.rhs().roots());
+        }
+        xs.iter().map(|x| to_fp(self.curve, x)).collect()
+    }
+}
+
+struct TorsionDegree<'c> {
+    curve: &'c EllipticCurve,
+    n: usize,
+}
+
+impl WithLimbs for TorsionDegree<'_> {
+    type Output = usize;
+    fn run<const L: usize>(self) -> usize {
+        let coefficients = Coefficients::<L>::new(self.curve);
+        let p = self.curve.original_p().resize::<L>();
+        let rhs = coefficients.rhs();
+        let f = coefficients.division_polynomial(self.n);
+        let f = if self.n.is_multiple_of(2) {
+            &f * &rhs
+        } else {
+            f
+        };
+        if f.degree().unwrap_or(0) == 0 {
+            return 1;
+        }
+
+        // distinct degree factorization: the factors of degree `d` are those of x^(p^d) - x, once the smaller ones are
+        // gone from `remaining`
+        let unit = DynResidue::one(rhs.params());
+        let (one, x) = (Poly::constant(unit), Poly::x(&unit));
+        let ring = PolyRing::new(f.clone());
+        let (mut remaining, mut power) = (f.monic(), x.clone());
+        let (mut degree, mut d) = (1, 0);
+        while let Some(left) = remaining.degree().filter(|left| *left > 0) {
+            d += 1;
+            // what's left has no factor of degree below `d`, so it's irreducible if it can't hold two
+            let factors = if left < 2 * d {
+                d = left;
+                remaining.clone()
+            } else {
+                power = ring.pow(&power, &p);
+                remaining.gcd(&(&power - &x))
+            };
+            if factors.degree() == Some(0) {
+                continue;
+            }
+            let mut common = factors.clone();
+            while common.degree() != Some(0) {
+                remaining = remaining.div_rem(&common).0;
+                common = remaining.gcd(&common);
+            }
+            let ring = PolyRing::new(factors);
+            let value = ring.reduce(&rhs);
+            let (mut conjugate, mut norm) = (value.clone(), value.clone());
+            for _ in 1..d {
+                conjugate = ring.pow(&conjugate, &p);
+                norm = ring.mul(&norm, &conjugate);
+            }
+            let half = p.wrapping_sub(&Uint::ONE).shr_vartime(1);
+            // zero at the roots where `y` is zero or the norm is a square
+            let square = ring.mul(&value, &(&ring.pow(&norm, &half) - &one));
+            let field = if square.is_zero() { d } else { 2 * d };
+            degree = lcm(degree, field as u64);
+        }
+        degree as usize
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crypto_bigint::modular::runtime_mod::DynResidueParams;
+
+    use super::*;
+    use crate::extension::Fp2;
+
+    /// $y^2 = x^3 + x$ over $p = 3 \pmod 4$, supersingular with a Frobenius squaring to $-p$
+    fn supersingular() -> Rc<EllipticCurve> {
+        Rc::new(EllipticCurve::new(
+            "y^2 = x^3 + x".to_string(),
+            Ufeat::from(139u8),
+            Ufeat::from(10007u16),
+            [0, 1, 0],
+        ))
+    }
+
+    #[test]
+    fn torsion_degree_follows_the_frobenius() {
+        let curve = supersingular();
+        // E[n] is rational over F_{p^2} for n dividing p + 1, otherwise once (-p)^(k/2) = 1 modulo n
+        for (n, degree) in [
+            (1, 1),
+            (2, 2),
+            (3, 2),
+            (4, 2),
+            (5, 8),
+            (6, 2),
+            (7, 12),
+            (9, 2),
+            (10, 8),
+        ] {
+            assert_eq!(curve.torsion_degree(n), degree, "E[{n}]");
+        }
+    }
+
+    #[test]
+    fn all_of_the_torsion_shows_up_at_its_degree() {
+        let curve = supersingular();
+        let one = Fp2::one(-DynResidue::one(*curve.p())).unwrap();
+        for n in 1..=6 {
+            let points = curve.torsion_points_over(&one, n).unwrap();
+            assert_eq!(
+                points.len() == n * n,
+                curve.torsion_degree(n) <= 2,
+                "E[{n}]"
+            );
+            assert!(points
+                .iter()
+                .all(|point| (point * Ufeat::from(n as u64)).is_infinity()));
+        }
+        // the one of F_p, where only a cyclic part of E[n] lies
+        let prime = DynResidue::one(DynResidueParams::new(&curve.original_p()));
+        assert_eq!(
+            curve.torsion_points_over(&prime, 3).unwrap().len(),
+            curve.torsion_points(3).len()
+        );
+    }
+}